@@ -0,0 +1,260 @@
+//! Collection advisor.
+//!
+//! Encodes the Q1–Q3 reflection notes from `main.rs` as scoring rules: each
+//! std collection starts from a base score and gains or loses points for
+//! every workload trait, and each adjustment records the reason behind it.
+
+use std::fmt;
+
+/// The std collections covered by the interactive menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Collection {
+    Vec,
+    VecDeque,
+    LinkedList,
+    HashMap,
+    BTreeMap,
+    HashSet,
+    BTreeSet,
+    BinaryHeap,
+}
+
+impl Collection {
+    /// Every collection, in menu order.
+    pub const ALL: [Collection; 8] = [
+        Collection::Vec,
+        Collection::VecDeque,
+        Collection::LinkedList,
+        Collection::HashMap,
+        Collection::BTreeMap,
+        Collection::HashSet,
+        Collection::BTreeSet,
+        Collection::BinaryHeap,
+    ];
+}
+
+impl fmt::Display for Collection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Collection::Vec => "Vec",
+            Collection::VecDeque => "VecDeque",
+            Collection::LinkedList => "LinkedList",
+            Collection::HashMap => "HashMap",
+            Collection::BTreeMap => "BTreeMap",
+            Collection::HashSet => "HashSet",
+            Collection::BTreeSet => "BTreeSet",
+            Collection::BinaryHeap => "BinaryHeap",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Description of how a collection is going to be used.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Workload {
+    /// Relative number of inserts.
+    pub inserts: u32,
+    /// Relative number of lookups (by key, value or index).
+    pub lookups: u32,
+    /// Elements are key/value pairs rather than single values.
+    pub key_value: bool,
+    /// Iteration must follow sorted order.
+    pub ordered: bool,
+    /// Range queries such as "all keys between a and b".
+    pub range_queries: bool,
+    /// Push/pop at the front.
+    pub front_access: bool,
+    /// Push/pop at the back.
+    pub back_access: bool,
+    /// The same value may be stored more than once.
+    pub duplicates: bool,
+    /// Elements are consumed largest-first.
+    pub priority: bool,
+}
+
+impl Workload {
+    /// True when lookups outnumber inserts at least two to one.
+    pub fn lookup_heavy(&self) -> bool {
+        self.lookups >= self.inserts.saturating_mul(2) && self.lookups > 0
+    }
+
+    /// True when inserts outnumber lookups at least two to one.
+    pub fn insert_heavy(&self) -> bool {
+        self.inserts >= self.lookups.saturating_mul(2) && self.inserts > 0
+    }
+}
+
+/// A scored suggestion together with the reasons behind the score.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recommendation {
+    pub collection: Collection,
+    pub score: i32,
+    pub reasons: Vec<String>,
+}
+
+impl Recommendation {
+    fn new(collection: Collection) -> Self {
+        Recommendation {
+            collection,
+            score: 0,
+            reasons: Vec::new(),
+        }
+    }
+
+    fn note(&mut self, points: i32, reason: &str) {
+        self.score += points;
+        let sign = if points >= 0 { "+" } else { "" };
+        self.reasons.push(format!("{}{} {}", sign, points, reason));
+    }
+}
+
+/// Ranks every collection for `workload`, best match first.
+///
+/// Ties keep menu order, so the result is deterministic.
+pub fn recommend(workload: &Workload) -> Vec<Recommendation> {
+    let mut ranked: Vec<Recommendation> = Collection::ALL
+        .iter()
+        .map(|&collection| score(collection, workload))
+        .collect();
+    ranked.sort_by_key(|r| std::cmp::Reverse(r.score));
+    ranked
+}
+
+fn score(collection: Collection, w: &Workload) -> Recommendation {
+    let mut r = Recommendation::new(collection);
+    let sequence = matches!(
+        collection,
+        Collection::Vec | Collection::VecDeque | Collection::LinkedList
+    );
+    let hashed = matches!(collection, Collection::HashMap | Collection::HashSet);
+    let sorted = matches!(collection, Collection::BTreeMap | Collection::BTreeSet);
+    let map = matches!(collection, Collection::HashMap | Collection::BTreeMap);
+    let set = matches!(collection, Collection::HashSet | Collection::BTreeSet);
+
+    // Q1: sequences
+    match collection {
+        Collection::Vec => {
+            r.note(2, "general purpose default with O(1) random access");
+            if w.back_access {
+                r.note(2, "fast push/pop at the end");
+            }
+            if w.front_access {
+                r.note(-3, "push/pop at the front shifts every element (O(n))");
+            }
+        }
+        Collection::VecDeque => {
+            if w.front_access {
+                r.note(3, "O(1) push/pop at the front");
+            }
+            if w.back_access {
+                r.note(1, "O(1) push/pop at the back");
+            }
+        }
+        Collection::LinkedList => {
+            r.note(-1, "poor cache locality; rarely the right choice in Rust");
+            if w.front_access {
+                r.note(2, "O(1) push/pop at the front");
+            }
+            if w.back_access {
+                r.note(1, "O(1) push/pop at the back");
+            }
+        }
+        _ => {}
+    }
+    if sequence {
+        if w.duplicates {
+            r.note(1, "keeps duplicate values");
+        }
+        if w.key_value {
+            r.note(-2, "no key lookup; pairs must be searched linearly");
+        } else if w.lookup_heavy() {
+            r.note(-2, "value lookup is a linear scan");
+        }
+        if w.ordered || w.range_queries {
+            r.note(-1, "sorted order has to be maintained by hand");
+        }
+        if w.priority {
+            r.note(-2, "finding the largest element is O(n)");
+        }
+    }
+
+    // Q2: maps and sets
+    if map {
+        if w.key_value {
+            r.note(3, "stores key/value pairs");
+        } else {
+            r.note(-3, "values have no natural key");
+        }
+    }
+    if set {
+        if w.key_value {
+            r.note(-2, "stores values only, not key/value pairs");
+        } else {
+            r.note(1, "membership testing");
+        }
+    }
+    if map || set {
+        if w.duplicates {
+            r.note(-3, "keys are unique, duplicates are merged");
+        }
+        if w.front_access || w.back_access {
+            r.note(-1, "no positional push/pop");
+        }
+        if w.priority {
+            r.note(-1, "largest element reachable but not a priority queue");
+        }
+    }
+    if hashed {
+        if w.lookup_heavy() {
+            r.note(3, "O(1) average lookup");
+        } else if w.lookups > 0 {
+            r.note(1, "O(1) average lookup");
+        }
+        if w.insert_heavy() {
+            r.note(1, "O(1) average insert");
+        }
+        if w.ordered {
+            r.note(-3, "iteration order is unspecified");
+        }
+        if w.range_queries {
+            r.note(-3, "no range queries");
+        }
+    }
+    if sorted {
+        if w.lookups > 0 {
+            r.note(1, "O(log n) lookup");
+        }
+        if w.ordered {
+            r.note(3, "iterates in sorted order");
+        }
+        if w.range_queries {
+            r.note(3, "range() over sorted keys");
+        }
+    }
+
+    // Q3: binary heap
+    if collection == Collection::BinaryHeap {
+        if w.priority {
+            r.note(
+                5,
+                "max-heap: O(1) peek and O(log n) pop of the largest element",
+            );
+        } else {
+            r.note(-2, "only useful when elements are consumed by priority");
+        }
+        if w.duplicates {
+            r.note(1, "keeps duplicate values");
+        }
+        if w.key_value {
+            r.note(-1, "pairs are ordered by key first, no key lookup");
+        }
+        if w.lookups > 0 {
+            r.note(-2, "lookup of anything but the maximum is O(n)");
+        }
+        if w.ordered || w.range_queries {
+            r.note(-2, "iteration order is heap order, not sorted");
+        }
+    }
+
+    r
+}
@@ -0,0 +1,419 @@
+//! Load-balancing strategies and a simulator that replays a request trace.
+//!
+//! A [`Balancer`] picks a server for each request and is told when a
+//! request finishes. [`LeastConnections`] keeps servers in an indexed
+//! min-heap keyed by open connections: a position table lets it raise or
+//! lower one server's key in O(log n) instead of rebuilding the heap.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::filter::hash;
+use crate::import::split_csv_line;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Server {
+    pub name: String,
+    /// Relative capacity; only the weighted strategy looks at it.
+    pub weight: u32,
+}
+
+impl Server {
+    pub fn new(name: impl Into<String>, weight: u32) -> Self {
+        Server {
+            name: name.into(),
+            weight,
+        }
+    }
+}
+
+/// Parses `a:3,b,c:2` into servers; a missing weight means 1.
+pub fn parse_servers(spec: &str) -> Result<Vec<Server>, String> {
+    let mut servers: Vec<Server> = Vec::new();
+    for part in spec.split(',').map(str::trim) {
+        let (name, weight) = match part.split_once(':') {
+            Some((name, weight)) => (name, weight.parse::<u32>().ok().filter(|&w| w > 0)),
+            None => (part, Some(1)),
+        };
+        let weight = weight.ok_or_else(|| format!("Invalid weight in '{}'", part))?;
+        if name.is_empty() || servers.iter().any(|s| s.name == name) {
+            return Err(format!(
+                "Server names must be unique and non-empty: '{}'",
+                part
+            ));
+        }
+        servers.push(Server::new(name, weight));
+    }
+    Ok(servers)
+}
+
+pub trait Balancer {
+    /// Chooses the server for a request; `key` identifies the client.
+    fn pick(&mut self, key: &str) -> usize;
+
+    /// A request on `server` has finished.
+    fn release(&mut self, server: usize) {
+        let _ = server;
+    }
+}
+
+pub struct RoundRobin {
+    servers: usize,
+    next: usize,
+}
+
+impl RoundRobin {
+    pub fn new(servers: usize) -> Self {
+        assert!(servers > 0, "a balancer needs at least one server");
+        RoundRobin { servers, next: 0 }
+    }
+}
+
+impl Balancer for RoundRobin {
+    fn pick(&mut self, _key: &str) -> usize {
+        let server = self.next;
+        self.next = (self.next + 1) % self.servers;
+        server
+    }
+}
+
+/// Always picks the server with the fewest open connections; ties go to
+/// the lowest index.
+pub struct LeastConnections {
+    connections: Vec<u64>,
+    /// Server ids arranged as a binary min-heap on `(connections, id)`.
+    heap: Vec<usize>,
+    /// Where each server currently sits in `heap`.
+    position: Vec<usize>,
+}
+
+impl LeastConnections {
+    pub fn new(servers: usize) -> Self {
+        assert!(servers > 0, "a balancer needs at least one server");
+        LeastConnections {
+            connections: vec![0; servers],
+            heap: (0..servers).collect(),
+            position: (0..servers).collect(),
+        }
+    }
+
+    pub fn connections(&self, server: usize) -> u64 {
+        self.connections[server]
+    }
+
+    fn key(&self, slot: usize) -> (u64, usize) {
+        let server = self.heap[slot];
+        (self.connections[server], server)
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.position[self.heap[a]] = a;
+        self.position[self.heap[b]] = b;
+    }
+
+    fn sift_up(&mut self, mut slot: usize) {
+        while slot > 0 {
+            let parent = (slot - 1) / 2;
+            if self.key(slot) >= self.key(parent) {
+                break;
+            }
+            self.swap(slot, parent);
+            slot = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut slot: usize) {
+        loop {
+            let mut smallest = slot;
+            for child in [2 * slot + 1, 2 * slot + 2] {
+                if child < self.heap.len() && self.key(child) < self.key(smallest) {
+                    smallest = child;
+                }
+            }
+            if smallest == slot {
+                break;
+            }
+            self.swap(slot, smallest);
+            slot = smallest;
+        }
+    }
+}
+
+impl Balancer for LeastConnections {
+    fn pick(&mut self, _key: &str) -> usize {
+        let server = self.heap[0];
+        self.connections[server] += 1;
+        self.sift_down(0);
+        server
+    }
+
+    fn release(&mut self, server: usize) {
+        if self.connections[server] > 0 {
+            self.connections[server] -= 1;
+            self.sift_up(self.position[server]);
+        }
+    }
+}
+
+/// Smooth weighted round-robin: each server gains its weight every pick and
+/// the leader is chosen and set back by the total, which interleaves picks
+/// instead of sending runs of requests to the heaviest server.
+pub struct Weighted {
+    weights: Vec<i64>,
+    current: Vec<i64>,
+    total: i64,
+}
+
+impl Weighted {
+    pub fn new(servers: &[Server]) -> Self {
+        assert!(!servers.is_empty(), "a balancer needs at least one server");
+        let weights: Vec<i64> = servers.iter().map(|s| i64::from(s.weight)).collect();
+        Weighted {
+            total: weights.iter().sum(),
+            current: vec![0; weights.len()],
+            weights,
+        }
+    }
+}
+
+impl Balancer for Weighted {
+    fn pick(&mut self, _key: &str) -> usize {
+        for (current, weight) in self.current.iter_mut().zip(&self.weights) {
+            *current += weight;
+        }
+        let (server, _) = self
+            .current
+            .iter()
+            .enumerate()
+            .max_by_key(|&(i, &c)| (c, Reverse(i)))
+            .expect("at least one server");
+        self.current[server] -= self.total;
+        server
+    }
+}
+
+/// Maps client keys onto a hash ring with `replicas` points per server, so
+/// a client keeps its server and adding or removing a server only moves
+/// the keys next to its points.
+pub struct ConsistentHash {
+    ring: BTreeMap<u64, usize>,
+    replicas: usize,
+}
+
+impl ConsistentHash {
+    pub const DEFAULT_REPLICAS: usize = 100;
+
+    /// Panics if there are no servers or `replicas` is zero, either of
+    /// which would leave the ring empty.
+    pub fn new(servers: &[Server], replicas: usize) -> Self {
+        assert!(!servers.is_empty(), "a balancer needs at least one server");
+        assert!(replicas > 0, "each server needs at least one ring point");
+        let mut ring = ConsistentHash {
+            ring: BTreeMap::new(),
+            replicas,
+        };
+        for (id, server) in servers.iter().enumerate() {
+            ring.add(id, &server.name);
+        }
+        ring
+    }
+
+    /// Places `replicas` points for server `id` on the ring.
+    pub fn add(&mut self, id: usize, name: &str) {
+        for replica in 0..self.replicas {
+            self.ring
+                .insert(hash(format!("{}#{}", name, replica).as_bytes()), id);
+        }
+    }
+
+    /// Takes server `id` off the ring; its keys move to the next points.
+    pub fn remove(&mut self, id: usize) {
+        self.ring.retain(|_, server| *server != id);
+    }
+
+    /// The server owning `key`, or `None` if the ring is empty.
+    pub fn owner(&self, key: &str) -> Option<usize> {
+        let point = hash(key.as_bytes());
+        self.ring
+            .range(point..)
+            .next()
+            .or_else(|| self.ring.iter().next())
+            .map(|(_, &server)| server)
+    }
+}
+
+impl Balancer for ConsistentHash {
+    fn pick(&mut self, key: &str) -> usize {
+        self.owner(key).expect("ring has servers")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    RoundRobin,
+    LeastConnections,
+    Weighted,
+    ConsistentHash,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 4] = [
+        Strategy::RoundRobin,
+        Strategy::LeastConnections,
+        Strategy::Weighted,
+        Strategy::ConsistentHash,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Strategy> {
+        match name {
+            "round-robin" | "rr" => Some(Strategy::RoundRobin),
+            "least-connections" | "least" => Some(Strategy::LeastConnections),
+            "weighted" => Some(Strategy::Weighted),
+            "consistent-hash" | "hash" => Some(Strategy::ConsistentHash),
+            _ => None,
+        }
+    }
+
+    pub fn build(self, servers: &[Server]) -> Box<dyn Balancer> {
+        match self {
+            Strategy::RoundRobin => Box::new(RoundRobin::new(servers.len())),
+            Strategy::LeastConnections => Box::new(LeastConnections::new(servers.len())),
+            Strategy::Weighted => Box::new(Weighted::new(servers)),
+            Strategy::ConsistentHash => Box::new(ConsistentHash::new(
+                servers,
+                ConsistentHash::DEFAULT_REPLICAS,
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Strategy::RoundRobin => "round-robin",
+            Strategy::LeastConnections => "least-connections",
+            Strategy::Weighted => "weighted",
+            Strategy::ConsistentHash => "consistent-hash",
+        })
+    }
+}
+
+/// One line of a trace: a request from `key` arriving at `time` and
+/// holding its connection for `duration` ticks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub time: u64,
+    pub key: String,
+    pub duration: u64,
+}
+
+/// Parses a `time,key,duration` CSV trace; a header line is skipped.
+pub fn parse_trace(text: &str) -> io::Result<Vec<Request>> {
+    let mut requests = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let cells = split_csv_line(line).map_err(|e| invalid(i + 1, &e))?;
+        let [time, key, duration] = cells.as_slice() else {
+            return Err(invalid(i + 1, "expected time,key,duration"));
+        };
+        match (time.trim().parse(), duration.trim().parse()) {
+            (Ok(time), Ok(duration)) => requests.push(Request {
+                time,
+                key: key.trim().to_string(),
+                duration,
+            }),
+            _ if i == 0 => continue,
+            _ => return Err(invalid(i + 1, "time and duration must be whole ticks")),
+        }
+    }
+    Ok(requests)
+}
+
+pub fn load_trace(path: impl AsRef<Path>) -> io::Result<Vec<Request>> {
+    parse_trace(&fs::read_to_string(path)?)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerLoad {
+    pub name: String,
+    pub requests: u64,
+    /// Total ticks of connection time served.
+    pub busy: u64,
+    /// Most connections open at once.
+    pub peak: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadReport {
+    pub strategy: Strategy,
+    pub servers: Vec<ServerLoad>,
+}
+
+impl LoadReport {
+    /// Busiest server's request count over the mean; 1.0 is perfectly even.
+    pub fn skew(&self) -> f64 {
+        let total: u64 = self.servers.iter().map(|s| s.requests).sum();
+        if total == 0 {
+            return 1.0;
+        }
+        let mean = total as f64 / self.servers.len() as f64;
+        let max = self.servers.iter().map(|s| s.requests).max().unwrap_or(0);
+        max as f64 / mean
+    }
+}
+
+/// Replays `trace` in time order through `strategy`. Requests ending at a
+/// tick release their server before requests arriving at that tick pick.
+pub fn simulate(servers: &[Server], trace: &[Request], strategy: Strategy) -> LoadReport {
+    let mut balancer = strategy.build(servers);
+    let mut loads: Vec<ServerLoad> = servers
+        .iter()
+        .map(|s| ServerLoad {
+            name: s.name.clone(),
+            requests: 0,
+            busy: 0,
+            peak: 0,
+        })
+        .collect();
+    let mut open = vec![0u64; servers.len()];
+    let mut finishing: BinaryHeap<Reverse<(u64, usize)>> = BinaryHeap::new();
+
+    let mut order: Vec<&Request> = trace.iter().collect();
+    order.sort_by_key(|r| r.time);
+    for request in order {
+        while let Some(&Reverse((end, server))) = finishing.peek() {
+            if end > request.time {
+                break;
+            }
+            finishing.pop();
+            open[server] -= 1;
+            balancer.release(server);
+        }
+        let server = balancer.pick(&request.key);
+        let load = &mut loads[server];
+        load.requests += 1;
+        load.busy = load.busy.saturating_add(request.duration);
+        open[server] += 1;
+        load.peak = load.peak.max(open[server]);
+        let end = request.time.saturating_add(request.duration);
+        finishing.push(Reverse((end, server)));
+    }
+    LoadReport {
+        strategy,
+        servers: loads,
+    }
+}
+
+fn invalid(line: usize, message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("line {}: {}", line, message),
+    )
+}
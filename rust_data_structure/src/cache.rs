@@ -0,0 +1,545 @@
+//! LRU and LFU caches with statistics and TTL expiry.
+//!
+//! [`LruCache`] is the classic HashMap + doubly linked list: the map finds a
+//! node, the list (stored in a slab of indices) keeps recency order, and
+//! every operation is O(1). [`LfuCache`] keeps a `BTreeMap` ordered by
+//! `(use count, last use)`, so it evicts the least frequently used entry and
+//! breaks ties by recency, in O(log n).
+//!
+//! Entries with a TTL are also indexed in a `BTreeMap` by expiry time, which
+//! costs O(log n) per write of such an entry. A full cache first drops the
+//! entry that expired soonest, if any has, and only otherwise evicts; purging
+//! touches only expired entries.
+//!
+//! Expiry reads time from a [`Clock`]. [`SystemClock`] follows real time;
+//! [`ManualClock`] only moves when told to, which makes TTL behaviour
+//! testable and lets the editor step through time.
+
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// A source of "now", as time elapsed since some fixed origin.
+pub trait Clock {
+    fn now(&self) -> Duration;
+}
+
+/// Real time since the clock was created.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        SystemClock {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// A clock that only advances when told to. Clones share the same time, so
+/// a caller can keep one handle and give another to a cache.
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock {
+    now: Rc<Cell<Duration>>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        ManualClock::default()
+    }
+
+    /// Moves time forward by `by`, stopping at `Duration::MAX`.
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get().saturating_add(by));
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Live entries removed to make room.
+    pub evictions: u64,
+    /// Entries removed because their TTL passed.
+    pub expirations: u64,
+}
+
+impl Stats {
+    /// Hits over lookups, or `None` before the first lookup.
+    pub fn hit_rate(&self) -> Option<f64> {
+        let lookups = self.hits + self.misses;
+        (lookups > 0).then(|| self.hits as f64 / lookups as f64)
+    }
+}
+
+/// Operations shared by both caches.
+pub trait Cache<K, V> {
+    /// Looks up `key`, counting a hit or miss and marking it as used.
+    fn get(&mut self, key: &K) -> Option<&V>;
+    /// Looks up `key` without touching statistics or eviction order.
+    fn peek(&self, key: &K) -> Option<&V>;
+    /// Inserts or replaces `key` with the cache's default TTL. Returns the
+    /// entry evicted to make room, if any.
+    fn put(&mut self, key: K, value: V) -> Option<(K, V)>;
+    /// [`Cache::put`] with an explicit TTL for this entry. A TTL too large
+    /// to add to the current time means the entry never expires.
+    fn put_with_ttl(&mut self, key: K, value: V, ttl: Option<Duration>) -> Option<(K, V)>;
+    fn remove(&mut self, key: &K) -> Option<V>;
+    /// Drops every expired entry and returns how many there were.
+    fn purge_expired(&mut self) -> usize;
+    /// Keys from the next one to be evicted to the last.
+    fn eviction_order(&self) -> Vec<K>;
+    fn len(&self) -> usize;
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    fn capacity(&self) -> usize;
+    fn stats(&self) -> Stats;
+}
+
+/// When an entry expires, made unique by a write counter so it can key the
+/// expiry index.
+type Expiry = (Duration, u64);
+
+struct Node<K, V> {
+    key: K,
+    value: V,
+    expires: Option<Expiry>,
+    /// Towards the most recently used end.
+    prev: Option<usize>,
+    /// Towards the least recently used end.
+    next: Option<usize>,
+}
+
+/// Least-recently-used cache.
+pub struct LruCache<K, V, C = SystemClock> {
+    index: HashMap<K, usize>,
+    slots: Vec<Option<Node<K, V>>>,
+    free: Vec<usize>,
+    /// Most recently used.
+    head: Option<usize>,
+    /// Least recently used; evicted first.
+    tail: Option<usize>,
+    /// Keys of entries with a TTL, soonest to expire first.
+    expiry: BTreeMap<Expiry, K>,
+    writes: u64,
+    capacity: usize,
+    ttl: Option<Duration>,
+    clock: C,
+    stats: Stats,
+}
+
+impl<K: Hash + Eq + Clone, V> LruCache<K, V, SystemClock> {
+    /// Creates a cache without TTL on real time. Panics if `capacity` is
+    /// zero.
+    pub fn new(capacity: usize) -> Self {
+        LruCache::with_clock(capacity, None, SystemClock::default())
+    }
+}
+
+impl<K: Hash + Eq + Clone, V, C: Clock> LruCache<K, V, C> {
+    /// Creates a cache whose entries expire `ttl` after they are written.
+    /// Panics if `capacity` is zero.
+    pub fn with_clock(capacity: usize, ttl: Option<Duration>, clock: C) -> Self {
+        assert!(capacity > 0, "cache capacity must be positive");
+        LruCache {
+            index: HashMap::new(),
+            slots: Vec::new(),
+            free: Vec::new(),
+            head: None,
+            tail: None,
+            expiry: BTreeMap::new(),
+            writes: 0,
+            capacity,
+            ttl,
+            clock,
+            stats: Stats::default(),
+        }
+    }
+
+    fn node(&self, i: usize) -> &Node<K, V> {
+        self.slots[i].as_ref().expect("indexed slot is occupied")
+    }
+
+    fn node_mut(&mut self, i: usize) -> &mut Node<K, V> {
+        self.slots[i].as_mut().expect("indexed slot is occupied")
+    }
+
+    fn detach(&mut self, i: usize) {
+        let (prev, next) = {
+            let node = self.node(i);
+            (node.prev, node.next)
+        };
+        match prev {
+            Some(p) => self.node_mut(p).next = next,
+            None => self.head = next,
+        }
+        match next {
+            Some(n) => self.node_mut(n).prev = prev,
+            None => self.tail = prev,
+        }
+    }
+
+    fn push_front(&mut self, i: usize) {
+        let old_head = self.head;
+        {
+            let node = self.node_mut(i);
+            node.prev = None;
+            node.next = old_head;
+        }
+        match old_head {
+            Some(h) => self.node_mut(h).prev = Some(i),
+            None => self.tail = Some(i),
+        }
+        self.head = Some(i);
+    }
+
+    fn take(&mut self, i: usize) -> Node<K, V> {
+        self.detach(i);
+        let node = self.slots[i].take().expect("indexed slot is occupied");
+        self.index.remove(&node.key);
+        if let Some(expires) = node.expires {
+            self.expiry.remove(&expires);
+        }
+        self.free.push(i);
+        node
+    }
+
+    fn expired(&self, expires: Option<Expiry>) -> bool {
+        expires.is_some_and(|(at, _)| self.clock.now() >= at)
+    }
+
+    /// Indexes `key` as expiring `ttl` from now. An expiry past the end of
+    /// `Duration` is treated as never.
+    fn schedule(&mut self, key: &K, ttl: Option<Duration>) -> Option<Expiry> {
+        let expires = self.clock.now().checked_add(ttl?)?;
+        self.writes += 1;
+        let expires = (expires, self.writes);
+        self.expiry.insert(expires, key.clone());
+        Some(expires)
+    }
+
+    /// The key of the entry that expired soonest, if any has expired.
+    fn first_expired(&self) -> Option<K> {
+        let (&expires, key) = self.expiry.first_key_value()?;
+        self.expired(Some(expires)).then(|| key.clone())
+    }
+}
+
+impl<K: Hash + Eq + Clone, V, C: Clock> Cache<K, V> for LruCache<K, V, C> {
+    fn get(&mut self, key: &K) -> Option<&V> {
+        let Some(&i) = self.index.get(key) else {
+            self.stats.misses += 1;
+            return None;
+        };
+        if self.expired(self.node(i).expires) {
+            self.take(i);
+            self.stats.expirations += 1;
+            self.stats.misses += 1;
+            return None;
+        }
+        self.stats.hits += 1;
+        self.detach(i);
+        self.push_front(i);
+        Some(&self.node(i).value)
+    }
+
+    fn peek(&self, key: &K) -> Option<&V> {
+        let node = self.node(*self.index.get(key)?);
+        (!self.expired(node.expires)).then_some(&node.value)
+    }
+
+    fn put(&mut self, key: K, value: V) -> Option<(K, V)> {
+        self.put_with_ttl(key, value, self.ttl)
+    }
+
+    fn put_with_ttl(&mut self, key: K, value: V, ttl: Option<Duration>) -> Option<(K, V)> {
+        if let Some(&i) = self.index.get(&key) {
+            let expires = self.schedule(&key, ttl);
+            let node = self.node_mut(i);
+            node.value = value;
+            let old = std::mem::replace(&mut node.expires, expires);
+            if let Some(old) = old {
+                self.expiry.remove(&old);
+            }
+            self.detach(i);
+            self.push_front(i);
+            return None;
+        }
+
+        let mut evicted = None;
+        if self.index.len() == self.capacity {
+            if let Some(key) = self.first_expired() {
+                self.take(self.index[&key]);
+                self.stats.expirations += 1;
+            } else if let Some(t) = self.tail {
+                let node = self.take(t);
+                self.stats.evictions += 1;
+                evicted = Some((node.key, node.value));
+            }
+        }
+        let expires = self.schedule(&key, ttl);
+        let node = Node {
+            key: key.clone(),
+            value,
+            expires,
+            prev: None,
+            next: None,
+        };
+        let i = match self.free.pop() {
+            Some(i) => {
+                self.slots[i] = Some(node);
+                i
+            }
+            None => {
+                self.slots.push(Some(node));
+                self.slots.len() - 1
+            }
+        };
+        self.index.insert(key, i);
+        self.push_front(i);
+        evicted
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        let i = *self.index.get(key)?;
+        Some(self.take(i).value)
+    }
+
+    fn purge_expired(&mut self) -> usize {
+        let mut purged = 0;
+        while let Some(key) = self.first_expired() {
+            self.take(self.index[&key]);
+            purged += 1;
+        }
+        self.stats.expirations += purged as u64;
+        purged
+    }
+
+    fn eviction_order(&self) -> Vec<K> {
+        let mut keys = Vec::new();
+        let mut at = self.tail;
+        while let Some(i) = at {
+            let node = self.node(i);
+            keys.push(node.key.clone());
+            at = node.prev;
+        }
+        keys
+    }
+
+    fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn stats(&self) -> Stats {
+        self.stats
+    }
+}
+
+struct LfuEntry<V> {
+    value: V,
+    uses: u64,
+    last_use: u64,
+    expires: Option<Expiry>,
+}
+
+/// Least-frequently-used cache; ties go to the least recently used entry.
+pub struct LfuCache<K, V, C = SystemClock> {
+    entries: HashMap<K, LfuEntry<V>>,
+    /// `(uses, last_use)` of every entry; the first one is evicted next.
+    order: BTreeMap<(u64, u64), K>,
+    tick: u64,
+    /// Keys of entries with a TTL, soonest to expire first.
+    expiry: BTreeMap<Expiry, K>,
+    writes: u64,
+    capacity: usize,
+    ttl: Option<Duration>,
+    clock: C,
+    stats: Stats,
+}
+
+impl<K: Hash + Eq + Clone, V> LfuCache<K, V, SystemClock> {
+    /// Creates a cache without TTL on real time. Panics if `capacity` is
+    /// zero.
+    pub fn new(capacity: usize) -> Self {
+        LfuCache::with_clock(capacity, None, SystemClock::default())
+    }
+}
+
+impl<K: Hash + Eq + Clone, V, C: Clock> LfuCache<K, V, C> {
+    /// Creates a cache whose entries expire `ttl` after they are written.
+    /// Panics if `capacity` is zero.
+    pub fn with_clock(capacity: usize, ttl: Option<Duration>, clock: C) -> Self {
+        assert!(capacity > 0, "cache capacity must be positive");
+        LfuCache {
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            tick: 0,
+            expiry: BTreeMap::new(),
+            writes: 0,
+            capacity,
+            ttl,
+            clock,
+            stats: Stats::default(),
+        }
+    }
+
+    /// How often `key` has been written or read.
+    pub fn uses(&self, key: &K) -> Option<u64> {
+        self.entries.get(key).map(|e| e.uses)
+    }
+
+    fn touch(&mut self, key: &K) {
+        self.tick += 1;
+        let entry = self.entries.get_mut(key).expect("touched key exists");
+        self.order.remove(&(entry.uses, entry.last_use));
+        entry.uses += 1;
+        entry.last_use = self.tick;
+        self.order.insert((entry.uses, entry.last_use), key.clone());
+    }
+
+    fn take(&mut self, key: &K) -> Option<V> {
+        let entry = self.entries.remove(key)?;
+        self.order.remove(&(entry.uses, entry.last_use));
+        if let Some(expires) = entry.expires {
+            self.expiry.remove(&expires);
+        }
+        Some(entry.value)
+    }
+
+    fn expired(&self, expires: Option<Expiry>) -> bool {
+        expires.is_some_and(|(at, _)| self.clock.now() >= at)
+    }
+
+    /// Indexes `key` as expiring `ttl` from now. An expiry past the end of
+    /// `Duration` is treated as never.
+    fn schedule(&mut self, key: &K, ttl: Option<Duration>) -> Option<Expiry> {
+        let expires = self.clock.now().checked_add(ttl?)?;
+        self.writes += 1;
+        let expires = (expires, self.writes);
+        self.expiry.insert(expires, key.clone());
+        Some(expires)
+    }
+
+    /// The key of the entry that expired soonest, if any has expired.
+    fn first_expired(&self) -> Option<K> {
+        let (&expires, key) = self.expiry.first_key_value()?;
+        self.expired(Some(expires)).then(|| key.clone())
+    }
+}
+
+impl<K: Hash + Eq + Clone, V, C: Clock> Cache<K, V> for LfuCache<K, V, C> {
+    fn get(&mut self, key: &K) -> Option<&V> {
+        let Some(entry) = self.entries.get(key) else {
+            self.stats.misses += 1;
+            return None;
+        };
+        if self.expired(entry.expires) {
+            self.take(key);
+            self.stats.expirations += 1;
+            self.stats.misses += 1;
+            return None;
+        }
+        self.stats.hits += 1;
+        self.touch(key);
+        self.entries.get(key).map(|e| &e.value)
+    }
+
+    fn peek(&self, key: &K) -> Option<&V> {
+        let entry = self.entries.get(key)?;
+        (!self.expired(entry.expires)).then_some(&entry.value)
+    }
+
+    fn put(&mut self, key: K, value: V) -> Option<(K, V)> {
+        self.put_with_ttl(key, value, self.ttl)
+    }
+
+    fn put_with_ttl(&mut self, key: K, value: V, ttl: Option<Duration>) -> Option<(K, V)> {
+        if self.entries.contains_key(&key) {
+            let expires = self.schedule(&key, ttl);
+            let entry = self.entries.get_mut(&key).expect("checked above");
+            entry.value = value;
+            let old = std::mem::replace(&mut entry.expires, expires);
+            if let Some(old) = old {
+                self.expiry.remove(&old);
+            }
+            self.touch(&key);
+            return None;
+        }
+
+        let mut evicted = None;
+        if self.entries.len() == self.capacity {
+            if let Some(key) = self.first_expired() {
+                self.take(&key);
+                self.stats.expirations += 1;
+            } else if let Some((_, victim)) = self.order.pop_first() {
+                let value = self.take(&victim).expect("ordered key exists");
+                self.stats.evictions += 1;
+                evicted = Some((victim, value));
+            }
+        }
+        let expires = self.schedule(&key, ttl);
+        self.tick += 1;
+        self.order.insert((1, self.tick), key.clone());
+        self.entries.insert(
+            key,
+            LfuEntry {
+                value,
+                uses: 1,
+                last_use: self.tick,
+                expires,
+            },
+        );
+        evicted
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        self.take(key)
+    }
+
+    fn purge_expired(&mut self) -> usize {
+        let mut purged = 0;
+        while let Some(key) = self.first_expired() {
+            self.take(&key);
+            purged += 1;
+        }
+        self.stats.expirations += purged as u64;
+        purged
+    }
+
+    fn eviction_order(&self) -> Vec<K> {
+        self.order.values().cloned().collect()
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn stats(&self) -> Stats {
+        self.stats
+    }
+}
@@ -0,0 +1,381 @@
+//! Thread-safe collections for sharing between producer and consumer
+//! threads. All of them are used through `&self`, so wrap one in an `Arc`
+//! (or borrow it from a scoped thread) to share it.
+//!
+//! [`ShardedMap`] spreads keys over several `RwLock<HashMap>` shards, so
+//! threads touching different shards never wait for each other. A closure
+//! that panics while holding a shard lock does not poison the map for
+//! everyone else; the shard stays usable with whatever the closure left.
+//! [`BoundedQueue`] and [`PriorityQueue`] guard a `VecDeque` and a
+//! `BinaryHeap` with one mutex and use condition variables to block
+//! consumers (and, for the bounded queue, producers) until they can proceed.
+//! Closing a queue wakes every waiter; consumers still drain what is left.
+
+use std::collections::hash_map::RandomState;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::{BuildHasher, Hash};
+use std::sync::{
+    Condvar, Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard,
+};
+use std::time::{Duration, Instant};
+
+// Shard locks are taken through these, which ignore poisoning: a panic in
+// a caller's closure must not make every later access panic too.
+fn read_shard<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn write_shard<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    lock.write().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// A hash map split into independently locked shards.
+#[derive(Debug)]
+pub struct ShardedMap<K, V> {
+    shards: Vec<RwLock<HashMap<K, V>>>,
+    hasher: RandomState,
+}
+
+impl<K: Hash + Eq, V> Default for ShardedMap<K, V> {
+    fn default() -> Self {
+        ShardedMap::new()
+    }
+}
+
+impl<K: Hash + Eq, V> ShardedMap<K, V> {
+    pub const DEFAULT_SHARDS: usize = 16;
+
+    pub fn new() -> Self {
+        ShardedMap::with_shards(Self::DEFAULT_SHARDS)
+    }
+
+    pub fn with_shards(shards: usize) -> Self {
+        ShardedMap {
+            shards: (0..shards.max(1))
+                .map(|_| RwLock::new(HashMap::new()))
+                .collect(),
+            hasher: RandomState::new(),
+        }
+    }
+
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    fn shard(&self, key: &K) -> &RwLock<HashMap<K, V>> {
+        let index = self.hasher.hash_one(key) as usize % self.shards.len();
+        &self.shards[index]
+    }
+
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        write_shard(self.shard(&key)).insert(key, value)
+    }
+
+    pub fn remove(&self, key: &K) -> Option<V> {
+        write_shard(self.shard(key)).remove(key)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        read_shard(self.shard(key)).contains_key(key)
+    }
+
+    /// Calls `f` with the value for `key` under the shard's read lock.
+    pub fn read<R>(&self, key: &K, f: impl FnOnce(Option<&V>) -> R) -> R {
+        f(read_shard(self.shard(key)).get(key))
+    }
+
+    /// Updates the value for `key` in place, inserting `default()` first if
+    /// it is missing. The whole update happens under the shard's write
+    /// lock, so concurrent updates to one key never lose each other.
+    pub fn update<R>(&self, key: K, default: impl FnOnce() -> V, f: impl FnOnce(&mut V) -> R) -> R {
+        let mut shard = write_shard(self.shard(&key));
+        f(shard.entry(key).or_insert_with(default))
+    }
+
+    /// Total entries. Shards are counted one after another, so the result
+    /// may be stale while other threads are writing.
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|s| read_shard(s).len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Entries per shard, to check how evenly keys spread.
+    pub fn shard_sizes(&self) -> Vec<usize> {
+        self.shards.iter().map(|s| read_shard(s).len()).collect()
+    }
+}
+
+impl<K: Hash + Eq, V: Clone> ShardedMap<K, V> {
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.read(key, |v| v.cloned())
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> ShardedMap<K, V> {
+    /// A copy of every entry, taken shard by shard.
+    pub fn snapshot(&self) -> HashMap<K, V> {
+        let mut all = HashMap::new();
+        for shard in &self.shards {
+            all.extend(
+                read_shard(shard)
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone())),
+            );
+        }
+        all
+    }
+}
+
+/// Why an item could not be pushed; the item is handed back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PushError<T> {
+    /// The queue stayed full (for `try_push` and `push_timeout`).
+    Full(T),
+    Closed(T),
+}
+
+impl<T> PushError<T> {
+    pub fn into_inner(self) -> T {
+        match self {
+            PushError::Full(item) | PushError::Closed(item) => item,
+        }
+    }
+}
+
+struct QueueState<T> {
+    items: VecDeque<T>,
+    closed: bool,
+}
+
+/// A fixed-capacity multi-producer, multi-consumer FIFO queue.
+pub struct BoundedQueue<T> {
+    capacity: usize,
+    state: Mutex<QueueState<T>>,
+    not_empty: Condvar,
+    not_full: Condvar,
+}
+
+impl<T> BoundedQueue<T> {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "a bounded queue needs room for one item");
+        BoundedQueue {
+            capacity,
+            state: Mutex::new(QueueState {
+                items: VecDeque::with_capacity(capacity),
+                closed: false,
+            }),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.lock().closed
+    }
+
+    /// Blocks while the queue is full.
+    pub fn push(&self, item: T) -> Result<(), PushError<T>> {
+        self.push_until(item, None)
+    }
+
+    pub fn try_push(&self, item: T) -> Result<(), PushError<T>> {
+        self.push_until(item, Some(Instant::now()))
+    }
+
+    /// A timeout too large to represent waits indefinitely.
+    pub fn push_timeout(&self, item: T, timeout: Duration) -> Result<(), PushError<T>> {
+        self.push_until(item, deadline(timeout))
+    }
+
+    /// Blocks while the queue is empty. `None` means the queue was closed
+    /// and fully drained.
+    pub fn pop(&self) -> Option<T> {
+        self.pop_until(None)
+    }
+
+    pub fn try_pop(&self) -> Option<T> {
+        self.pop_until(Some(Instant::now()))
+    }
+
+    /// `None` if nothing arrived within `timeout` or the queue is closed
+    /// and drained. A timeout too large to represent waits indefinitely.
+    pub fn pop_timeout(&self, timeout: Duration) -> Option<T> {
+        self.pop_until(deadline(timeout))
+    }
+
+    /// Stops accepting pushes and wakes every blocked thread.
+    pub fn close(&self) {
+        self.lock().closed = true;
+        self.not_empty.notify_all();
+        self.not_full.notify_all();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, QueueState<T>> {
+        self.state.lock().unwrap()
+    }
+
+    fn push_until(&self, item: T, deadline: Option<Instant>) -> Result<(), PushError<T>> {
+        let mut state = self.lock();
+        loop {
+            if state.closed {
+                return Err(PushError::Closed(item));
+            }
+            if state.items.len() < self.capacity {
+                state.items.push_back(item);
+                drop(state);
+                self.not_empty.notify_one();
+                return Ok(());
+            }
+            state = match wait(&self.not_full, state, deadline) {
+                Some(state) => state,
+                None => return Err(PushError::Full(item)),
+            };
+        }
+    }
+
+    fn pop_until(&self, deadline: Option<Instant>) -> Option<T> {
+        let mut state = self.lock();
+        loop {
+            if let Some(item) = state.items.pop_front() {
+                drop(state);
+                self.not_full.notify_one();
+                return Some(item);
+            }
+            if state.closed {
+                return None;
+            }
+            state = wait(&self.not_empty, state, deadline)?;
+        }
+    }
+}
+
+/// `timeout` from now, or `None` (no deadline) if that is past the end of
+/// time `Instant` can represent.
+fn deadline(timeout: Duration) -> Option<Instant> {
+    Instant::now().checked_add(timeout)
+}
+
+/// Waits on `condvar` until notified or `deadline` passes; `None` means the
+/// deadline passed. No deadline waits indefinitely.
+fn wait<'a, S>(
+    condvar: &Condvar,
+    guard: MutexGuard<'a, S>,
+    deadline: Option<Instant>,
+) -> Option<MutexGuard<'a, S>> {
+    match deadline {
+        None => Some(condvar.wait(guard).unwrap()),
+        Some(deadline) => {
+            let left = deadline.checked_duration_since(Instant::now())?;
+            if left.is_zero() {
+                return None;
+            }
+            Some(condvar.wait_timeout(guard, left).unwrap().0)
+        }
+    }
+}
+
+struct HeapState<T> {
+    items: BinaryHeap<T>,
+    closed: bool,
+}
+
+/// An unbounded max-priority queue shared between threads.
+pub struct PriorityQueue<T> {
+    state: Mutex<HeapState<T>>,
+    not_empty: Condvar,
+}
+
+impl<T: Ord> Default for PriorityQueue<T> {
+    fn default() -> Self {
+        PriorityQueue::new()
+    }
+}
+
+impl<T: Ord> PriorityQueue<T> {
+    pub fn new() -> Self {
+        PriorityQueue {
+            state: Mutex::new(HeapState {
+                items: BinaryHeap::new(),
+                closed: false,
+            }),
+            not_empty: Condvar::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Hands the item back if the queue is closed.
+    pub fn push(&self, item: T) -> Result<(), T> {
+        let mut state = self.lock();
+        if state.closed {
+            return Err(item);
+        }
+        state.items.push(item);
+        drop(state);
+        self.not_empty.notify_one();
+        Ok(())
+    }
+
+    /// Blocks until an item is available; `None` once closed and drained.
+    pub fn pop(&self) -> Option<T> {
+        self.pop_until(None)
+    }
+
+    pub fn try_pop(&self) -> Option<T> {
+        self.pop_until(Some(Instant::now()))
+    }
+
+    /// A timeout too large to represent waits indefinitely.
+    pub fn pop_timeout(&self, timeout: Duration) -> Option<T> {
+        self.pop_until(deadline(timeout))
+    }
+
+    pub fn close(&self) {
+        self.lock().closed = true;
+        self.not_empty.notify_all();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HeapState<T>> {
+        self.state.lock().unwrap()
+    }
+
+    fn pop_until(&self, deadline: Option<Instant>) -> Option<T> {
+        let mut state = self.lock();
+        loop {
+            if let Some(item) = state.items.pop() {
+                return Some(item);
+            }
+            if state.closed {
+                return None;
+            }
+            state = wait(&self.not_empty, state, deadline)?;
+        }
+    }
+}
+
+impl<T: Ord + Clone> PriorityQueue<T> {
+    pub fn peek(&self) -> Option<T> {
+        self.lock().items.peek().cloned()
+    }
+}
@@ -0,0 +1,620 @@
+//! RFC 4180 CSV reading and writing, with typed records.
+//!
+//! [`Reader`] splits input into records of text fields. Quoted fields may
+//! contain the delimiter, doubled quotes (`""` for `"`) and line breaks;
+//! records may end in `\n` or `\r\n`, and blank lines are skipped (except
+//! in single-column data, where they hold a null). [`TypedReader`] buffers
+//! the first rows, infers a [`DataType`] for each column from them (unless
+//! the caller overrides it) and then streams records converted to
+//! [`Value`]s. [`read_table`] collects them into a [`Table`]; [`Writer`]
+//! goes the other way.
+//!
+//! Type inference picks, per column, the first type every sampled value
+//! parses as: bool (`true`/`false`), int, decimal (plain `12.50` notation,
+//! scaled to the most fractional digits seen, as long as every value still
+//! fits at that scale), float (anything else `f64` accepts, such as
+//! `1e-3`), and finally string. A bare empty field is null; a quoted `""`
+//! in a string column is an empty string.
+//!
+//! Errors carry the 1-based record number (the header is row 1) and field
+//! number where they were found.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use crate::table::{DataType, Decimal, Table, TableError, Value};
+
+#[derive(Debug)]
+pub enum CsvError {
+    Io(io::Error),
+    Parse {
+        row: usize,
+        column: usize,
+        message: String,
+    },
+}
+
+impl CsvError {
+    fn at(row: usize, column: usize, message: impl Into<String>) -> Self {
+        CsvError::Parse {
+            row,
+            column,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::Io(e) => write!(f, "{}", e),
+            CsvError::Parse {
+                row,
+                column,
+                message,
+            } => write!(f, "row {}, column {}: {}", row, column, message),
+        }
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+impl From<io::Error> for CsvError {
+    fn from(e: io::Error) -> Self {
+        CsvError::Io(e)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvOptions {
+    pub delimiter: char,
+    pub quote: char,
+    /// Whether the first record names the columns. Without one, columns
+    /// are called `column1`, `column2`, ...
+    pub has_header: bool,
+    /// How many records type inference looks at.
+    pub sample_rows: usize,
+    /// Column types to use instead of inferring them.
+    pub overrides: Vec<(String, DataType)>,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: ',',
+            quote: '"',
+            has_header: true,
+            sample_rows: 100,
+            overrides: Vec::new(),
+        }
+    }
+}
+
+impl CsvOptions {
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn has_header(mut self, has_header: bool) -> Self {
+        self.has_header = has_header;
+        self
+    }
+
+    pub fn sample_rows(mut self, rows: usize) -> Self {
+        self.sample_rows = rows.max(1);
+        self
+    }
+
+    /// Reads column `name` as `data_type` instead of inferring it.
+    pub fn with_type(mut self, name: impl Into<String>, data_type: DataType) -> Self {
+        self.overrides.push((name.into(), data_type));
+        self
+    }
+}
+
+/// One field of a record, as read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub text: String,
+    /// Whether the field was enclosed in quotes, which tells `""` (an empty
+    /// string) from an empty field (null).
+    pub quoted: bool,
+}
+
+/// Splits CSV input into records of text fields.
+pub struct Reader<R> {
+    input: R,
+    delimiter: char,
+    quote: char,
+    skip_blank_lines: bool,
+    rows: usize,
+    line: String,
+}
+
+impl<R: BufRead> Reader<R> {
+    pub fn new(input: R, options: &CsvOptions) -> Self {
+        Reader {
+            input,
+            delimiter: options.delimiter,
+            quote: options.quote,
+            skip_blank_lines: true,
+            rows: 0,
+            line: String::new(),
+        }
+    }
+
+    /// Records read so far.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Whether blank lines are skipped (the default) or read as records
+    /// with one empty field, which is what they are in single-column data.
+    pub fn skip_blank_lines(&mut self, skip: bool) {
+        self.skip_blank_lines = skip;
+    }
+
+    /// The next record, or `None` at the end of input.
+    pub fn read_record(&mut self) -> Result<Option<Vec<String>>, CsvError> {
+        let fields = self.read_fields()?;
+        Ok(fields.map(|fields| fields.into_iter().map(|f| f.text).collect()))
+    }
+
+    /// Like [`Reader::read_record`], but says which fields were quoted.
+    pub fn read_fields(&mut self) -> Result<Option<Vec<Field>>, CsvError> {
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+        let mut in_quotes = false;
+        // Set after a closing quote, when only a delimiter or the end of the
+        // record may follow.
+        let mut closed = false;
+        loop {
+            self.line.clear();
+            if self.input.read_line(&mut self.line)? == 0 {
+                if in_quotes {
+                    return Err(CsvError::at(
+                        self.rows + 1,
+                        fields.len() + 1,
+                        "quoted field is never closed",
+                    ));
+                }
+                if fields.is_empty() && field.is_empty() && !quoted {
+                    return Ok(None);
+                }
+                break;
+            }
+            if self.skip_blank_lines
+                && !in_quotes
+                && fields.is_empty()
+                && field.is_empty()
+                && self.line.trim_end_matches(['\r', '\n']).is_empty()
+            {
+                continue;
+            }
+            let mut chars = self.line.chars().peekable();
+            let mut ended = false;
+            while let Some(c) = chars.next() {
+                if in_quotes {
+                    if c == self.quote {
+                        if chars.peek() == Some(&self.quote) {
+                            chars.next();
+                            field.push(c);
+                        } else {
+                            in_quotes = false;
+                            closed = true;
+                        }
+                    } else {
+                        field.push(c);
+                    }
+                } else if c == self.delimiter {
+                    fields.push(Field {
+                        text: std::mem::take(&mut field),
+                        quoted,
+                    });
+                    quoted = false;
+                    closed = false;
+                } else if c == '\n' || (c == '\r' && chars.peek().is_none_or(|&n| n == '\n')) {
+                    ended = true;
+                    break;
+                } else if closed {
+                    return Err(CsvError::at(
+                        self.rows + 1,
+                        fields.len() + 1,
+                        format!("unexpected {:?} after closing quote", c),
+                    ));
+                } else if c == self.quote {
+                    if !field.is_empty() {
+                        return Err(CsvError::at(
+                            self.rows + 1,
+                            fields.len() + 1,
+                            "quote inside an unquoted field",
+                        ));
+                    }
+                    in_quotes = true;
+                    quoted = true;
+                } else {
+                    field.push(c);
+                }
+            }
+            if ended || !in_quotes {
+                break;
+            }
+        }
+        fields.push(Field {
+            text: field,
+            quoted,
+        });
+        self.rows += 1;
+        Ok(Some(fields))
+    }
+}
+
+impl<R: BufRead> Iterator for Reader<R> {
+    type Item = Result<Vec<String>, CsvError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+/// Converts one field to `data_type`; empty text is null.
+pub fn parse_value(text: &str, data_type: DataType) -> Result<Value, String> {
+    if text.is_empty() {
+        return Ok(Value::Null);
+    }
+    let trimmed = text.trim();
+    let parsed = match data_type {
+        DataType::Str => Some(Value::Str(text.to_string())),
+        DataType::Int => trimmed.parse().ok().map(Value::Int),
+        DataType::Float => trimmed.parse().ok().map(Value::Float),
+        DataType::Bool => parse_bool(trimmed).map(Value::Bool),
+        DataType::Decimal { scale } => Decimal::parse(trimmed, scale).map(Value::Decimal),
+    };
+    parsed.ok_or_else(|| format!("expected {}, found {:?}", data_type, text))
+}
+
+fn parse_bool(text: &str) -> Option<bool> {
+    if text.eq_ignore_ascii_case("true") {
+        Some(true)
+    } else if text.eq_ignore_ascii_case("false") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Digits after the point if `text` is plain decimal notation.
+fn decimal_scale(text: &str) -> Option<u8> {
+    let digits = text.strip_prefix(['-', '+']).unwrap_or(text);
+    let (whole, fraction) = digits.split_once('.')?;
+    let plain = !(whole.is_empty() && fraction.is_empty())
+        && whole
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit());
+    let scale = u8::try_from(fraction.len()).ok()?;
+    (plain && scale <= Decimal::MAX_SCALE).then_some(scale)
+}
+
+/// The narrowest type every non-empty value in `values` parses as.
+pub fn infer_type<'a>(values: impl IntoIterator<Item = &'a str>) -> DataType {
+    let mut candidates = [true; 4]; // bool, int, decimal, float
+    let mut scale = 0;
+    let values: Vec<&str> = values
+        .into_iter()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .collect();
+    for &value in &values {
+        candidates[0] &= parse_bool(value).is_some();
+        candidates[1] &= value.parse::<i64>().is_ok();
+        match decimal_scale(value) {
+            Some(s) => scale = scale.max(s),
+            None => candidates[2] &= value.parse::<i64>().is_ok(),
+        }
+        candidates[3] &= value.parse::<f64>().is_ok();
+    }
+    // Widening to the largest scale can push the integer part of another
+    // value out of range.
+    candidates[2] &= values.iter().all(|v| Decimal::parse(v, scale).is_some());
+    match candidates {
+        _ if values.is_empty() => DataType::Str,
+        [true, ..] => DataType::Bool,
+        [_, true, ..] => DataType::Int,
+        [_, _, true, _] => DataType::Decimal { scale },
+        [_, _, _, true] => DataType::Float,
+        _ => DataType::Str,
+    }
+}
+
+/// Streams records as typed values, inferring the schema first.
+pub struct TypedReader<R> {
+    reader: Reader<R>,
+    schema: Vec<(String, DataType)>,
+    /// Sampled records not yet handed out, with their row numbers.
+    buffered: VecDeque<(usize, Vec<Field>)>,
+}
+
+impl<R: BufRead> TypedReader<R> {
+    pub fn new(input: R, options: &CsvOptions) -> Result<Self, CsvError> {
+        let mut reader = Reader::new(input, options);
+        let header = if options.has_header {
+            reader.read_record()?
+        } else {
+            None
+        };
+        let mut buffered = VecDeque::new();
+        let mut width = header.as_ref().map(Vec::len);
+        while buffered.len() < options.sample_rows {
+            // A blank line is a null in a single column, so once the width
+            // is known to be one, blank lines stop being skipped.
+            reader.skip_blank_lines(width != Some(1));
+            match reader.read_fields()? {
+                Some(record) => {
+                    width.get_or_insert(record.len());
+                    buffered.push_back((reader.rows(), record));
+                }
+                None => break,
+            }
+        }
+        reader.skip_blank_lines(width != Some(1));
+        let width = width.unwrap_or(0);
+        let names = header.unwrap_or_else(|| (1..=width).map(|i| format!("column{}", i)).collect());
+
+        for (name, _) in &options.overrides {
+            if !names.contains(name) {
+                return Err(CsvError::at(
+                    1,
+                    1,
+                    format!("no column named '{}' to override", name),
+                ));
+            }
+        }
+        let schema = names
+            .into_iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let data_type = options
+                    .overrides
+                    .iter()
+                    .find(|(n, _)| *n == name)
+                    .map(|&(_, t)| t)
+                    .unwrap_or_else(|| {
+                        infer_type(
+                            buffered
+                                .iter()
+                                .filter_map(|(_, r)| r.get(i).map(|f| f.text.as_str())),
+                        )
+                    });
+                (name, data_type)
+            })
+            .collect();
+        Ok(TypedReader {
+            reader,
+            schema,
+            buffered,
+        })
+    }
+
+    /// Column names and types, in order.
+    pub fn schema(&self) -> &[(String, DataType)] {
+        &self.schema
+    }
+
+    /// The next record converted to the schema's types.
+    pub fn read_values(&mut self) -> Result<Option<Vec<Value>>, CsvError> {
+        let (row, record) = match self.buffered.pop_front() {
+            Some(next) => next,
+            None => match self.reader.read_fields()? {
+                Some(record) => (self.reader.rows(), record),
+                None => return Ok(None),
+            },
+        };
+        if record.len() != self.schema.len() {
+            return Err(CsvError::at(
+                row,
+                record.len().min(self.schema.len()) + 1,
+                format!(
+                    "expected {} fields, found {}",
+                    self.schema.len(),
+                    record.len()
+                ),
+            ));
+        }
+        record
+            .iter()
+            .zip(&self.schema)
+            .enumerate()
+            .map(|(i, (field, (_, data_type)))| {
+                if field.quoted && *data_type == DataType::Str {
+                    return Ok(Value::Str(field.text.clone()));
+                }
+                parse_value(&field.text, *data_type)
+                    .map_err(|message| CsvError::at(row, i + 1, message))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Some)
+    }
+}
+
+impl<R: BufRead> Iterator for TypedReader<R> {
+    type Item = Result<Vec<Value>, CsvError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_values().transpose()
+    }
+}
+
+/// Reads all of `input` into a table.
+pub fn read_table(input: impl BufRead, options: &CsvOptions) -> Result<Table, CsvError> {
+    let mut reader = TypedReader::new(input, options)?;
+    let columns: Vec<(&str, DataType)> = reader
+        .schema()
+        .iter()
+        .map(|(n, t)| (n.as_str(), *t))
+        .collect();
+    let mut table = Table::new(&columns).map_err(|e| match e {
+        TableError::DuplicateColumn(name) => {
+            let second = columns
+                .iter()
+                .enumerate()
+                .filter(|(_, (n, _))| *n == name)
+                .nth(1);
+            let column = second.map_or(1, |(i, _)| i + 1);
+            CsvError::at(1, column, format!("column '{}' appears twice", name))
+        }
+        other => CsvError::at(1, 1, other.to_string()),
+    })?;
+    while let Some(values) = reader.read_values()? {
+        table.push_row(values).expect("values match the schema");
+    }
+    Ok(table)
+}
+
+/// What a field written by [`Writer`] holds, which decides its quoting.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Null,
+    Number,
+    Text,
+}
+
+/// When [`Writer`] wraps fields in quotes. Nulls are always written as
+/// bare empty fields and empty strings as `""`, so the two read back apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Quoting {
+    /// Only fields containing the delimiter, a quote or a line break.
+    #[default]
+    Minimal,
+    Always,
+    /// Every field that is not a number; nulls stay bare.
+    NonNumeric,
+    /// Never; a field that would need quotes is an error.
+    Never,
+}
+
+pub struct Writer<W> {
+    output: W,
+    delimiter: char,
+    quote: char,
+    quoting: Quoting,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(output: W) -> Self {
+        Writer {
+            output,
+            delimiter: ',',
+            quote: '"',
+            quoting: Quoting::default(),
+        }
+    }
+
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn quoting(mut self, quoting: Quoting) -> Self {
+        self.quoting = quoting;
+        self
+    }
+
+    /// Writes one record of text. An empty field is written bare, so it
+    /// reads back as null; [`Writer::write_table`] keeps empty strings.
+    pub fn write_record<S: AsRef<str>>(&mut self, fields: &[S]) -> io::Result<()> {
+        let fields: Vec<(&str, Cell)> = fields
+            .iter()
+            .map(|f| {
+                let text = f.as_ref();
+                let cell = if text.is_empty() {
+                    Cell::Null
+                } else if text.trim().parse::<f64>().is_ok() {
+                    Cell::Number
+                } else {
+                    Cell::Text
+                };
+                (text, cell)
+            })
+            .collect();
+        self.write_fields(&fields)
+    }
+
+    /// Writes the header and every row; nulls become empty fields.
+    pub fn write_table(&mut self, table: &Table) -> io::Result<()> {
+        let names: Vec<&str> = table.columns().iter().map(|c| c.name()).collect();
+        self.write_record(&names)?;
+        for row in table.rows() {
+            let values = row.values();
+            let texts: Vec<String> = values
+                .iter()
+                .map(|v| {
+                    if v.is_null() {
+                        String::new()
+                    } else {
+                        v.to_string()
+                    }
+                })
+                .collect();
+            let fields: Vec<(&str, Cell)> = texts
+                .iter()
+                .zip(&values)
+                .map(|(text, value)| {
+                    let cell = match value {
+                        Value::Null => Cell::Null,
+                        Value::Int(_) | Value::Float(_) | Value::Decimal(_) => Cell::Number,
+                        Value::Str(_) | Value::Bool(_) => Cell::Text,
+                    };
+                    (text.as_str(), cell)
+                })
+                .collect();
+            self.write_fields(&fields)?;
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.output.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.output
+    }
+
+    fn write_fields(&mut self, fields: &[(&str, Cell)]) -> io::Result<()> {
+        let mut line = String::new();
+        for (i, &(field, cell)) in fields.iter().enumerate() {
+            if i > 0 {
+                line.push(self.delimiter);
+            }
+            let special = (cell == Cell::Text && field.is_empty())
+                || field.contains([self.delimiter, self.quote, '\n', '\r']);
+            let quote = match self.quoting {
+                _ if cell == Cell::Null => false,
+                Quoting::Minimal => special,
+                Quoting::Always => true,
+                Quoting::NonNumeric => special || cell == Cell::Text,
+                Quoting::Never if special => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("field {} needs quoting: {:?}", i + 1, field),
+                    ))
+                }
+                Quoting::Never => false,
+            };
+            if quote {
+                let doubled = format!("{}{}", self.quote, self.quote);
+                line.push(self.quote);
+                line.push_str(&field.replace(self.quote, &doubled));
+                line.push(self.quote);
+            } else {
+                line.push_str(field);
+            }
+        }
+        line.push('\n');
+        self.output.write_all(line.as_bytes())
+    }
+}
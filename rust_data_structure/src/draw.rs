@@ -0,0 +1,203 @@
+//! ASCII renderings of collection internals for the editors' `draw` command.
+//!
+//! Only what std exposes is drawn: slice layouts and capacities are real,
+//! while B-tree nodes and hash buckets are approximations of the layout.
+
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque};
+use std::fmt::Display;
+
+/// Keys per B-tree node in std (`2 * B - 1` with `B = 6`).
+const BTREE_NODE_CAPACITY: usize = 11;
+
+fn cells<T: Display>(values: impl IntoIterator<Item = T>) -> Vec<String> {
+    values.into_iter().map(|v| v.to_string()).collect()
+}
+
+fn row(cells: &[String], width: usize) -> String {
+    let inner: Vec<String> = cells.iter().map(|c| format!("{:^width$}", c)).collect();
+    format!("|{}|", inner.join("|"))
+}
+
+fn cell_width(cells: &[String]) -> usize {
+    cells
+        .iter()
+        .map(|c| c.chars().count())
+        .max()
+        .unwrap_or(1)
+        .max(1)
+        + 2
+}
+
+/// Draws the used slots of a `Vec` followed by its spare capacity.
+pub fn vec<T: Display>(vec: &Vec<T>) -> String {
+    let mut slots = cells(vec);
+    slots.extend((vec.len()..vec.capacity()).map(|_| String::from("·")));
+    let mut out = format!("Vec: len {} / capacity {}\n", vec.len(), vec.capacity());
+    if slots.is_empty() {
+        out.push_str("(no allocation)");
+        return out;
+    }
+    let width = cell_width(&slots);
+    out.push_str(&row(&slots, width));
+    out.push('\n');
+    let used = "^".repeat((width + 1) * vec.len());
+    out.push_str(&format!(" {} len", used.trim_end()));
+    out
+}
+
+/// Draws a `VecDeque` as its ring buffer, marking head and tail.
+///
+/// When the contents wrap, the back segment sits at the start of the buffer
+/// and the front segment at the end, which is drawn to scale. std does not
+/// expose the offset of a contiguous deque, so that case is drawn from slot 0.
+pub fn vecdeque<T: Display>(deque: &VecDeque<T>) -> String {
+    let (front, back) = deque.as_slices();
+    let capacity = deque.capacity();
+    let mut out = format!(
+        "VecDeque: len {} / capacity {} ({})\n",
+        deque.len(),
+        capacity,
+        if back.is_empty() {
+            "contiguous"
+        } else {
+            "wrapped"
+        }
+    );
+    if capacity == 0 {
+        out.push_str("(no allocation)");
+        return out;
+    }
+
+    let free = capacity - deque.len();
+    let (slots, head, tail) = if back.is_empty() {
+        let mut slots = cells(front);
+        slots.extend((0..free).map(|_| String::from("·")));
+        (slots, 0, front.len())
+    } else {
+        let mut slots = cells(back);
+        slots.extend((0..free).map(|_| String::from("·")));
+        slots.extend(cells(front));
+        (slots, back.len() + free, back.len())
+    };
+
+    let width = cell_width(&slots);
+    out.push_str(&row(&slots, width));
+    out.push('\n');
+    let tail = tail % capacity;
+    let mut markers = vec![' '; (width + 1) * capacity];
+    let col = |slot: usize| (width + 1) * slot + width / 2 + 1;
+    let both = deque.is_empty() || head == tail;
+    if both {
+        markers[col(tail)] = '*';
+    } else {
+        markers[col(head)] = 'H';
+        markers[col(tail)] = 'T';
+    }
+    out.push_str(markers.iter().collect::<String>().trim_end());
+    out.push_str("\nH = head (front), T = next back slot");
+    if both {
+        out.push_str(", * = head and tail");
+    }
+    out
+}
+
+/// Draws a `LinkedList` as a chain of doubly linked nodes.
+pub fn linkedlist<T: Display>(list: &LinkedList<T>) -> String {
+    let mut out = format!("LinkedList: len {}\n", list.len());
+    if list.is_empty() {
+        out.push_str("head -> None <- tail");
+        return out;
+    }
+    let nodes: Vec<String> = list.iter().map(|v| format!("[{}]", v)).collect();
+    out.push_str(&format!("head -> {} <- tail\n", nodes.join(" <-> ")));
+    out.push_str("(each node is a separate heap allocation)");
+    out
+}
+
+/// Draws a `BinaryHeap` as a binary tree, one line per level.
+pub fn binaryheap<T: Display + Ord>(heap: &BinaryHeap<T>) -> String {
+    let slots = cells(heap.as_slice());
+    let mut out = format!("BinaryHeap: len {} (max at the root)\n", slots.len());
+    if slots.is_empty() {
+        out.push_str("(empty tree)");
+        return out;
+    }
+
+    let depth = usize::BITS - slots.len().leading_zeros();
+    let width = cell_width(&slots);
+    let total = (1usize << (depth - 1)) * width;
+    let mut start = 0;
+    for level in 0..depth {
+        let count = 1usize << level;
+        let span = total / count;
+        let line: String = slots
+            .iter()
+            .skip(start)
+            .take(count)
+            .map(|c| format!("{:^span$}", c))
+            .collect();
+        out.push_str(&format!("L{} {}\n", level, line.trim_end()));
+        start += count;
+    }
+    out.push_str("children of slot i are slots 2i+1 and 2i+2");
+    out
+}
+
+fn buckets(entries: Vec<String>, kind: &str, len: usize) -> String {
+    let mut out = format!(
+        "{}: len {} in sorted nodes of up to {} keys\n",
+        kind, len, BTREE_NODE_CAPACITY
+    );
+    if entries.is_empty() {
+        out.push_str("(empty tree)");
+        return out;
+    }
+    let nodes: Vec<String> = entries
+        .chunks(BTREE_NODE_CAPACITY)
+        .map(|chunk| format!("[{}]", chunk.join(" ")))
+        .collect();
+    out.push_str(&nodes.join(" -> "));
+    out
+}
+
+/// Draws a `BTreeMap` as consecutive sorted buckets of node size.
+pub fn btreemap<K: Display, V: Display>(map: &BTreeMap<K, V>) -> String {
+    let entries = map.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+    buckets(entries, "BTreeMap", map.len())
+}
+
+/// Draws a `BTreeSet` as consecutive sorted buckets of node size.
+pub fn btreeset<T: Display>(set: &BTreeSet<T>) -> String {
+    buckets(cells(set), "BTreeSet", set.len())
+}
+
+fn table(entries: Vec<String>, kind: &str, capacity: usize) -> String {
+    let len = entries.len();
+    let mut out = format!("{}: len {} / capacity {}", kind, len, capacity);
+    if capacity > 0 {
+        out.push_str(&format!(
+            " (load {:.0}%)",
+            len as f64 * 100.0 / capacity as f64
+        ));
+    }
+    out.push('\n');
+    if entries.is_empty() {
+        out.push_str("(no entries)");
+        return out;
+    }
+    let width = cell_width(&entries);
+    out.push_str(&row(&entries, width));
+    out.push_str("\n(iteration order follows the hash table, not insertion)");
+    out
+}
+
+/// Draws a `HashMap` as its entries in table order with the load factor.
+pub fn hashmap<K: Display, V: Display>(map: &HashMap<K, V>) -> String {
+    let entries = map.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+    table(entries, "HashMap", map.capacity())
+}
+
+/// Draws a `HashSet` as its entries in table order with the load factor.
+pub fn hashset<T: Display>(set: &HashSet<T>) -> String {
+    table(cells(set), "HashSet", set.capacity())
+}
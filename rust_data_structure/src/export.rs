@@ -0,0 +1,186 @@
+//! Writing collections out for the editors' `export` command.
+//!
+//! Rows are written in the order they are given. Callers pass sequences in
+//! position order, B-trees in key order and heaps in pop order; hash-based
+//! collections iterate in an unspecified order, so `--sorted` exists to make
+//! their exports reproducible.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::json;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Json,
+    JsonLines,
+    Markdown,
+}
+
+impl Format {
+    pub fn from_name(name: &str) -> Option<Format> {
+        match name.to_lowercase().as_str() {
+            "csv" => Some(Format::Csv),
+            "json" => Some(Format::Json),
+            "jsonl" | "ndjson" => Some(Format::JsonLines),
+            "md" | "markdown" => Some(Format::Markdown),
+            _ => None,
+        }
+    }
+
+    /// Guesses the format from the file extension, defaulting to CSV.
+    pub fn from_path(path: &str) -> Format {
+        Path::new(path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Format::from_name)
+            .unwrap_or(Format::Csv)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportOptions {
+    pub format: Format,
+    /// Sort rows (by value, or by key for maps) before writing.
+    pub sorted: bool,
+}
+
+/// Parses `<path> [--format csv|json|jsonl|md] [--sorted]`, the arguments
+/// of the `export` command.
+pub fn parse_args(args: &[&str]) -> Result<(String, ExportOptions), String> {
+    let usage = "Usage: export <path> [--format csv|json|jsonl|md] [--sorted]";
+    let path = args.first().ok_or(usage)?.to_string();
+    let mut options = ExportOptions {
+        format: Format::from_path(&path),
+        sorted: false,
+    };
+
+    let mut rest = args[1..].iter();
+    while let Some(flag) = rest.next() {
+        match *flag {
+            "--sorted" => options.sorted = true,
+            "--format" => {
+                let name = rest.next().ok_or(usage)?;
+                options.format =
+                    Format::from_name(name).ok_or_else(|| format!("Unknown format '{}'", name))?;
+            }
+            _ => return Err(format!("Unknown option '{}'. {}", flag, usage)),
+        }
+    }
+    Ok((path, options))
+}
+
+/// Renders single values with a `value` column.
+pub fn values(mut values: Vec<i32>, options: &ExportOptions) -> String {
+    if options.sorted {
+        values.sort();
+    }
+    let rows: Vec<Vec<Cell>> = values.into_iter().map(|v| vec![Cell::Number(v)]).collect();
+    render(&["value"], &rows, options.format)
+}
+
+/// Renders key/value pairs with `key` and `value` columns.
+pub fn pairs(mut pairs: Vec<(String, i32)>, options: &ExportOptions) -> String {
+    if options.sorted {
+        pairs.sort();
+    }
+    let rows: Vec<Vec<Cell>> = pairs
+        .into_iter()
+        .map(|(k, v)| vec![Cell::Text(k), Cell::Number(v)])
+        .collect();
+    render(&["key", "value"], &rows, options.format)
+}
+
+pub fn write(path: impl AsRef<Path>, contents: &str) -> io::Result<()> {
+    fs::write(path, contents)
+}
+
+enum Cell {
+    Text(String),
+    Number(i32),
+}
+
+impl Cell {
+    fn plain(&self) -> String {
+        match self {
+            Cell::Text(s) => s.clone(),
+            Cell::Number(n) => n.to_string(),
+        }
+    }
+
+    fn json(&self) -> String {
+        match self {
+            Cell::Text(s) => json::quote(s),
+            Cell::Number(n) => n.to_string(),
+        }
+    }
+}
+
+fn render(columns: &[&str], rows: &[Vec<Cell>], format: Format) -> String {
+    match format {
+        Format::Csv => {
+            let mut out = columns.join(",");
+            out.push('\n');
+            for row in rows {
+                let cells: Vec<String> = row.iter().map(|c| csv_field(&c.plain())).collect();
+                out.push_str(&cells.join(","));
+                out.push('\n');
+            }
+            out
+        }
+        Format::Json | Format::JsonLines => {
+            let objects: Vec<String> = rows
+                .iter()
+                .map(|row| {
+                    let fields: Vec<String> = columns
+                        .iter()
+                        .zip(row)
+                        .map(|(name, cell)| format!("{}:{}", json::quote(name), cell.json()))
+                        .collect();
+                    format!("{{{}}}", fields.join(","))
+                })
+                .collect();
+            if format == Format::JsonLines {
+                objects.iter().map(|o| format!("{}\n", o)).collect()
+            } else if objects.is_empty() {
+                String::from("[]\n")
+            } else {
+                format!("[\n  {}\n]\n", objects.join(",\n  "))
+            }
+        }
+        Format::Markdown => {
+            let mut out = format!("| {} |\n", columns.join(" | "));
+            let rules: Vec<&str> = rows
+                .first()
+                .map(|row| {
+                    row.iter()
+                        .map(|c| match c {
+                            Cell::Number(_) => "---:",
+                            Cell::Text(_) => "---",
+                        })
+                        .collect()
+                })
+                .unwrap_or_else(|| columns.iter().map(|_| "---").collect());
+            out.push_str(&format!("| {} |\n", rules.join(" | ")));
+            for row in rows {
+                let cells: Vec<String> = row.iter().map(|c| markdown_cell(&c.plain())).collect();
+                out.push_str(&format!("| {} |\n", cells.join(" | ")));
+            }
+            out
+        }
+    }
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn markdown_cell(s: &str) -> String {
+    s.replace('|', "\\|").replace('\n', " ")
+}
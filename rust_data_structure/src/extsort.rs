@@ -0,0 +1,395 @@
+//! External merge sort for inputs larger than memory.
+//!
+//! Records (lines, or CSV rows, which may span lines inside quoted fields)
+//! are buffered until the memory budget is reached, sorted and spilled to a
+//! temporary run file. Once the input is exhausted the runs are
+//! k-way merged through a [`BinaryHeap`](std::collections::BinaryHeap) (see
+//! [`stream::merge_sorted`]). At most [`SortOptions::fan_in`] runs are open
+//! at once; with more, neighbouring runs are merged into longer ones in
+//! passes until few enough remain. Input that fits in the budget is sorted in
+//! memory without touching the disk. The sort is stable: rows with equal
+//! keys keep their input order.
+
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::import::{split_csv_line, Field};
+use crate::stream;
+
+/// Rough per-line bookkeeping cost added to the line length when measuring
+/// the buffer against the memory budget.
+const LINE_OVERHEAD: usize = 48;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// One record per line; fields are separated by whitespace.
+    Lines,
+    /// CSV rows, optionally after a header row that is copied to the output
+    /// unsorted. Quoted fields may contain line breaks.
+    Csv { header: bool },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortOptions {
+    pub format: Format,
+    /// Bytes of input to buffer before spilling a sorted run.
+    pub memory_budget: usize,
+    /// Where run files are written; they are removed when the sort ends.
+    pub temp_dir: PathBuf,
+    /// Most run files merged (and so held open) at once; at least 2.
+    pub fan_in: usize,
+}
+
+impl SortOptions {
+    pub fn new(format: Format) -> Self {
+        SortOptions {
+            format,
+            memory_budget: 64 * 1024 * 1024,
+            temp_dir: std::env::temp_dir(),
+            fan_in: 64,
+        }
+    }
+}
+
+/// A sort key read from one field; numbers sort before text.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SortKey {
+    Number(i64),
+    Text(String),
+}
+
+/// Which part of a row to sort by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeySpec {
+    /// Field index, or `None` for the whole line.
+    pub field: Option<usize>,
+    /// Compare as integers where the field parses as one.
+    pub numeric: bool,
+}
+
+impl KeySpec {
+    /// Extracts the key of `line`. A missing field sorts as empty text.
+    pub fn extract(&self, line: &str, format: Format) -> SortKey {
+        let text = match (self.field, format) {
+            (None, _) => line.to_string(),
+            (Some(i), Format::Lines) => line.split_whitespace().nth(i).unwrap_or("").to_string(),
+            (Some(i), Format::Csv { .. }) => split_csv_line(line)
+                .ok()
+                .and_then(|mut cells| (i < cells.len()).then(|| cells.swap_remove(i)))
+                .unwrap_or_default(),
+        };
+        match text.trim().parse::<i64>() {
+            Ok(n) if self.numeric => SortKey::Number(n),
+            _ => SortKey::Text(text),
+        }
+    }
+}
+
+/// Resolves a field to an index, looking names up in the CSV `header`.
+pub fn resolve_field(field: &Field, header: Option<&str>) -> Result<usize, String> {
+    match field {
+        Field::Index(i) => Ok(*i),
+        Field::Name(name) => header
+            .and_then(|h| split_csv_line(h).ok())
+            .and_then(|cells| cells.iter().position(|c| c.trim() == name))
+            .ok_or_else(|| format!("no column named '{}'", name)),
+    }
+}
+
+/// What a sort did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortStats {
+    /// Rows sorted, not counting a CSV header.
+    pub rows: usize,
+    /// Sorted runs spilled to disk; zero when everything fit in memory.
+    pub runs: usize,
+}
+
+/// Sorts the records of `input` by `key` into `output`. A CSV quote left
+/// open at the end of input is an [`io::ErrorKind::InvalidData`] error.
+pub fn sort<K, F>(
+    input: impl BufRead,
+    mut output: impl Write,
+    options: &SortOptions,
+    mut key: F,
+) -> io::Result<SortStats>
+where
+    K: Ord,
+    F: FnMut(&str) -> K,
+{
+    let mut lines = Records::new(input, options.format);
+    if let Format::Csv { header: true } = options.format {
+        if let Some(header) = lines.next() {
+            writeln!(output, "{}", header?)?;
+        }
+    }
+
+    let mut runs = Runs::new(&options.temp_dir);
+    let mut buffer: Vec<String> = Vec::new();
+    let mut buffered = 0;
+    let mut rows = 0;
+    let mut spilled = 0;
+    for line in lines {
+        let line = line?;
+        rows += 1;
+        buffered += line.len() + LINE_OVERHEAD;
+        buffer.push(line);
+        if buffered >= options.memory_budget {
+            buffer.sort_by_cached_key(|l| key(l));
+            runs.spill(&buffer)?;
+            buffer.clear();
+            buffered = 0;
+        }
+    }
+    buffer.sort_by_cached_key(|l| key(l));
+
+    if runs.paths.is_empty() {
+        for line in &buffer {
+            writeln!(output, "{}", line)?;
+        }
+    } else {
+        if !buffer.is_empty() {
+            runs.spill(&buffer)?;
+        }
+        drop(buffer);
+        spilled = runs.paths.len();
+        // Merging neighbours keeps equal keys in input order.
+        let fan_in = options.fan_in.max(2);
+        let mut level = runs.paths.clone();
+        while level.len() > fan_in {
+            let mut next = Vec::new();
+            for group in level.chunks(fan_in) {
+                if let [single] = group {
+                    next.push(single.clone());
+                    continue;
+                }
+                let (path, mut writer) = runs.create()?;
+                merge_runs(group, options.format, &mut key, &mut writer)?;
+                writer.flush()?;
+                for merged in group {
+                    let _ = fs::remove_file(merged);
+                }
+                next.push(path);
+            }
+            level = next;
+        }
+        merge_runs(&level, options.format, &mut key, &mut output)?;
+    }
+    output.flush()?;
+    Ok(SortStats {
+        rows,
+        runs: spilled,
+    })
+}
+
+fn merge_runs<K: Ord>(
+    paths: &[PathBuf],
+    format: Format,
+    key: &mut impl FnMut(&str) -> K,
+    output: &mut impl Write,
+) -> io::Result<()> {
+    let readers = paths
+        .iter()
+        .map(|path| Ok(Records::new(BufReader::new(File::open(path)?), format)))
+        .collect::<io::Result<Vec<_>>>()?;
+    // Read errors have no key and therefore surface first.
+    for line in stream::merge_sorted(readers, |line| line.as_deref().ok().map(&mut *key)) {
+        writeln!(output, "{}", line?)?;
+    }
+    Ok(())
+}
+
+/// Splits input into records: one per line, except that a CSV record goes
+/// on until its quoted fields are closed. Line breaks inside quotes are
+/// kept as they are; the one ending the record is dropped, like
+/// [`BufRead::lines`] does.
+struct Records<R> {
+    input: R,
+    csv: bool,
+}
+
+impl<R: BufRead> Records<R> {
+    fn new(input: R, format: Format) -> Self {
+        Records {
+            input,
+            csv: matches!(format, Format::Csv { .. }),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Records<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<io::Result<String>> {
+        let mut record = String::new();
+        loop {
+            match self.input.read_line(&mut record) {
+                Err(e) => return Some(Err(e)),
+                Ok(0) if record.is_empty() => return None,
+                Ok(0) => {
+                    return Some(Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "unterminated quoted field at end of input",
+                    )))
+                }
+                Ok(_) => {}
+            }
+            // The only error split_csv_line reports is an open quote.
+            if !self.csv || split_csv_line(&record).is_ok() {
+                break;
+            }
+        }
+        if record.ends_with('\n') {
+            record.pop();
+            if record.ends_with('\r') {
+                record.pop();
+            }
+        }
+        Some(Ok(record))
+    }
+}
+
+/// Sorts the file at `input` into the file at `output`.
+pub fn sort_file<K, F>(
+    input: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+    options: &SortOptions,
+    key: F,
+) -> io::Result<SortStats>
+where
+    K: Ord,
+    F: FnMut(&str) -> K,
+{
+    let reader = BufReader::new(File::open(input)?);
+    let writer = BufWriter::new(File::create(output)?);
+    sort(reader, writer, options, key)
+}
+
+/// Run files of one sort, deleted when dropped.
+struct Runs {
+    dir: PathBuf,
+    paths: Vec<PathBuf>,
+}
+
+impl Runs {
+    fn new(dir: &Path) -> Self {
+        Runs {
+            dir: dir.to_path_buf(),
+            paths: Vec::new(),
+        }
+    }
+
+    /// A new run file, removed with the others on drop.
+    fn create(&mut self) -> io::Result<(PathBuf, BufWriter<File>)> {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let name = format!(
+            "extsort-{}-{}.run",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        );
+        let path = self.dir.join(name);
+        self.paths.push(path.clone());
+        let writer = BufWriter::new(File::create(&path)?);
+        Ok((path, writer))
+    }
+
+    fn spill(&mut self, lines: &[String]) -> io::Result<()> {
+        let (_, mut writer) = self.create()?;
+        for line in lines {
+            writeln!(writer, "{}", line)?;
+        }
+        writer.flush()
+    }
+}
+
+impl Drop for Runs {
+    fn drop(&mut self) {
+        for path in &self.paths {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// Arguments of the `sort` subcommand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortArgs {
+    pub input: String,
+    /// Output file, or `None` for standard output.
+    pub output: Option<String>,
+    pub options: SortOptions,
+    pub key: Option<Field>,
+    pub numeric: bool,
+    pub reverse: bool,
+}
+
+/// Parses `<input> [-o <output>] [--csv] [--no-header] [--key <field>]
+/// [--numeric] [--reverse] [--memory <size>] [--temp-dir <dir>]`. Sizes
+/// accept `K`, `M` and `G` suffixes.
+pub fn parse_args(args: &[&str]) -> Result<SortArgs, String> {
+    let usage = "Usage: sort <input> [-o <output>] [--csv] [--no-header] [--key <field>] [--numeric] [--reverse] [--memory <size>] [--temp-dir <dir>]";
+    let input = args.first().ok_or(usage)?.to_string();
+    let mut parsed = SortArgs {
+        options: SortOptions::new(if input.ends_with(".csv") {
+            Format::Csv { header: true }
+        } else {
+            Format::Lines
+        }),
+        input,
+        output: None,
+        key: None,
+        numeric: false,
+        reverse: false,
+    };
+
+    let mut rest = args[1..].iter();
+    while let Some(flag) = rest.next() {
+        match *flag {
+            // Keeps the header choice of an earlier --no-header.
+            "--csv" => {
+                if parsed.options.format == Format::Lines {
+                    parsed.options.format = Format::Csv { header: true }
+                }
+            }
+            "--no-header" => parsed.options.format = Format::Csv { header: false },
+            "--numeric" => parsed.numeric = true,
+            "--reverse" => parsed.reverse = true,
+            _ => {
+                let arg = rest
+                    .next()
+                    .ok_or_else(|| format!("{} needs a value. {}", flag, usage))?;
+                match *flag {
+                    "-o" | "--output" => parsed.output = Some(arg.to_string()),
+                    "--key" => parsed.key = Some(Field::parse(arg)),
+                    "--memory" => {
+                        parsed.options.memory_budget =
+                            parse_size(arg).ok_or_else(|| format!("Invalid size '{}'", arg))?
+                    }
+                    "--temp-dir" => parsed.options.temp_dir = PathBuf::from(arg),
+                    _ => return Err(format!("Unknown option '{}'. {}", flag, usage)),
+                }
+            }
+        }
+    }
+    Ok(parsed)
+}
+
+fn parse_size(s: &str) -> Option<usize> {
+    let (digits, unit) = match s.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+        Some((i, _)) => s.split_at(i),
+        None => (s, ""),
+    };
+    let scale = match unit.to_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" => 1 << 10,
+        "M" | "MB" => 1 << 20,
+        "G" | "GB" => 1 << 30,
+        _ => return None,
+    };
+    digits
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| n.checked_mul(scale))
+        .filter(|&n| n > 0)
+}
@@ -0,0 +1,444 @@
+//! Probabilistic membership filters: Bloom and cuckoo.
+//!
+//! Both answer "definitely absent" or "probably present" in a fraction of
+//! the memory an exact set needs. A [`BloomFilter`] is sized from the
+//! expected number of items and a target false-positive rate and cannot
+//! forget items; a [`CuckooFilter`] stores small fingerprints in buckets and
+//! supports deletion.
+//!
+//! Items are byte strings and are hashed with FNV-1a followed by a
+//! SplitMix64 finaliser, both fixed here, so a filter saved by one process
+//! answers the same way when loaded by another.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const BLOOM_MAGIC: &[u8; 4] = b"BLM1";
+const CUCKOO_MAGIC: &[u8; 4] = b"CKF1";
+const BUCKET_SIZE: usize = 4;
+const MAX_KICKS: usize = 500;
+
+/// Membership queries shared by both filters.
+pub trait Membership {
+    fn contains(&self, item: &[u8]) -> bool;
+}
+
+/// Fraction of `absent` items the filter claims to contain. Every probe
+/// must be an item that was never inserted.
+pub fn false_positive_rate<F, I, T>(filter: &F, absent: I) -> f64
+where
+    F: Membership,
+    I: IntoIterator<Item = T>,
+    T: AsRef<[u8]>,
+{
+    let (mut probes, mut hits) = (0usize, 0usize);
+    for item in absent {
+        probes += 1;
+        if filter.contains(item.as_ref()) {
+            hits += 1;
+        }
+    }
+    if probes == 0 {
+        0.0
+    } else {
+        hits as f64 / probes as f64
+    }
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for &b in bytes {
+        hash ^= u64::from(b);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+fn mix(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Stable across processes and platforms, unlike `DefaultHasher`.
+pub(crate) fn hash(item: &[u8]) -> u64 {
+    mix(fnv1a(item))
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Reads little-endian integers off the front of a byte slice.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl Reader<'_> {
+    fn magic(&mut self, magic: &[u8; 4]) -> io::Result<()> {
+        if self.take(4)? == magic {
+            Ok(())
+        } else {
+            Err(invalid("not a filter of this kind"))
+        }
+    }
+
+    fn take(&mut self, n: usize) -> io::Result<&[u8]> {
+        if self.bytes.len() < n {
+            return Err(invalid("truncated filter"));
+        }
+        let (head, tail) = self.bytes.split_at(n);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        let bytes = self.take(8)?;
+        Ok(u64::from_le_bytes(bytes.try_into().expect("took 8 bytes")))
+    }
+
+    fn u16(&mut self) -> io::Result<u16> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes(bytes.try_into().expect("took 2 bytes")))
+    }
+
+    /// Checks that exactly `len` bytes are left before anything is sized
+    /// from a header, so a corrupt length fails instead of allocating.
+    fn expect_remaining(&self, len: Option<usize>) -> io::Result<()> {
+        match len {
+            Some(len) if len == self.bytes.len() => Ok(()),
+            Some(len) if len > self.bytes.len() => Err(invalid("truncated filter")),
+            Some(_) => Err(invalid("trailing bytes after filter")),
+            None => Err(invalid("filter size overflows")),
+        }
+    }
+
+    fn finish(&self) -> io::Result<()> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(invalid("trailing bytes after filter"))
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    num_bits: u64,
+    hashes: u32,
+    items: u64,
+}
+
+impl BloomFilter {
+    /// Sizes a filter so that after `expected` insertions the false-positive
+    /// rate is about `fp_rate`. Panics unless `0 < fp_rate < 1`.
+    pub fn with_rate(expected: usize, fp_rate: f64) -> Self {
+        assert!(
+            fp_rate > 0.0 && fp_rate < 1.0,
+            "false-positive rate must be between 0 and 1"
+        );
+        let n = expected.max(1) as f64;
+        let ln2 = std::f64::consts::LN_2;
+        let num_bits = (-n * fp_rate.ln() / (ln2 * ln2)).ceil().max(64.0) as u64;
+        let hashes = ((num_bits as f64 / n) * ln2).round().clamp(1.0, 32.0) as u32;
+        BloomFilter::with_size(num_bits, hashes)
+    }
+
+    /// A filter of exactly `num_bits` bits probed by `hashes` hash functions.
+    pub fn with_size(num_bits: u64, hashes: u32) -> Self {
+        let num_bits = num_bits.max(1);
+        BloomFilter {
+            bits: vec![0; num_bits.div_ceil(64) as usize],
+            num_bits,
+            hashes: hashes.max(1),
+            items: 0,
+        }
+    }
+
+    pub fn num_bits(&self) -> u64 {
+        self.num_bits
+    }
+
+    pub fn hashes(&self) -> u32 {
+        self.hashes
+    }
+
+    /// Insertions so far, counting repeats.
+    pub fn items(&self) -> u64 {
+        self.items
+    }
+
+    pub fn insert(&mut self, item: &[u8]) {
+        for bit in self.positions(item) {
+            self.bits[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+        self.items += 1;
+    }
+
+    /// Expected false-positive rate for the current number of insertions.
+    pub fn estimated_fp_rate(&self) -> f64 {
+        let k = f64::from(self.hashes);
+        let fill = 1.0 - (-k * self.items as f64 / self.num_bits as f64).exp();
+        fill.powf(k)
+    }
+
+    /// Double hashing: probe `h1 + i * h2` for each of the hash functions.
+    fn positions(&self, item: &[u8]) -> impl Iterator<Item = u64> {
+        let h1 = hash(item);
+        let h2 = mix(h1) | 1;
+        let m = self.num_bits;
+        (0..u64::from(self.hashes)).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % m)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = BLOOM_MAGIC.to_vec();
+        out.extend(self.num_bits.to_le_bytes());
+        out.extend(u64::from(self.hashes).to_le_bytes());
+        out.extend(self.items.to_le_bytes());
+        for word in &self.bits {
+            out.extend(word.to_le_bytes());
+        }
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+        let mut reader = Reader { bytes };
+        reader.magic(BLOOM_MAGIC)?;
+        let num_bits = reader.u64()?;
+        let hashes = reader.u64()?;
+        let items = reader.u64()?;
+        if num_bits == 0 || hashes == 0 || hashes > 32 {
+            return Err(invalid("bad Bloom filter parameters"));
+        }
+        let words = usize::try_from(num_bits.div_ceil(64)).ok();
+        reader.expect_remaining(words.and_then(|w| w.checked_mul(8)))?;
+        let mut filter = BloomFilter::with_size(num_bits, hashes as u32);
+        filter.items = items;
+        for word in filter.bits.iter_mut() {
+            *word = reader.u64()?;
+        }
+        reader.finish()?;
+        Ok(filter)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_bytes())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        BloomFilter::from_bytes(&fs::read(path)?)
+    }
+}
+
+impl Membership for BloomFilter {
+    fn contains(&self, item: &[u8]) -> bool {
+        self.positions(item)
+            .all(|bit| self.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0)
+    }
+}
+
+/// Why a cuckoo insertion failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FilterFull;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CuckooFilter {
+    /// `BUCKET_SIZE` fingerprints per bucket; 0 marks an empty slot.
+    slots: Vec<u16>,
+    num_buckets: usize,
+    items: u64,
+    /// A fingerprint evicted by a failed insertion, kept so no item is lost.
+    victim: Option<(usize, u16)>,
+    /// State of the generator that picks which fingerprint to kick out.
+    rng: u64,
+}
+
+impl CuckooFilter {
+    /// A filter with room for about `capacity` items at 95% load.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let wanted = (capacity as f64 / BUCKET_SIZE as f64 / 0.95).ceil() as usize;
+        let num_buckets = wanted.max(1).next_power_of_two();
+        CuckooFilter {
+            slots: vec![0; num_buckets * BUCKET_SIZE],
+            num_buckets,
+            items: 0,
+            victim: None,
+            rng: 0x2545_f491_4f6c_dd1d,
+        }
+    }
+
+    /// Items currently stored.
+    pub fn len(&self) -> u64 {
+        self.items
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn load_factor(&self) -> f64 {
+        self.items as f64 / self.capacity() as f64
+    }
+
+    /// Adds `item`. Fails once the filter is too full to place it; the
+    /// filter stays correct for everything inserted before.
+    pub fn insert(&mut self, item: &[u8]) -> Result<(), FilterFull> {
+        if self.victim.is_some() {
+            return Err(FilterFull);
+        }
+        let (fp, i1, i2) = self.locate(item);
+        if self.put(i1, fp) || self.put(i2, fp) {
+            self.items += 1;
+            return Ok(());
+        }
+
+        let mut bucket = if self.next_random() & 1 == 0 { i1 } else { i2 };
+        let mut fp = fp;
+        for _ in 0..MAX_KICKS {
+            let slot = bucket * BUCKET_SIZE + (self.next_random() as usize % BUCKET_SIZE);
+            std::mem::swap(&mut fp, &mut self.slots[slot]);
+            bucket = self.alternate(bucket, fp);
+            if self.put(bucket, fp) {
+                self.items += 1;
+                return Ok(());
+            }
+        }
+        // The new item is in; the last displaced fingerprint waits aside.
+        self.victim = Some((bucket, fp));
+        self.items += 1;
+        Ok(())
+    }
+
+    /// Removes one copy of `item`. Deleting something that was never
+    /// inserted may remove another item sharing its fingerprint.
+    pub fn remove(&mut self, item: &[u8]) -> bool {
+        let (fp, i1, i2) = self.locate(item);
+        if self
+            .victim
+            .is_some_and(|(b, f)| f == fp && (b == i1 || b == i2))
+        {
+            self.victim = None;
+            self.items -= 1;
+            return true;
+        }
+        for bucket in [i1, i2] {
+            let range = bucket * BUCKET_SIZE..(bucket + 1) * BUCKET_SIZE;
+            if let Some(slot) = self.slots[range.clone()].iter().position(|&f| f == fp) {
+                self.slots[range.start + slot] = 0;
+                self.items -= 1;
+                self.reinsert_victim();
+                return true;
+            }
+        }
+        false
+    }
+
+    fn reinsert_victim(&mut self) {
+        if let Some((bucket, fp)) = self.victim.take() {
+            if !self.put(bucket, fp) && !self.put(self.alternate(bucket, fp), fp) {
+                self.victim = Some((bucket, fp));
+            }
+        }
+    }
+
+    /// Fingerprint and both candidate buckets of `item`.
+    fn locate(&self, item: &[u8]) -> (u16, usize, usize) {
+        let h = hash(item);
+        let fp = ((h >> 48) as u16).max(1);
+        let i1 = (h as usize) & (self.num_buckets - 1);
+        (fp, i1, self.alternate(i1, fp))
+    }
+
+    fn alternate(&self, bucket: usize, fp: u16) -> usize {
+        (bucket ^ mix(u64::from(fp)) as usize) & (self.num_buckets - 1)
+    }
+
+    fn put(&mut self, bucket: usize, fp: u16) -> bool {
+        let range = bucket * BUCKET_SIZE..(bucket + 1) * BUCKET_SIZE;
+        match self.slots[range.clone()].iter().position(|&f| f == 0) {
+            Some(slot) => {
+                self.slots[range.start + slot] = fp;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn next_random(&mut self) -> u64 {
+        self.rng = mix(self.rng);
+        self.rng
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = CUCKOO_MAGIC.to_vec();
+        out.extend((self.num_buckets as u64).to_le_bytes());
+        out.extend(self.items.to_le_bytes());
+        out.extend(self.rng.to_le_bytes());
+        let (bucket, fp) = self.victim.unwrap_or((0, 0));
+        out.extend((bucket as u64).to_le_bytes());
+        out.extend(fp.to_le_bytes());
+        for slot in &self.slots {
+            out.extend(slot.to_le_bytes());
+        }
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+        let mut reader = Reader { bytes };
+        reader.magic(CUCKOO_MAGIC)?;
+        let num_buckets = reader.u64()? as usize;
+        let items = reader.u64()?;
+        let rng = reader.u64()?;
+        let victim_bucket = reader.u64()? as usize;
+        let victim_fp = reader.u16()?;
+        if !num_buckets.is_power_of_two() || victim_bucket >= num_buckets {
+            return Err(invalid("bad cuckoo filter parameters"));
+        }
+        let num_slots = num_buckets.checked_mul(BUCKET_SIZE);
+        reader.expect_remaining(num_slots.and_then(|n| n.checked_mul(2)))?;
+        let num_slots = num_slots.expect("checked above");
+        let mut slots = Vec::with_capacity(num_slots);
+        for _ in 0..num_slots {
+            slots.push(reader.u16()?);
+        }
+        reader.finish()?;
+        let victim = (victim_fp != 0).then_some((victim_bucket, victim_fp));
+        let stored = slots.iter().filter(|&&fp| fp != 0).count() + usize::from(victim.is_some());
+        if items != stored as u64 {
+            return Err(invalid("cuckoo filter item count does not match its slots"));
+        }
+        Ok(CuckooFilter {
+            slots,
+            num_buckets,
+            items,
+            victim,
+            rng,
+        })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_bytes())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        CuckooFilter::from_bytes(&fs::read(path)?)
+    }
+}
+
+impl Membership for CuckooFilter {
+    fn contains(&self, item: &[u8]) -> bool {
+        let (fp, i1, i2) = self.locate(item);
+        self.victim
+            .is_some_and(|(b, f)| f == fp && (b == i1 || b == i2))
+            || [i1, i2].iter().any(|&bucket| {
+                self.slots[bucket * BUCKET_SIZE..(bucket + 1) * BUCKET_SIZE].contains(&fp)
+            })
+    }
+}
@@ -0,0 +1,322 @@
+//! An adjacency-list graph with named nodes and non-negative edge weights.
+//!
+//! Traversals visit neighbours in the order their edges were added, so
+//! results are reproducible. [`Graph::shortest_path`] is Dijkstra's
+//! algorithm with a [`BinaryHeap`] used as a min-priority queue.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::fmt;
+use std::fs;
+use std::io;
+
+use crate::import::split_csv_line;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GraphError {
+    UnknownNode(String),
+    /// Topological order requested for an undirected graph.
+    Undirected,
+    /// Topological order is impossible; holds the nodes left on cycles.
+    Cycle(Vec<String>),
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphError::UnknownNode(name) => write!(f, "no node named '{}'", name),
+            GraphError::Undirected => f.write_str("topological order needs a directed graph"),
+            GraphError::Cycle(nodes) => write!(f, "graph has a cycle through {}", nodes.join(", ")),
+        }
+    }
+}
+
+impl std::error::Error for GraphError {}
+
+/// A cheapest route and its total weight.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path {
+    pub nodes: Vec<String>,
+    pub cost: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Graph {
+    directed: bool,
+    names: Vec<String>,
+    index: HashMap<String, usize>,
+    /// Outgoing `(target, weight)` edges of each node. Undirected edges are
+    /// stored in both directions.
+    adjacency: Vec<Vec<(usize, u64)>>,
+    edges: usize,
+}
+
+impl Default for Graph {
+    /// An empty directed graph.
+    fn default() -> Self {
+        Graph::new(true)
+    }
+}
+
+impl Graph {
+    pub fn new(directed: bool) -> Self {
+        Graph {
+            directed,
+            names: Vec::new(),
+            index: HashMap::new(),
+            adjacency: Vec::new(),
+            edges: 0,
+        }
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges
+    }
+
+    /// Node names in the order they were added.
+    pub fn nodes(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Adds `name` if it is new and returns its id.
+    pub fn add_node(&mut self, name: &str) -> usize {
+        if let Some(&id) = self.index.get(name) {
+            return id;
+        }
+        self.names.push(name.to_string());
+        self.adjacency.push(Vec::new());
+        self.index.insert(name.to_string(), self.names.len() - 1);
+        self.names.len() - 1
+    }
+
+    /// Adds an edge, creating missing nodes. Parallel edges are allowed.
+    pub fn add_edge(&mut self, from: &str, to: &str, weight: u64) {
+        let a = self.add_node(from);
+        let b = self.add_node(to);
+        self.adjacency[a].push((b, weight));
+        if !self.directed && a != b {
+            self.adjacency[b].push((a, weight));
+        }
+        self.edges += 1;
+    }
+
+    /// Outgoing `(neighbour, weight)` edges of `name`.
+    pub fn neighbors(&self, name: &str) -> Result<Vec<(&str, u64)>, GraphError> {
+        let id = self.id(name)?;
+        Ok(self.adjacency[id]
+            .iter()
+            .map(|&(n, w)| (self.names[n].as_str(), w))
+            .collect())
+    }
+
+    /// Nodes reachable from `start`, nearest first.
+    pub fn bfs(&self, start: &str) -> Result<Vec<String>, GraphError> {
+        let start = self.id(start)?;
+        let mut seen = vec![false; self.node_count()];
+        let mut queue = VecDeque::from([start]);
+        seen[start] = true;
+        let mut order = Vec::new();
+        while let Some(node) = queue.pop_front() {
+            order.push(self.names[node].clone());
+            for &(next, _) in &self.adjacency[node] {
+                if !seen[next] {
+                    seen[next] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+        Ok(order)
+    }
+
+    /// Nodes reachable from `start` in depth-first preorder.
+    pub fn dfs(&self, start: &str) -> Result<Vec<String>, GraphError> {
+        let start = self.id(start)?;
+        let mut seen = vec![false; self.node_count()];
+        let mut stack = vec![start];
+        let mut order = Vec::new();
+        while let Some(node) = stack.pop() {
+            if seen[node] {
+                continue;
+            }
+            seen[node] = true;
+            order.push(self.names[node].clone());
+            // Reversed so the first-added neighbour is explored first.
+            for &(next, _) in self.adjacency[node].iter().rev() {
+                if !seen[next] {
+                    stack.push(next);
+                }
+            }
+        }
+        Ok(order)
+    }
+
+    /// Cheapest path from `from` to `to`, or `None` if `to` is unreachable.
+    pub fn shortest_path(&self, from: &str, to: &str) -> Result<Option<Path>, GraphError> {
+        let (start, goal) = (self.id(from)?, self.id(to)?);
+        let mut dist: Vec<Option<u64>> = vec![None; self.node_count()];
+        let mut prev: Vec<Option<usize>> = vec![None; self.node_count()];
+        let mut heap = BinaryHeap::new();
+        dist[start] = Some(0);
+        heap.push(Reverse((0, start)));
+
+        while let Some(Reverse((cost, node))) = heap.pop() {
+            if node == goal {
+                break;
+            }
+            if dist[node].is_some_and(|best| cost > best) {
+                continue;
+            }
+            for &(next, weight) in &self.adjacency[node] {
+                let candidate = cost.saturating_add(weight);
+                if dist[next].is_none_or(|best| candidate < best) {
+                    dist[next] = Some(candidate);
+                    prev[next] = Some(node);
+                    heap.push(Reverse((candidate, next)));
+                }
+            }
+        }
+
+        let Some(cost) = dist[goal] else {
+            return Ok(None);
+        };
+        let mut nodes = vec![self.names[goal].clone()];
+        let mut at = goal;
+        while let Some(p) = prev[at] {
+            nodes.push(self.names[p].clone());
+            at = p;
+        }
+        nodes.reverse();
+        Ok(Some(Path { nodes, cost }))
+    }
+
+    /// Kahn's algorithm; among ready nodes, earlier-added ones come first.
+    pub fn topological_sort(&self) -> Result<Vec<String>, GraphError> {
+        if !self.directed {
+            return Err(GraphError::Undirected);
+        }
+        let mut indegree = vec![0usize; self.node_count()];
+        for edges in &self.adjacency {
+            for &(next, _) in edges {
+                indegree[next] += 1;
+            }
+        }
+        let mut ready: BinaryHeap<Reverse<usize>> = (0..self.node_count())
+            .filter(|&n| indegree[n] == 0)
+            .map(Reverse)
+            .collect();
+        let mut order = Vec::new();
+        while let Some(Reverse(node)) = ready.pop() {
+            order.push(self.names[node].clone());
+            for &(next, _) in &self.adjacency[node] {
+                indegree[next] -= 1;
+                if indegree[next] == 0 {
+                    ready.push(Reverse(next));
+                }
+            }
+        }
+        if order.len() < self.node_count() {
+            let stuck = (0..self.node_count())
+                .filter(|&n| indegree[n] > 0)
+                .map(|n| self.names[n].clone())
+                .collect();
+            return Err(GraphError::Cycle(stuck));
+        }
+        Ok(order)
+    }
+
+    /// Connected components, ignoring edge direction. Each component lists
+    /// its nodes in insertion order; components are ordered by first node.
+    pub fn components(&self) -> Vec<Vec<String>> {
+        let mut undirected = vec![Vec::new(); self.node_count()];
+        for (a, edges) in self.adjacency.iter().enumerate() {
+            for &(b, _) in edges {
+                undirected[a].push(b);
+                undirected[b].push(a);
+            }
+        }
+        let mut component = vec![usize::MAX; self.node_count()];
+        let mut count = 0;
+        for start in 0..self.node_count() {
+            if component[start] != usize::MAX {
+                continue;
+            }
+            let mut stack = vec![start];
+            component[start] = count;
+            while let Some(node) = stack.pop() {
+                for &next in &undirected[node] {
+                    if component[next] == usize::MAX {
+                        component[next] = count;
+                        stack.push(next);
+                    }
+                }
+            }
+            count += 1;
+        }
+        let mut groups = vec![Vec::new(); count];
+        for (node, &c) in component.iter().enumerate() {
+            groups[c].push(self.names[node].clone());
+        }
+        groups
+    }
+
+    /// Parses an edge list: `from,to[,weight]` per line, weight defaulting
+    /// to 1. A first line whose weight is not a number, or that reads
+    /// `from,to`/`source,target`, is taken as a header.
+    pub fn from_edge_csv(text: &str, directed: bool) -> io::Result<Graph> {
+        let mut graph = Graph::new(directed);
+        for (i, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let cells = split_csv_line(line).map_err(|e| invalid(i + 1, &e))?;
+            let cells: Vec<&str> = cells.iter().map(|c| c.trim()).collect();
+            if cells.len() < 2 || cells.len() > 3 || cells[0].is_empty() || cells[1].is_empty() {
+                return Err(invalid(i + 1, "expected from,to[,weight]"));
+            }
+            let weight = match cells.get(2) {
+                None => Ok(1),
+                Some(w) => w.parse::<u64>(),
+            };
+            let header_names = matches!(
+                (
+                    cells[0].to_lowercase().as_str(),
+                    cells[1].to_lowercase().as_str()
+                ),
+                ("from", "to") | ("source", "target") | ("src", "dst")
+            );
+            match weight {
+                _ if i == 0 && header_names => continue,
+                Err(_) if i == 0 => continue,
+                Err(_) => return Err(invalid(i + 1, "weight must be a non-negative integer")),
+                Ok(weight) => graph.add_edge(cells[0], cells[1], weight),
+            }
+        }
+        Ok(graph)
+    }
+
+    pub fn load_edge_csv(path: impl AsRef<std::path::Path>, directed: bool) -> io::Result<Graph> {
+        Graph::from_edge_csv(&fs::read_to_string(path)?, directed)
+    }
+
+    fn id(&self, name: &str) -> Result<usize, GraphError> {
+        self.index
+            .get(name)
+            .copied()
+            .ok_or_else(|| GraphError::UnknownNode(name.to_string()))
+    }
+}
+
+fn invalid(line: usize, message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("line {}: {}", line, message),
+    )
+}
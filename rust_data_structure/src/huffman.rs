@@ -0,0 +1,250 @@
+//! Huffman coding of byte streams.
+//!
+//! The tree is built by repeatedly merging the two rarest subtrees popped
+//! from a [`BinaryHeap`] (wrapped in `Reverse`, since the heap is a max-heap).
+//! Only the resulting code lengths are kept: codes are reassigned
+//! canonically, so the header needs just one `(byte, length)` pair per
+//! symbol to rebuild the exact same codebook on decode.
+//!
+//! Encoded layout: magic `HUF1`, original length (u64 LE), symbol count
+//! (u16 LE), the `(byte, length)` pairs ordered by length then byte, and
+//! finally the code bits, most significant first, zero-padded to a byte.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const MAGIC: &[u8; 4] = b"HUF1";
+
+/// How often each byte value occurs in `data`.
+pub fn frequencies(data: &[u8]) -> [u64; 256] {
+    let mut counts = [0u64; 256];
+    for &b in data {
+        counts[b as usize] += 1;
+    }
+    counts
+}
+
+/// Canonical prefix codes for the bytes of one input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Codebook {
+    /// `(byte, code length)` ordered by length, then byte.
+    symbols: Vec<(u8, u8)>,
+    /// Code of each byte as `(bits, length)`; length 0 means unused.
+    codes: Vec<(u128, u8)>,
+}
+
+impl Codebook {
+    pub fn from_frequencies(counts: &[u64; 256]) -> Self {
+        let mut lengths = [0u8; 256];
+        // Leaves are nodes 0..256; merged nodes are appended after them.
+        // Ties on frequency fall back to the node id, so the tree is
+        // reproducible.
+        let mut parent: Vec<usize> = vec![usize::MAX; 256];
+        let mut heap = BinaryHeap::new();
+        for (byte, &count) in counts.iter().enumerate() {
+            if count > 0 {
+                heap.push(Reverse((count, byte)));
+            }
+        }
+        if heap.len() == 1 {
+            // A lone symbol still needs one bit per occurrence.
+            let Reverse((_, byte)) = heap.pop().expect("one symbol");
+            lengths[byte] = 1;
+        }
+        while heap.len() > 1 {
+            let Reverse((a_count, a)) = heap.pop().expect("two subtrees");
+            let Reverse((b_count, b)) = heap.pop().expect("two subtrees");
+            let merged = parent.len();
+            parent.push(usize::MAX);
+            parent[a] = merged;
+            parent[b] = merged;
+            heap.push(Reverse((a_count + b_count, merged)));
+        }
+        for (byte, length) in lengths.iter_mut().enumerate() {
+            if counts[byte] == 0 || *length > 0 {
+                continue;
+            }
+            let mut node = byte;
+            while parent[node] != usize::MAX {
+                node = parent[node];
+                *length += 1;
+            }
+        }
+        let symbols = (0..=255u8)
+            .filter(|&b| lengths[b as usize] > 0)
+            .map(|b| (b, lengths[b as usize]))
+            .collect();
+        Codebook::from_lengths(symbols)
+    }
+
+    pub fn for_data(data: &[u8]) -> Self {
+        Codebook::from_frequencies(&frequencies(data))
+    }
+
+    /// Assigns canonical codes: shorter codes first, counting upwards.
+    fn from_lengths(mut symbols: Vec<(u8, u8)>) -> Self {
+        symbols.sort_by_key(|&(byte, length)| (length, byte));
+        let mut codes = vec![(0u128, 0u8); 256];
+        let mut code = 0u128;
+        let mut previous = symbols.first().map_or(0, |&(_, length)| length);
+        for &(byte, length) in &symbols {
+            code <<= length - previous;
+            codes[byte as usize] = (code, length);
+            code += 1;
+            previous = length;
+        }
+        Codebook { symbols, codes }
+    }
+
+    /// `(byte, code length)` for every symbol, shortest codes first.
+    pub fn lengths(&self) -> &[(u8, u8)] {
+        &self.symbols
+    }
+
+    /// The code for `byte` as a bit string, e.g. `"101"`.
+    pub fn code(&self, byte: u8) -> Option<String> {
+        let (bits, length) = self.codes[byte as usize];
+        (length > 0).then(|| format!("{:0width$b}", bits, width = length as usize))
+    }
+
+    /// Total encoded size of `counts` in bits.
+    pub fn encoded_bits(&self, counts: &[u64; 256]) -> u64 {
+        self.symbols
+            .iter()
+            .map(|&(byte, length)| counts[byte as usize] * u64::from(length))
+            .sum()
+    }
+}
+
+/// Sizes before and after compression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub original: u64,
+    pub compressed: u64,
+}
+
+impl Stats {
+    /// Compressed size as a fraction of the original (lower is better).
+    pub fn ratio(&self) -> f64 {
+        if self.original == 0 {
+            return 1.0;
+        }
+        self.compressed as f64 / self.original as f64
+    }
+}
+
+pub fn encode(data: &[u8]) -> Vec<u8> {
+    let book = Codebook::for_data(data);
+    let mut out = MAGIC.to_vec();
+    out.extend((data.len() as u64).to_le_bytes());
+    out.extend((book.symbols.len() as u16).to_le_bytes());
+    for &(byte, length) in &book.symbols {
+        out.extend([byte, length]);
+    }
+
+    let mut buffer = 0u8;
+    let mut filled = 0;
+    for &b in data {
+        let (bits, length) = book.codes[b as usize];
+        for shift in (0..length).rev() {
+            buffer = (buffer << 1) | ((bits >> shift) & 1) as u8;
+            filled += 1;
+            if filled == 8 {
+                out.push(buffer);
+                buffer = 0;
+                filled = 0;
+            }
+        }
+    }
+    if filled > 0 {
+        out.push(buffer << (8 - filled));
+    }
+    out
+}
+
+pub fn decode(bytes: &[u8]) -> io::Result<Vec<u8>> {
+    if bytes.len() < 14 || &bytes[..4] != MAGIC {
+        return Err(invalid("not Huffman-encoded data"));
+    }
+    let original = u64::from_le_bytes(bytes[4..12].try_into().expect("8 bytes"));
+    let count = u16::from_le_bytes(bytes[12..14].try_into().expect("2 bytes")) as usize;
+    let table_end = 14 + 2 * count;
+    if count > 256 || bytes.len() < table_end {
+        return Err(invalid("truncated symbol table"));
+    }
+    let symbols: Vec<(u8, u8)> = bytes[14..table_end]
+        .chunks(2)
+        .map(|pair| (pair[0], pair[1]))
+        .collect();
+    if symbols
+        .iter()
+        .any(|&(_, length)| length == 0 || length > 127)
+    {
+        return Err(invalid("bad code length"));
+    }
+    let book = Codebook::from_lengths(symbols);
+    if original > 0 && book.symbols.is_empty() {
+        return Err(invalid("empty symbol table"));
+    }
+
+    // How many codes of each length exist, for canonical decoding.
+    let max_length = book.symbols.last().map_or(0, |&(_, length)| length) as usize;
+    let mut per_length = vec![0u128; max_length + 1];
+    for &(_, length) in &book.symbols {
+        per_length[length as usize] += 1;
+    }
+
+    let mut out = Vec::with_capacity(original.min(1 << 24) as usize);
+    let mut bits = bytes[table_end..]
+        .iter()
+        .flat_map(|&b| (0..8).rev().map(move |i| (b >> i) & 1));
+    while (out.len() as u64) < original {
+        let (mut code, mut first, mut index) = (0u128, 0u128, 0usize);
+        let mut length = 0;
+        loop {
+            length += 1;
+            if length > max_length {
+                return Err(invalid("invalid code in bit stream"));
+            }
+            let bit = bits.next().ok_or_else(|| invalid("truncated bit stream"))?;
+            code |= u128::from(bit);
+            if code >= first && code - first < per_length[length] {
+                out.push(book.symbols[index + (code - first) as usize].0);
+                break;
+            }
+            index += per_length[length] as usize;
+            first = (first + per_length[length]) << 1;
+            code <<= 1;
+        }
+    }
+    Ok(out)
+}
+
+/// Compresses `input` into `output`, returning the file sizes.
+pub fn compress_file(input: impl AsRef<Path>, output: impl AsRef<Path>) -> io::Result<Stats> {
+    let data = fs::read(input)?;
+    let encoded = encode(&data);
+    fs::write(output, &encoded)?;
+    Ok(Stats {
+        original: data.len() as u64,
+        compressed: encoded.len() as u64,
+    })
+}
+
+/// Restores a file written by [`compress_file`], returning the file sizes.
+pub fn decompress_file(input: impl AsRef<Path>, output: impl AsRef<Path>) -> io::Result<Stats> {
+    let encoded = fs::read(input)?;
+    let data = decode(&encoded)?;
+    fs::write(output, &data)?;
+    Ok(Stats {
+        original: data.len() as u64,
+        compressed: encoded.len() as u64,
+    })
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
@@ -0,0 +1,321 @@
+//! Bulk loading for the editors' `import` command.
+//!
+//! Values come from a CSV column, a JSON-lines field or one value per line
+//! of plain text. Maps read a key and a value from the same row. Every line
+//! is counted as loaded, skipped (blank, comment or missing field) or
+//! malformed (unparseable row or value).
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::json::{self, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    JsonLines,
+    Text,
+}
+
+impl Format {
+    pub fn from_name(name: &str) -> Option<Format> {
+        match name.to_lowercase().as_str() {
+            "csv" => Some(Format::Csv),
+            "jsonl" | "json" | "ndjson" => Some(Format::JsonLines),
+            "text" | "txt" => Some(Format::Text),
+            _ => None,
+        }
+    }
+
+    /// Guesses the format from the file extension, defaulting to text.
+    pub fn from_path(path: &str) -> Format {
+        Path::new(path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Format::from_name)
+            .unwrap_or(Format::Text)
+    }
+}
+
+/// A CSV column or JSON field, by position or by name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Field {
+    Index(usize),
+    Name(String),
+}
+
+impl Field {
+    pub fn parse(s: &str) -> Field {
+        match s.parse::<usize>() {
+            Ok(i) => Field::Index(i),
+            Err(_) => Field::Name(s.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportOptions {
+    pub format: Format,
+    /// Column or field holding the values (default: the first one).
+    pub column: Field,
+    /// Column or field holding map keys (default: the first one).
+    pub key: Field,
+    /// Column or field holding map values (default: the second one).
+    pub value: Field,
+    /// Whether the first CSV line is a header row.
+    pub header: bool,
+}
+
+impl ImportOptions {
+    pub fn new(format: Format) -> Self {
+        ImportOptions {
+            format,
+            column: Field::Index(0),
+            key: Field::Index(0),
+            value: Field::Index(1),
+            header: true,
+        }
+    }
+}
+
+/// Parses `<path> [--format csv|jsonl|text] [--column c] [--key c]
+/// [--value c] [--no-header]`, the arguments of the `import` command.
+pub fn parse_args(args: &[&str]) -> Result<(String, ImportOptions), String> {
+    let usage = "Usage: import <path> [--format csv|jsonl|text] [--column c] [--key c] [--value c] [--no-header]";
+    let path = args.first().ok_or(usage)?.to_string();
+    let mut options = ImportOptions::new(Format::from_path(&path));
+
+    let mut rest = args[1..].iter();
+    while let Some(flag) = rest.next() {
+        if *flag == "--no-header" {
+            options.header = false;
+            continue;
+        }
+        let arg = rest
+            .next()
+            .ok_or_else(|| format!("{} needs a value. {}", flag, usage))?;
+        match *flag {
+            "--format" => {
+                options.format =
+                    Format::from_name(arg).ok_or_else(|| format!("Unknown format '{}'", arg))?
+            }
+            "--column" => options.column = Field::parse(arg),
+            "--key" => options.key = Field::parse(arg),
+            "--value" => options.value = Field::parse(arg),
+            _ => return Err(format!("Unknown option '{}'. {}", flag, usage)),
+        }
+    }
+    Ok((path, options))
+}
+
+/// What an import produced, with per-line accounting.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report<T> {
+    pub records: Vec<T>,
+    pub loaded: usize,
+    pub skipped: usize,
+    pub malformed: usize,
+    /// One `line N: ...` message per malformed line.
+    pub problems: Vec<String>,
+}
+
+impl<T> Report<T> {
+    fn new() -> Self {
+        Report {
+            records: Vec::new(),
+            loaded: 0,
+            skipped: 0,
+            malformed: 0,
+            problems: Vec::new(),
+        }
+    }
+
+    fn malformed(&mut self, line: usize, message: String) {
+        self.malformed += 1;
+        self.problems.push(format!("line {}: {}", line, message));
+    }
+}
+
+/// Outcome of extracting the wanted cells from one line.
+enum Row {
+    Skip,
+    Bad(String),
+    Cells(Vec<String>),
+}
+
+/// Reads values from `text`.
+pub fn values(text: &str, options: &ImportOptions) -> Report<i32> {
+    let mut report = Report::new();
+    for (line, row) in rows(text, options, std::slice::from_ref(&options.column)) {
+        match row {
+            Row::Skip => report.skipped += 1,
+            Row::Bad(message) => report.malformed(line, message),
+            Row::Cells(cells) => match parse_number(&cells[0]) {
+                Ok(value) => {
+                    report.records.push(value);
+                    report.loaded += 1;
+                }
+                Err(message) => report.malformed(line, message),
+            },
+        }
+    }
+    report
+}
+
+/// Reads key/value pairs from `text`.
+pub fn pairs(text: &str, options: &ImportOptions) -> Report<(String, i32)> {
+    let mut report = Report::new();
+    let fields = [options.key.clone(), options.value.clone()];
+    for (line, row) in rows(text, options, &fields) {
+        match row {
+            Row::Skip => report.skipped += 1,
+            Row::Bad(message) => report.malformed(line, message),
+            Row::Cells(cells) => match parse_number(&cells[1]) {
+                Ok(value) => {
+                    report.records.push((cells[0].clone(), value));
+                    report.loaded += 1;
+                }
+                Err(message) => report.malformed(line, message),
+            },
+        }
+    }
+    report
+}
+
+pub fn load_values(path: impl AsRef<Path>, options: &ImportOptions) -> io::Result<Report<i32>> {
+    Ok(values(&fs::read_to_string(path)?, options))
+}
+
+pub fn load_pairs(
+    path: impl AsRef<Path>,
+    options: &ImportOptions,
+) -> io::Result<Report<(String, i32)>> {
+    Ok(pairs(&fs::read_to_string(path)?, options))
+}
+
+fn parse_number(cell: &str) -> Result<i32, String> {
+    cell.trim()
+        .parse::<i32>()
+        .map_err(|_| format!("invalid number '{}'", cell))
+}
+
+fn rows(text: &str, options: &ImportOptions, fields: &[Field]) -> Vec<(usize, Row)> {
+    let mut out = Vec::new();
+    let mut header: Option<Vec<String>> = None;
+    let mut expect_header = options.format == Format::Csv && options.header;
+
+    for (i, line) in text.lines().enumerate() {
+        let line_no = i + 1;
+        if line.trim().is_empty() {
+            out.push((line_no, Row::Skip));
+            continue;
+        }
+        let row = match options.format {
+            Format::Text => text_row(line, fields.len()),
+            Format::JsonLines => json_row(line, fields),
+            Format::Csv => {
+                let cells = match split_csv_line(line) {
+                    Ok(cells) => cells,
+                    Err(message) => {
+                        out.push((line_no, Row::Bad(message)));
+                        continue;
+                    }
+                };
+                if expect_header {
+                    expect_header = false;
+                    header = Some(cells);
+                    continue;
+                }
+                csv_row(cells, header.as_deref(), fields)
+            }
+        };
+        out.push((line_no, row));
+    }
+    out
+}
+
+fn text_row(line: &str, wanted: usize) -> Row {
+    let line = line.trim();
+    if line.starts_with('#') {
+        return Row::Skip;
+    }
+    if wanted == 1 {
+        return Row::Cells(vec![line.to_string()]);
+    }
+    let cells: Vec<String> = line.split_whitespace().map(String::from).collect();
+    if cells.len() == wanted {
+        Row::Cells(cells)
+    } else {
+        Row::Bad(format!("expected {} whitespace-separated values", wanted))
+    }
+}
+
+fn json_row(line: &str, fields: &[Field]) -> Row {
+    let entries = match json::parse(line) {
+        Ok(Value::Object(entries)) => entries,
+        Ok(_) => return Row::Bad(String::from("expected a JSON object")),
+        Err(message) => return Row::Bad(message),
+    };
+
+    let mut cells = Vec::new();
+    for field in fields {
+        let found = match field {
+            Field::Index(i) => entries.get(*i).map(|(_, v)| v),
+            Field::Name(name) => entries.iter().find(|(k, _)| k == name).map(|(_, v)| v),
+        };
+        match found {
+            None | Some(Value::Null) => return Row::Skip,
+            Some(Value::String(s)) => cells.push(s.clone()),
+            Some(Value::Number(n)) => cells.push(n.clone()),
+            Some(Value::Bool(b)) => cells.push(b.to_string()),
+            Some(_) => return Row::Bad(String::from("nested values are not supported")),
+        }
+    }
+    Row::Cells(cells)
+}
+
+fn csv_row(cells: Vec<String>, header: Option<&[String]>, fields: &[Field]) -> Row {
+    let mut out = Vec::new();
+    for field in fields {
+        let index = match field {
+            Field::Index(i) => *i,
+            Field::Name(name) => match header.and_then(|h| h.iter().position(|c| c == name)) {
+                Some(i) => i,
+                None => return Row::Bad(format!("no column named '{}'", name)),
+            },
+        };
+        match cells.get(index) {
+            Some(cell) if !cell.trim().is_empty() => out.push(cell.clone()),
+            Some(_) => return Row::Skip,
+            None => return Row::Bad(format!("row has no column {}", index)),
+        }
+    }
+    Row::Cells(out)
+}
+
+/// Splits one CSV line on commas, honouring double-quoted fields.
+pub fn split_csv_line(line: &str) -> Result<Vec<String>, String> {
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut chars = line.chars().peekable();
+    let mut quoted = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                cell.push('"');
+                chars.next();
+            }
+            '"' if quoted => quoted = false,
+            '"' if cell.is_empty() => quoted = true,
+            ',' if !quoted => cells.push(std::mem::take(&mut cell)),
+            c => cell.push(c),
+        }
+    }
+    if quoted {
+        return Err(String::from("unterminated quoted field"));
+    }
+    cells.push(cell);
+    Ok(cells)
+}
@@ -0,0 +1,245 @@
+//! Joins between two keyed collections.
+//!
+//! Both sides are lists of `(key, value)` rows and keys may repeat, so a key
+//! present `m` times on the left and `n` times on the right yields `m * n`
+//! matched rows. The hash join indexes the right side in a [`HashMap`] and
+//! keeps the left side's order; the sort-merge join groups both sides in
+//! [`BTreeMap`]s and emits rows in key order. Both produce the same rows.
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::hash::Hash;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinKind {
+    /// Matched rows only.
+    Inner,
+    /// Every left row, with its matches or alone.
+    Left,
+    /// Every right row, with its matches or alone.
+    Right,
+    /// Every row of either side.
+    Full,
+    /// Left rows without a match.
+    Anti,
+}
+
+impl JoinKind {
+    pub const ALL: [JoinKind; 5] = [
+        JoinKind::Inner,
+        JoinKind::Left,
+        JoinKind::Right,
+        JoinKind::Full,
+        JoinKind::Anti,
+    ];
+
+    pub fn from_name(name: &str) -> Option<JoinKind> {
+        match name.to_lowercase().as_str() {
+            "inner" => Some(JoinKind::Inner),
+            "left" => Some(JoinKind::Left),
+            "right" => Some(JoinKind::Right),
+            "full" | "outer" => Some(JoinKind::Full),
+            "anti" => Some(JoinKind::Anti),
+            _ => None,
+        }
+    }
+
+    fn keeps_matches(self) -> bool {
+        self != JoinKind::Anti
+    }
+
+    fn keeps_unmatched_left(self) -> bool {
+        matches!(self, JoinKind::Left | JoinKind::Full | JoinKind::Anti)
+    }
+
+    fn keeps_unmatched_right(self) -> bool {
+        matches!(self, JoinKind::Right | JoinKind::Full)
+    }
+}
+
+impl fmt::Display for JoinKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            JoinKind::Inner => "inner",
+            JoinKind::Left => "left",
+            JoinKind::Right => "right",
+            JoinKind::Full => "full",
+            JoinKind::Anti => "anti",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    Hash,
+    SortMerge,
+}
+
+/// One output row; a side is `None` where the key had no match there.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct JoinRow<K, A, B> {
+    pub key: K,
+    pub left: Option<A>,
+    pub right: Option<B>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JoinResult<K, A, B> {
+    pub rows: Vec<JoinRow<K, A, B>>,
+    /// Matched left/right pairs, whatever the join kind.
+    pub matches: usize,
+    /// Distinct left keys missing on the right, sorted.
+    pub unmatched_left: Vec<K>,
+    /// Distinct right keys missing on the left, sorted.
+    pub unmatched_right: Vec<K>,
+}
+
+pub fn join<K, A, B>(
+    left: &[(K, A)],
+    right: &[(K, B)],
+    kind: JoinKind,
+    strategy: Strategy,
+) -> JoinResult<K, A, B>
+where
+    K: Ord + Hash + Clone,
+    A: Clone,
+    B: Clone,
+{
+    match strategy {
+        Strategy::Hash => hash_join(left, right, kind),
+        Strategy::SortMerge => sort_merge_join(left, right, kind),
+    }
+}
+
+pub fn hash_join<K, A, B>(left: &[(K, A)], right: &[(K, B)], kind: JoinKind) -> JoinResult<K, A, B>
+where
+    K: Ord + Hash + Clone,
+    A: Clone,
+    B: Clone,
+{
+    let mut index: HashMap<&K, Vec<&B>> = HashMap::new();
+    for (k, b) in right {
+        index.entry(k).or_default().push(b);
+    }
+
+    let mut rows = Vec::new();
+    let mut matches = 0;
+    let mut unmatched_left = BTreeSet::new();
+    let left_keys: HashSet<&K> = left.iter().map(|(k, _)| k).collect();
+    for (k, a) in left {
+        match index.get(k) {
+            Some(found) => {
+                matches += found.len();
+                if kind.keeps_matches() {
+                    rows.extend(found.iter().map(|b| row(k, Some(a), Some(*b))));
+                }
+            }
+            None => {
+                unmatched_left.insert(k.clone());
+                if kind.keeps_unmatched_left() {
+                    rows.push(row(k, Some(a), None));
+                }
+            }
+        }
+    }
+
+    let mut unmatched_right = BTreeSet::new();
+    for (k, b) in right {
+        if !left_keys.contains(k) {
+            unmatched_right.insert(k.clone());
+            if kind.keeps_unmatched_right() {
+                rows.push(row(k, None, Some(b)));
+            }
+        }
+    }
+
+    JoinResult {
+        rows,
+        matches,
+        unmatched_left: unmatched_left.into_iter().collect(),
+        unmatched_right: unmatched_right.into_iter().collect(),
+    }
+}
+
+pub fn sort_merge_join<K, A, B>(
+    left: &[(K, A)],
+    right: &[(K, B)],
+    kind: JoinKind,
+) -> JoinResult<K, A, B>
+where
+    K: Ord + Clone,
+    A: Clone,
+    B: Clone,
+{
+    let mut lefts: BTreeMap<&K, Vec<&A>> = BTreeMap::new();
+    for (k, a) in left {
+        lefts.entry(k).or_default().push(a);
+    }
+    let mut rights: BTreeMap<&K, Vec<&B>> = BTreeMap::new();
+    for (k, b) in right {
+        rights.entry(k).or_default().push(b);
+    }
+
+    let mut result = JoinResult {
+        rows: Vec::new(),
+        matches: 0,
+        unmatched_left: Vec::new(),
+        unmatched_right: Vec::new(),
+    };
+    let mut ls = lefts.into_iter().peekable();
+    let mut rs = rights.into_iter().peekable();
+    loop {
+        let order = match (ls.peek(), rs.peek()) {
+            (None, None) => break,
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some((lk, _)), Some((rk, _))) => lk.cmp(rk),
+        };
+        match order {
+            Ordering::Less => {
+                let (k, group) = ls.next().expect("peeked");
+                result.unmatched_left.push(k.clone());
+                if kind.keeps_unmatched_left() {
+                    result
+                        .rows
+                        .extend(group.into_iter().map(|a| row(k, Some(a), None)));
+                }
+            }
+            Ordering::Greater => {
+                let (k, group) = rs.next().expect("peeked");
+                result.unmatched_right.push(k.clone());
+                if kind.keeps_unmatched_right() {
+                    result
+                        .rows
+                        .extend(group.into_iter().map(|b| row(k, None, Some(b))));
+                }
+            }
+            Ordering::Equal => {
+                let (k, lgroup) = ls.next().expect("peeked");
+                let (_, rgroup) = rs.next().expect("peeked");
+                result.matches += lgroup.len() * rgroup.len();
+                if kind.keeps_matches() {
+                    for a in lgroup {
+                        result
+                            .rows
+                            .extend(rgroup.iter().map(|b| row(k, Some(a), Some(*b))));
+                    }
+                }
+            }
+        }
+    }
+    result
+}
+
+fn row<K: Clone, A: Clone, B: Clone>(
+    key: &K,
+    left: Option<&A>,
+    right: Option<&B>,
+) -> JoinRow<K, A, B> {
+    JoinRow {
+        key: key.clone(),
+        left: left.cloned(),
+        right: right.cloned(),
+    }
+}
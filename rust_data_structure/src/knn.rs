@@ -0,0 +1,344 @@
+//! K-nearest-neighbour search over numeric vectors.
+//!
+//! [`brute_force`] scans every point and keeps the best `k` in a bounded
+//! max-heap: the current worst candidate sits on top, so each new point
+//! costs one comparison and at most one O(log k) replacement. [`KdTree`]
+//! splits space on one axis per level and skips subtrees whose slab is
+//! already farther away than the worst candidate, which pays off for
+//! low-dimensional data.
+//!
+//! Both order results by distance, then by point index, so they agree
+//! exactly even when distances tie.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fmt;
+use std::io;
+
+use crate::import::split_csv_line;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    Euclidean,
+    Manhattan,
+    /// `1 - cos(angle)`; 0 for identical directions, 2 for opposite ones.
+    Cosine,
+}
+
+impl Metric {
+    pub fn from_name(name: &str) -> Option<Metric> {
+        match name {
+            "euclidean" | "l2" => Some(Metric::Euclidean),
+            "manhattan" | "l1" => Some(Metric::Manhattan),
+            "cosine" => Some(Metric::Cosine),
+            _ => None,
+        }
+    }
+
+    pub fn distance(self, a: &[f64], b: &[f64]) -> f64 {
+        let pairs = a.iter().zip(b);
+        match self {
+            Metric::Euclidean => pairs.map(|(x, y)| (x - y) * (x - y)).sum::<f64>().sqrt(),
+            Metric::Manhattan => pairs.map(|(x, y)| (x - y).abs()).sum(),
+            Metric::Cosine => {
+                let dot: f64 = pairs.map(|(x, y)| x * y).sum();
+                let norms = norm(a) * norm(b);
+                if norms == 0.0 {
+                    // The zero vector has no direction; treat it as unrelated.
+                    1.0
+                } else {
+                    1.0 - dot / norms
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Metric::Euclidean => "euclidean",
+            Metric::Manhattan => "manhattan",
+            Metric::Cosine => "cosine",
+        })
+    }
+}
+
+fn norm(v: &[f64]) -> f64 {
+    v.iter().map(|x| x * x).sum::<f64>().sqrt()
+}
+
+/// A labelled vector.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Point {
+    pub label: String,
+    pub coords: Vec<f64>,
+}
+
+impl Point {
+    pub fn new(label: impl Into<String>, coords: Vec<f64>) -> Self {
+        Point {
+            label: label.into(),
+            coords,
+        }
+    }
+}
+
+/// One search result: the index of a point and its distance to the query.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Neighbor {
+    pub index: usize,
+    pub distance: f64,
+}
+
+impl Eq for Neighbor {}
+
+impl PartialOrd for Neighbor {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Neighbor {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance
+            .total_cmp(&other.distance)
+            .then(self.index.cmp(&other.index))
+    }
+}
+
+/// Keeps the `k` smallest neighbours seen so far; the worst is on top.
+struct Best {
+    k: usize,
+    heap: BinaryHeap<Neighbor>,
+}
+
+impl Best {
+    /// Room for `k` neighbours out of `available` points; `k` comes from
+    /// the user and may be far larger than there are points.
+    fn new(k: usize, available: usize) -> Self {
+        Best {
+            k,
+            heap: BinaryHeap::with_capacity(k.min(available) + 1),
+        }
+    }
+
+    fn offer(&mut self, candidate: Neighbor) {
+        if self.heap.len() < self.k {
+            self.heap.push(candidate);
+        } else if self.heap.peek().is_some_and(|worst| candidate < *worst) {
+            self.heap.pop();
+            self.heap.push(candidate);
+        }
+    }
+
+    /// Whether a point at `distance` could still make the cut.
+    fn admits(&self, distance: f64) -> bool {
+        self.heap.len() < self.k || self.heap.peek().is_some_and(|w| distance <= w.distance)
+    }
+
+    fn into_sorted(self) -> Vec<Neighbor> {
+        self.heap.into_sorted_vec()
+    }
+}
+
+/// The `k` points nearest to `query`, nearest first. Panics if a point and
+/// the query differ in dimension.
+pub fn brute_force(points: &[Point], query: &[f64], k: usize, metric: Metric) -> Vec<Neighbor> {
+    let mut best = Best::new(k, points.len());
+    for (index, point) in points.iter().enumerate() {
+        check_dimensions(point.coords.len(), query);
+        best.offer(Neighbor {
+            index,
+            distance: metric.distance(&point.coords, query),
+        });
+    }
+    best.into_sorted()
+}
+
+fn check_dimensions(dimensions: usize, query: &[f64]) {
+    assert!(
+        query.len() == dimensions,
+        "query has {} coordinates, points have {}",
+        query.len(),
+        dimensions
+    );
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Leaf,
+    Split {
+        /// Index of the point stored at this node.
+        point: usize,
+        axis: usize,
+        left: Box<Node>,
+        right: Box<Node>,
+    },
+}
+
+/// A k-d tree over a fixed set of points of one dimension.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KdTree {
+    points: Vec<Point>,
+    dimensions: usize,
+    root: Node,
+}
+
+impl KdTree {
+    /// Builds a balanced tree by splitting on the median of each axis in
+    /// turn. Panics if the points do not all have the same dimension.
+    pub fn new(points: Vec<Point>) -> Self {
+        let dimensions = points.first().map_or(0, |p| p.coords.len());
+        assert!(
+            points.iter().all(|p| p.coords.len() == dimensions),
+            "all points must have {} dimensions",
+            dimensions
+        );
+        let mut order: Vec<usize> = (0..points.len()).collect();
+        let root = build(&points, &mut order, 0, dimensions);
+        KdTree {
+            points,
+            dimensions,
+            root,
+        }
+    }
+
+    pub fn points(&self) -> &[Point] {
+        &self.points
+    }
+
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// The `k` points nearest to `query`, nearest first. Cosine distance
+    /// does not bound by axis gaps, so it falls back to a full scan. Panics
+    /// if the tree has points and `query` differs from them in dimension.
+    pub fn nearest(&self, query: &[f64], k: usize, metric: Metric) -> Vec<Neighbor> {
+        if !self.is_empty() {
+            check_dimensions(self.dimensions, query);
+        }
+        if metric == Metric::Cosine || self.dimensions == 0 {
+            return brute_force(&self.points, query, k, metric);
+        }
+        let mut best = Best::new(k, self.points.len());
+        self.search(&self.root, query, metric, &mut best);
+        best.into_sorted()
+    }
+
+    fn search(&self, node: &Node, query: &[f64], metric: Metric, best: &mut Best) {
+        let Node::Split {
+            point,
+            axis,
+            left,
+            right,
+        } = node
+        else {
+            return;
+        };
+        let coords = &self.points[*point].coords;
+        best.offer(Neighbor {
+            index: *point,
+            distance: metric.distance(coords, query),
+        });
+        let gap = query[*axis] - coords[*axis];
+        let (near, far) = if gap < 0.0 {
+            (left, right)
+        } else {
+            (right, left)
+        };
+        self.search(near, query, metric, best);
+        // Every point across the split is at least `|gap|` away under both
+        // Euclidean and Manhattan distance.
+        if best.admits(gap.abs()) {
+            self.search(far, query, metric, best);
+        }
+    }
+}
+
+fn build(points: &[Point], order: &mut [usize], depth: usize, dimensions: usize) -> Node {
+    if order.is_empty() {
+        return Node::Leaf;
+    }
+    let axis = depth % dimensions;
+    let mid = order.len() / 2;
+    order.select_nth_unstable_by(mid, |&a, &b| {
+        points[a].coords[axis].total_cmp(&points[b].coords[axis])
+    });
+    let point = order[mid];
+    let (left, rest) = order.split_at_mut(mid);
+    Node::Split {
+        point,
+        axis,
+        left: Box::new(build(points, left, depth + 1, dimensions)),
+        right: Box::new(build(points, &mut rest[1..], depth + 1, dimensions)),
+    }
+}
+
+/// Parses points from CSV: one point per line, numeric columns as
+/// coordinates and an optional non-numeric first column as the label
+/// (otherwise points are labelled by line number). A first line with no
+/// numbers in its coordinate columns is skipped as a header.
+pub fn parse_points(text: &str) -> io::Result<Vec<Point>> {
+    let mut points: Vec<Point> = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let cells = split_csv_line(line).map_err(|e| invalid(i + 1, &e))?;
+        let cells: Vec<&str> = cells.iter().map(|c| c.trim()).collect();
+        let labelled = cells[0].parse::<f64>().is_err();
+        let (label, coords) = if labelled {
+            (cells[0].to_string(), &cells[1..])
+        } else {
+            ((i + 1).to_string(), &cells[..])
+        };
+        let coords: Result<Vec<f64>, _> = coords.iter().map(|c| c.parse::<f64>()).collect();
+        let coords = match coords {
+            Ok(coords) if !coords.is_empty() => coords,
+            _ if points.is_empty() && i == 0 => continue,
+            _ => return Err(invalid(i + 1, "coordinates must be numbers")),
+        };
+        if let Some(first) = points.first() {
+            if first.coords.len() != coords.len() {
+                return Err(invalid(
+                    i + 1,
+                    &format!(
+                        "expected {} coordinates, found {}",
+                        first.coords.len(),
+                        coords.len()
+                    ),
+                ));
+            }
+        }
+        points.push(Point::new(label, coords));
+    }
+    Ok(points)
+}
+
+/// Parses a query like `1.5,2` into coordinates.
+pub fn parse_query(text: &str) -> Result<Vec<f64>, String> {
+    text.split(',')
+        .map(|c| {
+            c.trim()
+                .parse::<f64>()
+                .map_err(|_| format!("'{}' is not a number", c.trim()))
+        })
+        .collect()
+}
+
+fn invalid(line: usize, message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("line {}: {}", line, message),
+    )
+}
@@ -0,0 +1,27 @@
+//! Library side of `print-data-structs`: the logic behind the interactive
+//! collection editors, kept out of `main.rs` so it can be reused and tested.
+
+pub mod advisor;
+pub mod balance;
+pub mod cache;
+pub mod concurrent;
+pub mod csv;
+pub mod draw;
+pub mod export;
+pub mod extsort;
+pub mod filter;
+pub mod graph;
+pub mod huffman;
+pub mod import;
+pub mod join;
+pub mod knn;
+pub mod json;
+pub mod pipeline;
+pub mod predicate;
+pub mod ring;
+pub mod sched;
+pub mod sim;
+pub mod stream;
+pub mod table;
+pub mod trace;
+pub mod trie;
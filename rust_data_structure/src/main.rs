@@ -1,5 +1,29 @@
 use std::collections::{VecDeque, LinkedList, HashMap, BTreeMap, HashSet, BTreeSet, BinaryHeap};
-use std::io::{self, Write};
+use std::cmp::Reverse;
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+
+use print_data_structs::advisor::{self, Workload};
+use print_data_structs::balance;
+use print_data_structs::cache::{Cache, Clock, LfuCache, LruCache, ManualClock};
+use print_data_structs::draw;
+use print_data_structs::export;
+use print_data_structs::filter::{false_positive_rate, BloomFilter, CuckooFilter};
+use print_data_structs::graph::Graph;
+use print_data_structs::huffman;
+use print_data_structs::extsort::{self, KeySpec};
+use print_data_structs::import::{self, Report};
+use print_data_structs::join::{self, JoinKind, JoinResult, Strategy};
+use print_data_structs::knn::{self, KdTree, Metric};
+use print_data_structs::pipeline::{Output, Pipeline};
+use print_data_structs::predicate::Predicate;
+use print_data_structs::ring::{Policy, Push, RingBuffer};
+use print_data_structs::sched::{self, Scheduler, Status};
+use print_data_structs::sim::QueueModel;
+use print_data_structs::trace::{self, Trace};
+use print_data_structs::trie::TrieMap;
+use std::time::{Duration, Instant};
 
 // ============================================
 // REFLECTION QUESTIONS ANSWERS (DOCUMENTATION)
@@ -32,6 +56,16 @@ use std::io::{self, Write};
 //   • K-Nearest Neighbors (ML algorithm)
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        if let Err(message) = run_subcommand(&args) {
+            eprintln!("✗ {}", message);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     println!("Common Rust Collections:");
 
     // Sequences
@@ -67,6 +101,270 @@ fn main() {
     interactive_menu();
 }
 
+/// Runs `print-data-structs <subcommand> ...` without the interactive menu.
+fn run_subcommand(args: &[&str]) -> Result<(), String> {
+    match args[0] {
+        "sort" => sort_subcommand(&args[1..]),
+        name @ ("compress" | "decompress") => huffman_subcommand(name, &args[1..]),
+        "simulate" => simulate_subcommand(&args[1..]),
+        "knn" => knn_subcommand(&args[1..]),
+        "balance" => balance_subcommand(&args[1..]),
+        "schedule" => schedule_subcommand(&args[1..]),
+        other => Err(format!("Unknown subcommand '{}'. Available: sort, compress, decompress, simulate, knn, balance, schedule", other)),
+    }
+}
+
+fn sort_subcommand(args: &[&str]) -> Result<(), String> {
+    let parsed = extsort::parse_args(args)?;
+    let format = parsed.options.format;
+    let field = match &parsed.key {
+        Some(field) => {
+            let header = match format {
+                extsort::Format::Csv { header: true } => first_line(&parsed.input)?,
+                _ => None,
+            };
+            Some(extsort::resolve_field(field, header.as_deref())?)
+        }
+        None => None,
+    };
+    let spec = KeySpec { field, numeric: parsed.numeric };
+
+    let input = File::open(&parsed.input)
+        .map(BufReader::new)
+        .map_err(|e| format!("Could not read {}: {}", parsed.input, e))?;
+    let output: Box<dyn Write> = match &parsed.output {
+        Some(path) => Box::new(BufWriter::new(
+            File::create(path).map_err(|e| format!("Could not write {}: {}", path, e))?,
+        )),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+    let stats = if parsed.reverse {
+        extsort::sort(input, output, &parsed.options, |line| Reverse(spec.extract(line, format)))
+    } else {
+        extsort::sort(input, output, &parsed.options, |line| spec.extract(line, format))
+    }
+    .map_err(|e| format!("Sort failed: {}", e))?;
+    eprintln!("✓ Sorted {} rows ({} runs spilled to disk)", stats.rows, stats.runs);
+    Ok(())
+}
+
+/// `compress <input> [-o <output>]` and `decompress <input> [-o <output>]`.
+/// Compressed files default to `<input>.huf`; decompressing strips it.
+fn huffman_subcommand(name: &str, args: &[&str]) -> Result<(), String> {
+    let (input, output) = match args {
+        [input] => (*input, None),
+        [input, "-o", output] => (*input, Some(output.to_string())),
+        _ => return Err(format!("Usage: {} <input> [-o <output>]", name)),
+    };
+    let output = match output {
+        Some(output) => output,
+        None if name == "compress" => format!("{}.huf", input),
+        None => match input.strip_suffix(".huf") {
+            Some(stem) if !stem.is_empty() => stem.to_string(),
+            _ => format!("{}.out", input),
+        },
+    };
+    let stats = if name == "compress" {
+        huffman::compress_file(input, &output)
+    } else {
+        huffman::decompress_file(input, &output)
+    }
+    .map_err(|e| format!("Could not {} {}: {}", name, input, e))?;
+    eprintln!(
+        "✓ Wrote {}: {} bytes original, {} bytes compressed (ratio {:.3}, {:.1}% saved)",
+        output,
+        stats.original,
+        stats.compressed,
+        stats.ratio(),
+        (1.0 - stats.ratio()) * 100.0
+    );
+    Ok(())
+}
+
+/// `simulate [--servers N] [--arrival <mean>] [--service <mean>]
+/// [--customers N] [--seed N]`: runs the sample queue model.
+fn simulate_subcommand(args: &[&str]) -> Result<(), String> {
+    let mut model = QueueModel::default();
+    let mut rest = args.iter();
+    while let Some(flag) = rest.next() {
+        let value = rest.next().ok_or_else(|| format!("{} needs a value", flag))?;
+        let bad = || format!("Invalid value for {}: {}", flag, value);
+        match *flag {
+            "--servers" => model.servers = value.parse().map_err(|_| bad())?,
+            "--arrival" => model.mean_interarrival = value.parse().map_err(|_| bad())?,
+            "--service" => model.mean_service = value.parse().map_err(|_| bad())?,
+            "--customers" => model.customers = value.parse().map_err(|_| bad())?,
+            "--seed" => model.seed = value.parse().map_err(|_| bad())?,
+            other => return Err(format!("Unknown option '{}'", other)),
+        }
+    }
+    let usable = |mean: f64| mean > 0.0 && mean.is_finite();
+    if model.servers == 0 || !usable(model.mean_interarrival) || !usable(model.mean_service) {
+        return Err("Servers and mean times must be positive and finite".to_string());
+    }
+
+    let report = model.run();
+    println!(
+        "{} customers, {} server(s), mean gap {}, mean service {}, seed {}",
+        model.customers, model.servers, model.mean_interarrival, model.mean_service, model.seed
+    );
+    println!("Served:       {} by tick {}", report.served, report.end_time);
+    println!("Wait (ticks): mean {:.2}, median {}, p95 {}, max {}", report.mean_wait, report.median_wait, report.p95_wait, report.max_wait);
+    println!("Longest queue: {}", report.max_queue);
+    println!("Utilization:  {:.1}%", report.utilization * 100.0);
+    Ok(())
+}
+
+/// `knn <points.csv> [-k N] [--metric euclidean|manhattan|cosine] [--brute]
+/// [query ...]`. Queries are comma-separated coordinates; without any, one
+/// query per line is read from stdin.
+fn knn_subcommand(args: &[&str]) -> Result<(), String> {
+    let usage = "Usage: knn <points.csv> [-k N] [--metric euclidean|manhattan|cosine] [--brute] [x,y,...]";
+    let path = args.first().ok_or(usage)?;
+    let (mut k, mut metric, mut brute) = (3, Metric::Euclidean, false);
+    let mut queries = Vec::new();
+    let mut rest = args[1..].iter();
+    while let Some(arg) = rest.next() {
+        match *arg {
+            "-k" => {
+                k = rest.next().and_then(|n| n.parse().ok()).filter(|&n| n > 0)
+                    .ok_or("-k needs a positive number")?;
+            }
+            "--metric" => {
+                metric = rest.next().and_then(|m| Metric::from_name(m))
+                    .ok_or("--metric must be euclidean, manhattan or cosine")?;
+            }
+            "--brute" => brute = true,
+            query => queries.push(knn::parse_query(query)?),
+        }
+    }
+
+    let text = std::fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+    let points = knn::parse_points(&text).map_err(|e| format!("Could not parse {}: {}", path, e))?;
+    if points.is_empty() {
+        return Err(format!("{} has no points", path));
+    }
+    let tree = KdTree::new(points);
+    eprintln!("✓ Loaded {} points in {} dimensions", tree.len(), tree.dimensions());
+
+    let answer = |query: &[f64]| -> Result<(), String> {
+        if query.len() != tree.dimensions() {
+            return Err(format!("Query has {} coordinates, points have {}", query.len(), tree.dimensions()));
+        }
+        let found = if brute {
+            knn::brute_force(tree.points(), query, k, metric)
+        } else {
+            tree.nearest(query, k, metric)
+        };
+        let shown: Vec<String> = found
+            .iter()
+            .map(|n| format!("{} ({:.4})", tree.points()[n.index].label, n.distance))
+            .collect();
+        println!("{:?} -> {}", query, shown.join(", "));
+        Ok(())
+    };
+    if queries.is_empty() {
+        for line in io::stdin().lock().lines() {
+            let line = line.map_err(|e| format!("Could not read stdin: {}", e))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match knn::parse_query(&line).and_then(|q| answer(&q)) {
+                Ok(()) => {}
+                Err(e) => eprintln!("✗ {}", e),
+            }
+        }
+    } else {
+        for query in &queries {
+            answer(query)?;
+        }
+    }
+    Ok(())
+}
+
+/// `balance <trace.csv> --servers a:3,b,c [--strategy <name>|all]`: replays
+/// a `time,key,duration` trace through each load-balancing strategy.
+fn balance_subcommand(args: &[&str]) -> Result<(), String> {
+    let usage = "Usage: balance <trace.csv> --servers a:3,b,c [--strategy round-robin|least-connections|weighted|consistent-hash|all]";
+    let path = args.first().ok_or(usage)?;
+    let mut servers = None;
+    let mut strategies = balance::Strategy::ALL.to_vec();
+    let mut rest = args[1..].iter();
+    while let Some(arg) = rest.next() {
+        match (*arg, rest.next()) {
+            ("--servers", Some(spec)) => servers = Some(balance::parse_servers(spec)?),
+            ("--strategy", Some(&"all")) => strategies = balance::Strategy::ALL.to_vec(),
+            ("--strategy", Some(name)) => {
+                strategies = vec![balance::Strategy::from_name(name).ok_or_else(|| format!("Unknown strategy '{}'", name))?];
+            }
+            _ => return Err(usage.to_string()),
+        }
+    }
+    let servers = servers.ok_or(usage)?;
+    let trace = balance::load_trace(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+    eprintln!("✓ Replaying {} requests over {} servers", trace.len(), servers.len());
+
+    for strategy in strategies {
+        let report = balance::simulate(&servers, &trace, strategy);
+        println!("\n{} (skew {:.2})", strategy, report.skew());
+        println!("  {:<12} {:>8} {:>8} {:>10} {:>6}", "server", "weight", "requests", "busy", "peak");
+        for (server, load) in servers.iter().zip(&report.servers) {
+            println!("  {:<12} {:>8} {:>8} {:>10} {:>6}", load.name, server.weight, load.requests, load.busy, load.peak);
+        }
+    }
+    Ok(())
+}
+
+/// `schedule <jobs-file> [--workers N]`: runs shell jobs in dependency and
+/// priority order and prints the execution log.
+fn schedule_subcommand(args: &[&str]) -> Result<(), String> {
+    let usage = "Usage: schedule <jobs-file> [--workers N]";
+    let (path, workers) = match args {
+        [path] => (*path, 2),
+        [path, "--workers", n] => (*path, n.parse().ok().filter(|&n| n > 0).ok_or(usage)?),
+        _ => return Err(usage.to_string()),
+    };
+    let text = std::fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+    let mut scheduler = Scheduler::new(workers);
+    for task in sched::parse_jobs(&text)? {
+        scheduler.add(task).map_err(|e| e.to_string())?;
+    }
+    let report = scheduler.run().map_err(|e| e.to_string())?;
+
+    for entry in &report.log {
+        let outcome = match &entry.result {
+            Ok(()) => "✓ ok".to_string(),
+            Err(e) => format!("✗ {}", e),
+        };
+        println!(
+            "[{:>8.3}s -> {:>8.3}s] worker {} {} (attempt {}) {}",
+            entry.started.as_secs_f64(), entry.finished.as_secs_f64(), entry.worker, entry.task, entry.attempt, outcome
+        );
+    }
+    println!();
+    for (name, status) in &report.statuses {
+        match status {
+            Status::Succeeded { attempts } => println!("✓ {} succeeded after {} attempt(s)", name, attempts),
+            Status::Failed { attempts, error } => println!("✗ {} failed after {} attempt(s): {}", name, attempts, error),
+            Status::Skipped { dependency } => println!("⚠ {} skipped because {} did not succeed", name, dependency),
+        }
+    }
+    if report.succeeded() {
+        Ok(())
+    } else {
+        Err("Some tasks did not succeed".to_string())
+    }
+}
+
+fn first_line(path: &str) -> Result<Option<String>, String> {
+    let file = File::open(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+    BufReader::new(file)
+        .lines()
+        .next()
+        .transpose()
+        .map_err(|e| format!("Could not read {}: {}", path, e))
+}
+
 fn demo_sequences() {
     println!("--- VEC Demo ---");
     // KEY METHODS: new(), push(), len(), access by index, insert(), remove()
@@ -142,8 +440,13 @@ fn interactive_menu() {
         println!("6. HashSet");
         println!("7. BTreeSet");
         println!("8. BinaryHeap");
+        println!("9. Collection advisor");
+        println!("10. Workspace (join keyed collections)");
+        println!("11. Cache (LRU/LFU)");
+        println!("12. Trie (prefix search)");
+        println!("13. Graph (paths and traversals)");
         println!("0. Exit");
-        print!("\nEnter your choice (0-8): ");
+        print!("\nEnter your choice (0-13): ");
         io::stdout().flush().unwrap();
 
         let mut choice = String::new();
@@ -165,19 +468,382 @@ fn interactive_menu() {
             6 => interact_with_hashset(),
             7 => interact_with_btreeset(),
             8 => interact_with_binaryheap(),
+            9 => collection_advisor(),
+            10 => interact_with_workspace(),
+            11 => interact_with_cache(),
+            12 => interact_with_trie(),
+            13 => interact_with_graph(),
             0 => {
                 println!("Goodbye!");
                 break;
             }
-            _ => println!("Invalid choice. Please select 0-8."),
+            _ => println!("Invalid choice. Please select 0-13."),
+        }
+    }
+}
+
+/// Commands every editor understands besides its own.
+const TOOLS_HELP: &str =
+    "Tools: draw | retain|find|count <predicate> | pipe <stages joined by '|'> | import <path> | export <path> | trace on|off|show|clear|save <path> | replay <path>";
+
+/// The same tools, as listed in "Unknown command" hints.
+const TOOLS_HINT: &str = "draw, retain, find, count, pipe, import, export, trace, replay";
+
+/// Hooks that let the shared trace and replay code drive one editor.
+struct Editor<C> {
+    name: &'static str,
+    apply: fn(&mut C, &[&str]),
+    state: fn(&C) -> Vec<String>,
+}
+
+fn run_command<C>(editor: &Editor<C>, collection: &mut C, trace: &mut Trace, parts: &[&str]) {
+    if !trace.is_recording() {
+        (editor.apply)(collection, parts);
+        return;
+    }
+    let size_before = (editor.state)(collection).len();
+    let start = Instant::now();
+    (editor.apply)(collection, parts);
+    let elapsed = start.elapsed();
+    let size_after = (editor.state)(collection).len();
+    trace.record(parts, size_before, size_after, elapsed);
+}
+
+fn trace_command(trace: &mut Trace, parts: &[&str]) {
+    match parts.get(1).copied() {
+        Some("on") => {
+            trace.start();
+            println!("✓ Recording {} trace", trace.editor());
+        }
+        Some("off") => {
+            trace.stop();
+            println!("✓ Recording stopped ({} events)", trace.events().len());
+        }
+        Some("clear") => {
+            trace.clear();
+            println!("✓ Trace cleared");
+        }
+        Some("show") => {
+            if trace.events().is_empty() {
+                println!("Trace is empty");
+            }
+            for (i, e) in trace.events().iter().enumerate() {
+                println!(
+                    "{:>3}. {} (size {} -> {}, {:?})",
+                    i + 1,
+                    e.command().join(" "),
+                    e.size_before,
+                    e.size_after,
+                    e.elapsed
+                );
+            }
+        }
+        Some("save") => {
+            if let Some(path) = parts.get(2) {
+                match trace.save(path) {
+                    Ok(()) => println!("✓ Saved {} events to {}", trace.events().len(), path),
+                    Err(e) => println!("✗ Could not save trace: {}", e),
+                }
+            } else {
+                println!("✗ Usage: trace save <path>");
+            }
+        }
+        _ => println!("✗ Usage: trace on|off|show|clear|save <path>"),
+    }
+}
+
+/// Commands that read or write files, which replay only runs when asked.
+const FILE_COMMANDS: &[&str] = &["import", "export", "load", "save"];
+
+/// Whether replaying `command` would touch files: one of [`FILE_COMMANDS`],
+/// or a command with a file option such as `sketch bloom save <path>`.
+fn uses_files(command: &[&str]) -> bool {
+    match command {
+        [op, ..] if FILE_COMMANDS.contains(op) => true,
+        ["sketch", .., "save", _] => true,
+        _ => false,
+    }
+}
+
+fn replay<C: Default>(editor: &Editor<C>, parts: &[&str]) {
+    let Some(path) = parts.get(1) else {
+        println!("✗ Usage: replay <path>");
+        return;
+    };
+    let saved = match Trace::load(path) {
+        Ok(saved) => saved,
+        Err(e) => {
+            println!("✗ Could not load trace: {}", e);
+            return;
+        }
+    };
+    if saved.editor() != editor.name {
+        println!("✗ Trace was recorded in the {} editor, not {}", saved.editor(), editor.name);
+        return;
+    }
+
+    let mut collection = C::default();
+    let total = saved.events().len();
+    println!("Replaying {} steps on a fresh collection (Enter = next step, q = stop)", total);
+    for (i, event) in saved.events().iter().enumerate() {
+        let touches_files = uses_files(&event.command());
+        print!("[{}/{}] {} ", i + 1, total, event.command().join(" "));
+        if touches_files {
+            print!("(uses files: y = run, Enter = skip, q = stop) ");
+        }
+        io::stdout().flush().unwrap();
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        if input.trim() == "q" {
+            println!("Replay stopped");
+            return;
+        }
+        if touches_files && input.trim() != "y" {
+            println!("    skipped");
+            continue;
+        }
+
+        let before = (editor.state)(&collection);
+        (editor.apply)(&mut collection, &event.command());
+        let after = (editor.state)(&collection);
+        println!(
+            "    size {} -> {} (recorded {} -> {})",
+            before.len(),
+            after.len(),
+            event.size_before,
+            event.size_after
+        );
+        let changes = trace::diff(&before, &after);
+        if changes.is_empty() && before != after {
+            println!("    order changed");
+        }
+        for change in changes {
+            println!("    {}", change);
+        }
+    }
+    println!("✓ Replay finished");
+}
+
+fn import_values(args: &[&str]) -> Option<Vec<i32>> {
+    let (path, options) = match import::parse_args(args) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            println!("✗ {}", e);
+            return None;
+        }
+    };
+    match import::load_values(&path, &options) {
+        Ok(report) => Some(print_import_report(&path, report)),
+        Err(e) => {
+            println!("✗ Could not read {}: {}", path, e);
+            None
+        }
+    }
+}
+
+fn import_pairs(args: &[&str]) -> Option<Vec<(String, i32)>> {
+    let (path, options) = match import::parse_args(args) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            println!("✗ {}", e);
+            return None;
+        }
+    };
+    match import::load_pairs(&path, &options) {
+        Ok(report) => Some(print_import_report(&path, report)),
+        Err(e) => {
+            println!("✗ Could not read {}: {}", path, e);
+            None
+        }
+    }
+}
+
+fn print_import_report<T>(path: &str, report: Report<T>) -> Vec<T> {
+    println!(
+        "✓ Imported {}: {} loaded, {} skipped, {} malformed",
+        path, report.loaded, report.skipped, report.malformed
+    );
+    for problem in report.problems.iter().take(5) {
+        println!("    {}", problem);
+    }
+    if report.problems.len() > 5 {
+        println!("    ... and {} more", report.problems.len() - 5);
+    }
+    report.records
+}
+
+fn export_values(args: &[&str], values: Vec<i32>) {
+    match export::parse_args(args) {
+        Ok((path, options)) => {
+            let rows = values.len();
+            write_export(&path, rows, &export::values(values, &options));
+        }
+        Err(e) => println!("✗ {}", e),
+    }
+}
+
+fn export_pairs(args: &[&str], pairs: Vec<(String, i32)>) {
+    match export::parse_args(args) {
+        Ok((path, options)) => {
+            let rows = pairs.len();
+            write_export(&path, rows, &export::pairs(pairs, &options));
+        }
+        Err(e) => println!("✗ {}", e),
+    }
+}
+
+fn write_export(path: &str, rows: usize, contents: &str) {
+    match export::write(path, contents) {
+        Ok(()) => println!("✓ Exported {} rows to {}", rows, path),
+        Err(e) => println!("✗ Could not write {}: {}", path, e),
+    }
+}
+
+fn parse_predicate(args: &[&str]) -> Option<Predicate> {
+    if args.is_empty() {
+        println!("✗ Missing predicate, e.g. x > 10 and x % 2 == 0");
+        return None;
+    }
+    let text = args.join(" ");
+    match Predicate::parse(&text) {
+        Ok(predicate) => Some(predicate),
+        Err(e) => {
+            println!("✗ Invalid predicate at {}", e);
+            for line in e.pointer(&text).lines() {
+                println!("    {}", line);
+            }
+            None
+        }
+    }
+}
+
+/// Parses the predicate in `args` and checks it evaluates for every value,
+/// so that commands using it never stop half way.
+fn value_predicate(args: &[&str], mut values: impl Iterator<Item = i32>) -> Option<Predicate> {
+    let predicate = parse_predicate(args)?;
+    if predicate.uses_key() {
+        println!("✗ 'key' is only available in the map editors");
+        return None;
+    }
+    if let Some((value, e)) = values.find_map(|v| predicate.matches(v).err().map(|e| (v, e))) {
+        println!("✗ {} evaluating '{}' for {}", e, predicate, value);
+        return None;
+    }
+    Some(predicate)
+}
+
+fn entry_predicate<'a>(
+    args: &[&str],
+    mut entries: impl Iterator<Item = (&'a String, &'a i32)>,
+) -> Option<Predicate> {
+    let predicate = parse_predicate(args)?;
+    if let Some((key, e)) =
+        entries.find_map(|(k, v)| predicate.matches_entry(k, *v).err().map(|e| (k, e)))
+    {
+        println!("✗ {} evaluating '{}' for key {}", e, predicate, key);
+        return None;
+    }
+    Some(predicate)
+}
+
+fn find_values(args: &[&str], values: Vec<i32>, positions: bool) {
+    if let Some(predicate) = value_predicate(args, values.iter().copied()) {
+        let found: Vec<String> = values
+            .iter()
+            .enumerate()
+            .filter(|(_, &x)| predicate.matches(x) == Ok(true))
+            .map(|(i, x)| if positions { format!("[{}] {}", i, x) } else { x.to_string() })
+            .collect();
+        println!("✓ {} matches: {}", found.len(), found.join(", "));
+    }
+}
+
+fn count_values(args: &[&str], values: Vec<i32>) {
+    if let Some(predicate) = value_predicate(args, values.iter().copied()) {
+        let count = values.iter().filter(|&&x| predicate.matches(x) == Ok(true)).count();
+        println!("✓ {} of {} elements match", count, values.len());
+    }
+}
+
+fn find_entries(args: &[&str], entries: Vec<(String, i32)>) {
+    if let Some(predicate) = entry_predicate(args, entries.iter().map(|(k, v)| (k, v))) {
+        let found: Vec<String> = entries
+            .iter()
+            .filter(|(k, v)| predicate.matches_entry(k, *v) == Ok(true))
+            .map(|(k, v)| format!("{} = {}", k, v))
+            .collect();
+        println!("✓ {} matches: {}", found.len(), found.join(", "));
+    }
+}
+
+fn count_entries(args: &[&str], entries: Vec<(String, i32)>) {
+    if let Some(predicate) = entry_predicate(args, entries.iter().map(|(k, v)| (k, v))) {
+        let count = entries
+            .iter()
+            .filter(|(k, v)| predicate.matches_entry(k, *v) == Ok(true))
+            .count();
+        println!("✓ {} of {} entries match", count, entries.len());
+    }
+}
+
+/// Parses a `pipe` command. Lines starting with a `map` or `filter` stage
+/// are pipelines too, so the `pipe` keyword is optional there.
+fn parse_pipeline(parts: &[&str]) -> Option<Pipeline> {
+    let stages = if parts.first() == Some(&"pipe") { &parts[1..] } else { parts };
+    match Pipeline::parse(&stages.join(" ")) {
+        Ok(pipeline) => Some(pipeline),
+        Err(e) => {
+            println!("✗ Invalid pipeline: {}", e);
+            println!("  e.g. pipe map x * 2 | filter x > 10 | sum");
+            None
+        }
+    }
+}
+
+/// Runs a pipeline over a copy of `values` and prints the result. Returns
+/// the elements to store when the pipeline ends with `store`.
+fn pipe_values(parts: &[&str], values: Vec<i32>) -> Option<Vec<i32>> {
+    let pipeline = parse_pipeline(parts)?;
+    if pipeline.uses_key() {
+        println!("✗ 'key' is only available in the map editors");
+        return None;
+    }
+    match pipeline.run(values) {
+        Ok(Output::Values(values)) if pipeline.stores() => return Some(values),
+        Ok(Output::Values(values)) => println!("✓ {} elements: {:?}", values.len(), values),
+        Ok(Output::Scalar(Some(n))) => println!("✓ {}", n),
+        Ok(Output::Scalar(None)) => println!("✓ No result: no elements reached the reducer"),
+        Ok(Output::Entries(_)) => unreachable!("values never produce entries"),
+        Err(e) => println!("✗ {}", e),
+    }
+    None
+}
+
+/// [`pipe_values`] for map entries; `map` changes values and keeps keys.
+fn pipe_entries(parts: &[&str], entries: Vec<(String, i32)>) -> Option<Vec<(String, i32)>> {
+    let pipeline = parse_pipeline(parts)?;
+    match pipeline.run_entries(entries) {
+        Ok(Output::Entries(entries)) if pipeline.stores() => return Some(entries),
+        Ok(Output::Entries(entries)) => {
+            let shown: Vec<String> = entries.iter().map(|(k, v)| format!("{} = {}", k, v)).collect();
+            println!("✓ {} entries: {}", entries.len(), shown.join(", "));
         }
+        Ok(Output::Scalar(Some(n))) => println!("✓ {}", n),
+        Ok(Output::Scalar(None)) => println!("✓ No result: no entries reached the reducer"),
+        Ok(Output::Values(_)) => unreachable!("entries never produce plain values"),
+        Err(e) => println!("✗ {}", e),
     }
+    None
 }
 
 fn interact_with_vec() {
     let mut vec: Vec<i32> = Vec::new();
+    let mut trace = Trace::new("vec");
     println!("\n--- VEC Editor ---");
-    println!("Commands: add <value> | remove <index> | show | exit");
+    println!("Commands: add <value> | remove <index> | insert <index> <value> | show | exit");
+    println!("          sort [asc|desc|stable] | binary_search <value> | dedup | reverse");
+    println!("          rotate <n> | slice <a> <b> | chunk <n>");
+    println!("{}", TOOLS_HELP);
     
     loop {
         print!("vec> ");
@@ -189,47 +855,194 @@ fn interact_with_vec() {
         
         let parts: Vec<&str> = input.split_whitespace().collect();
         
-        match parts.get(0).map(|s| *s) {
-            Some("add") => {
-                if let Some(val_str) = parts.get(1) {
-                    if let Ok(val) = val_str.parse::<i32>() {
-                        vec.push(val);
-                        println!("✓ Added {}. Vec: {:?}", val, vec);
+        match parts.first().copied() {
+            Some("trace") => trace_command(&mut trace, &parts),
+            Some("replay") => replay(&VEC_EDITOR, &parts),
+            Some("exit") => break,
+            Some(_) => run_command(&VEC_EDITOR, &mut vec, &mut trace, &parts),
+            None => {}
+        }
+    }
+}
+
+const VEC_EDITOR: Editor<Vec<i32>> = Editor {
+    name: "vec",
+    apply: vec_command,
+    state: |vec| trace::items(vec.iter()),
+};
+
+fn vec_command(vec: &mut Vec<i32>, parts: &[&str]) {
+    match parts.first().copied() {
+        Some("add") => {
+            if let Some(val_str) = parts.get(1) {
+                if let Ok(val) = val_str.parse::<i32>() {
+                    vec.push(val);
+                    println!("✓ Added {}. Vec: {:?}", val, vec);
+                } else {
+                    println!("✗ Invalid number");
+                }
+            } else {
+                println!("✗ Usage: add <value>");
+            }
+        }
+        Some("remove") => {
+            if let Some(idx_str) = parts.get(1) {
+                if let Ok(idx) = idx_str.parse::<usize>() {
+                    if idx < vec.len() {
+                        let removed = vec.remove(idx);
+                        println!("✓ Removed {} from index {}. Vec: {:?}", removed, idx, vec);
+                    } else {
+                        println!("✗ Index out of bounds");
+                    }
+                } else {
+                    println!("✗ Invalid index");
+                }
+            } else {
+                println!("✗ Usage: remove <index>");
+            }
+        }
+        Some("insert") => {
+            if let (Some(idx_str), Some(val_str)) = (parts.get(1), parts.get(2)) {
+                match (idx_str.parse::<usize>(), val_str.parse::<i32>()) {
+                    (Ok(idx), Ok(val)) if idx <= vec.len() => {
+                        vec.insert(idx, val);
+                        println!("✓ Inserted {} at index {}. Vec: {:?}", val, idx, vec);
+                    }
+                    (Ok(_), Ok(_)) => println!("✗ Index out of bounds"),
+                    (Err(_), _) => println!("✗ Invalid index"),
+                    (_, Err(_)) => println!("✗ Invalid number"),
+                }
+            } else {
+                println!("✗ Usage: insert <index> <value>");
+            }
+        }
+        Some("sort") => match parts.get(1).copied() {
+            None | Some("asc") => {
+                vec.sort_unstable();
+                println!("✓ Sorted ascending. Vec: {:?}", vec);
+            }
+            Some("desc") => {
+                vec.sort_unstable_by(|a, b| b.cmp(a));
+                println!("✓ Sorted descending. Vec: {:?}", vec);
+            }
+            Some("stable") => {
+                vec.sort();
+                println!("✓ Sorted ascending (stable). Vec: {:?}", vec);
+            }
+            Some(_) => println!("✗ Usage: sort [asc|desc|stable]"),
+        },
+        Some("binary_search") => {
+            if let Some(val_str) = parts.get(1) {
+                if let Ok(val) = val_str.parse::<i32>() {
+                    if !vec.is_sorted() {
+                        println!("✗ Vec is not sorted ascending. Run 'sort' first");
                     } else {
-                        println!("✗ Invalid number");
+                        match vec.binary_search(&val) {
+                            Ok(idx) => println!("✓ Found {} at index {}", val, idx),
+                            Err(idx) => println!("✗ {} not found (would be inserted at index {})", val, idx),
+                        }
                     }
                 } else {
-                    println!("✗ Usage: add <value>");
+                    println!("✗ Invalid number");
                 }
+            } else {
+                println!("✗ Usage: binary_search <value>");
             }
-            Some("remove") => {
-                if let Some(idx_str) = parts.get(1) {
-                    if let Ok(idx) = idx_str.parse::<usize>() {
-                        if idx < vec.len() {
-                            let removed = vec.remove(idx);
-                            println!("✓ Removed {} from index {}. Vec: {:?}", removed, idx, vec);
+        }
+        Some("dedup") => {
+            let before = vec.len();
+            vec.dedup();
+            println!(
+                "✓ Removed {} consecutive duplicates. Vec: {:?}",
+                before - vec.len(),
+                vec
+            );
+        }
+        Some("reverse") => {
+            vec.reverse();
+            println!("✓ Reversed. Vec: {:?}", vec);
+        }
+        Some("rotate") => {
+            if let Some(n_str) = parts.get(1) {
+                if let Ok(n) = n_str.parse::<i64>() {
+                    if !vec.is_empty() {
+                        let shift = n.unsigned_abs() as usize % vec.len();
+                        if n >= 0 {
+                            vec.rotate_left(shift);
                         } else {
-                            println!("✗ Index out of bounds");
+                            vec.rotate_right(shift);
                         }
-                    } else {
-                        println!("✗ Invalid index");
                     }
+                    println!("✓ Rotated by {}. Vec: {:?}", n, vec);
                 } else {
-                    println!("✗ Usage: remove <index>");
+                    println!("✗ Invalid number");
                 }
+            } else {
+                println!("✗ Usage: rotate <n> (negative rotates right)");
+            }
+        }
+        Some("slice") => {
+            if let (Some(a_str), Some(b_str)) = (parts.get(1), parts.get(2)) {
+                match (a_str.parse::<usize>(), b_str.parse::<usize>()) {
+                    (Ok(a), Ok(b)) => match vec.get(a..b) {
+                        Some(slice) => println!("✓ vec[{}..{}] = {:?}", a, b, slice),
+                        None => println!("✗ Range out of bounds"),
+                    },
+                    _ => println!("✗ Invalid index"),
+                }
+            } else {
+                println!("✗ Usage: slice <start> <end>");
+            }
+        }
+        Some("chunk") => {
+            if let Some(n_str) = parts.get(1) {
+                match n_str.parse::<usize>() {
+                    Ok(n) if n > 0 => {
+                        for (i, chunk) in vec.chunks(n).enumerate() {
+                            println!("  chunk {}: {:?}", i, chunk);
+                        }
+                    }
+                    _ => println!("✗ Chunk size must be a positive number"),
+                }
+            } else {
+                println!("✗ Usage: chunk <n>");
+            }
+        }
+        Some("retain") => {
+            if let Some(predicate) = value_predicate(&parts[1..], vec.iter().copied()) {
+                let before = vec.len();
+                vec.retain(|&x| predicate.matches(x) == Ok(true));
+                println!("✓ Removed {} elements. Vec: {:?}", before - vec.len(), vec);
+            }
+        }
+        Some("find") => find_values(&parts[1..], vec.clone(), true),
+        Some("count") => count_values(&parts[1..], vec.clone()),
+        Some("pipe" | "map" | "filter") => {
+            if let Some(result) = pipe_values(parts, vec.clone()) {
+                *vec = result;
+                println!("✓ Stored {} elements. Vec: {:?}", vec.len(), vec);
             }
-            Some("show") => println!("Vec: {:?} (length: {})", vec, vec.len()),
-            Some("exit") => break,
-            Some(_) => println!("Unknown command. Try: add, remove, show, exit"),
-            None => {}
         }
+        Some("import") => {
+            if let Some(values) = import_values(&parts[1..]) {
+                vec.extend(values);
+                println!("Vec length: {}", vec.len());
+            }
+        }
+        Some("export") => export_values(&parts[1..], vec.clone()),
+        Some("draw") => println!("{}", draw::vec(vec)),
+        Some("show") => println!("Vec: {:?} (length: {})", vec, vec.len()),
+        _ => println!("Unknown command. Try: add, remove, insert, sort, binary_search, dedup, reverse, rotate, slice, chunk, show, {}, exit", TOOLS_HINT),
     }
 }
 
 fn interact_with_vecdeque() {
     let mut deque: VecDeque<i32> = VecDeque::new();
+    let mut trace = Trace::new("vecdeque");
     println!("\n--- VECDEQUE Editor ---");
     println!("Commands: add_front <value> | add_back <value> | pop_front | pop_back | show | exit");
+    println!("          ring <capacity> [overwrite|reject] (bounded mode)");
+    println!("{}", TOOLS_HELP);
     
     loop {
         print!("deque> ");
@@ -241,121 +1054,343 @@ fn interact_with_vecdeque() {
         
         let parts: Vec<&str> = input.split_whitespace().collect();
         
-        match parts.get(0).map(|s| *s) {
-            Some("add_front") => {
-                if let Some(val_str) = parts.get(1) {
-                    if let Ok(val) = val_str.parse::<i32>() {
-                        deque.push_front(val);
-                        println!("✓ Added {} to front. Deque: {:?}", val, deque);
-                    } else {
-                        println!("✗ Invalid number");
-                    }
+        match parts.first().copied() {
+            Some("trace") => trace_command(&mut trace, &parts),
+            Some("replay") => replay(&VECDEQUE_EDITOR, &parts),
+            Some("ring") => interact_with_ring(&mut deque, &parts),
+            Some("exit") => break,
+            Some(_) => run_command(&VECDEQUE_EDITOR, &mut deque, &mut trace, &parts),
+            None => {}
+        }
+    }
+}
+
+const VECDEQUE_EDITOR: Editor<VecDeque<i32>> = Editor {
+    name: "vecdeque",
+    apply: vecdeque_command,
+    state: |deque| trace::items(deque.iter()),
+};
+
+fn vecdeque_command(deque: &mut VecDeque<i32>, parts: &[&str]) {
+    match parts.first().copied() {
+        Some("add_front") => {
+            if let Some(val_str) = parts.get(1) {
+                if let Ok(val) = val_str.parse::<i32>() {
+                    deque.push_front(val);
+                    println!("✓ Added {} to front. Deque: {:?}", val, deque);
                 } else {
-                    println!("✗ Usage: add_front <value>");
+                    println!("✗ Invalid number");
                 }
+            } else {
+                println!("✗ Usage: add_front <value>");
             }
-            Some("add_back") => {
-                if let Some(val_str) = parts.get(1) {
-                    if let Ok(val) = val_str.parse::<i32>() {
-                        deque.push_back(val);
-                        println!("✓ Added {} to back. Deque: {:?}", val, deque);
-                    } else {
-                        println!("✗ Invalid number");
-                    }
+        }
+        Some("add_back") => {
+            if let Some(val_str) = parts.get(1) {
+                if let Ok(val) = val_str.parse::<i32>() {
+                    deque.push_back(val);
+                    println!("✓ Added {} to back. Deque: {:?}", val, deque);
                 } else {
-                    println!("✗ Usage: add_back <value>");
+                    println!("✗ Invalid number");
                 }
+            } else {
+                println!("✗ Usage: add_back <value>");
             }
-            Some("pop_front") => {
-                if let Some(val) = deque.pop_front() {
-                    println!("✓ Removed {} from front. Deque: {:?}", val, deque);
-                } else {
-                    println!("✗ Deque is empty");
-                }
+        }
+        Some("pop_front") => {
+            if let Some(val) = deque.pop_front() {
+                println!("✓ Removed {} from front. Deque: {:?}", val, deque);
+            } else {
+                println!("✗ Deque is empty");
             }
-            Some("pop_back") => {
-                if let Some(val) = deque.pop_back() {
-                    println!("✓ Removed {} from back. Deque: {:?}", val, deque);
-                } else {
-                    println!("✗ Deque is empty");
-                }
+        }
+        Some("pop_back") => {
+            if let Some(val) = deque.pop_back() {
+                println!("✓ Removed {} from back. Deque: {:?}", val, deque);
+            } else {
+                println!("✗ Deque is empty");
+            }
+        }
+        Some("retain") => {
+            if let Some(predicate) = value_predicate(&parts[1..], deque.iter().copied()) {
+                let before = deque.len();
+                deque.retain(|&x| predicate.matches(x) == Ok(true));
+                println!("✓ Removed {} elements. Deque: {:?}", before - deque.len(), deque);
             }
-            Some("show") => println!("Deque: {:?} (length: {})", deque, deque.len()),
-            Some("exit") => break,
-            Some(_) => println!("Unknown command. Try: add_front, add_back, pop_front, pop_back, show, exit"),
-            None => {}
         }
+        Some("find") => find_values(&parts[1..], deque.iter().copied().collect(), true),
+        Some("count") => count_values(&parts[1..], deque.iter().copied().collect()),
+        Some("pipe" | "map" | "filter") => {
+            if let Some(result) = pipe_values(parts, deque.iter().copied().collect()) {
+                *deque = result.into();
+                println!("✓ Stored {} elements. Deque: {:?}", deque.len(), deque);
+            }
+        }
+        Some("import") => {
+            if let Some(values) = import_values(&parts[1..]) {
+                deque.extend(values);
+                println!("Deque length: {}", deque.len());
+            }
+        }
+        Some("export") => export_values(&parts[1..], deque.iter().copied().collect()),
+        Some("draw") => println!("{}", draw::vecdeque(deque)),
+        Some("show") => println!("Deque: {:?} (length: {})", deque, deque.len()),
+        _ => println!("Unknown command. Try: add_front, add_back, pop_front, pop_back, ring, show, {}, exit", TOOLS_HINT),
     }
 }
 
-fn interact_with_linkedlist() {
-    let mut list: LinkedList<i32> = LinkedList::new();
-    println!("\n--- LINKEDLIST Editor ---");
-    println!("Commands: add_front <value> | add_back <value> | pop_front | pop_back | show | exit");
-    
+/// Bounded mode of the VecDeque editor: the deque's elements are pushed
+/// into a ring buffer, and the deque takes the ring's contents on exit.
+fn interact_with_ring(deque: &mut VecDeque<i32>, parts: &[&str]) {
+    let capacity = match parts.get(1).map(|n| n.parse::<usize>()) {
+        Some(Ok(n)) if n > 0 => n,
+        _ => {
+            println!("✗ Usage: ring <capacity> [overwrite|reject]");
+            return;
+        }
+    };
+    let policy = match parts.get(2).map(|name| Policy::from_name(name)) {
+        None => Policy::Overwrite,
+        Some(Some(policy)) => policy,
+        Some(None) => {
+            println!("✗ Policy must be overwrite or reject");
+            return;
+        }
+    };
+    let mut ring = RingBuffer::new(capacity, policy);
+    let dropped = deque.iter().filter(|&&x| ring.push(x) != Push::Stored).count();
+    let mut trace = Trace::new("ring");
+    println!("\n--- RING BUFFER (capacity {}, {}) ---", capacity, policy);
+    if dropped > 0 {
+        println!("⚠ {} elements did not fit", dropped);
+    }
+    println!("Commands: push <value>... | pop | stats | capacity <n> | policy overwrite|reject");
+    println!("          draw | show | exit (back to the deque)");
+    println!("Tools: trace on|off|show|clear|save <path> | replay <path>");
+
     loop {
-        print!("list> ");
+        print!("ring> ");
         io::stdout().flush().unwrap();
-        
+
         let mut input = String::new();
         io::stdin().read_line(&mut input).unwrap();
         let input = input.trim();
-        
+
         let parts: Vec<&str> = input.split_whitespace().collect();
-        
-        match parts.get(0).map(|s| *s) {
-            Some("add_front") => {
-                if let Some(val_str) = parts.get(1) {
-                    if let Ok(val) = val_str.parse::<i32>() {
-                        list.push_front(val);
-                        println!("✓ Added {} to front. List: {:?}", val, list);
-                    } else {
-                        println!("✗ Invalid number");
-                    }
-                } else {
-                    println!("✗ Usage: add_front <value>");
-                }
-            }
-            Some("add_back") => {
-                if let Some(val_str) = parts.get(1) {
-                    if let Ok(val) = val_str.parse::<i32>() {
-                        list.push_back(val);
-                        println!("✓ Added {} to back. List: {:?}", val, list);
-                    } else {
-                        println!("✗ Invalid number");
-                    }
-                } else {
-                    println!("✗ Usage: add_back <value>");
-                }
-            }
-            Some("pop_front") => {
-                if let Some(val) = list.pop_front() {
-                    println!("✓ Removed {} from front. List: {:?}", val, list);
-                } else {
-                    println!("✗ List is empty");
+
+        match parts.first().copied() {
+            Some("trace") => {
+                trace_command(&mut trace, &parts);
+                if trace.is_recording() && trace.events().is_empty() {
+                    record_ring_setup(&mut trace, &ring);
                 }
             }
-            Some("pop_back") => {
-                if let Some(val) = list.pop_back() {
-                    println!("✓ Removed {} from back. List: {:?}", val, list);
-                } else {
-                    println!("✗ List is empty");
+            Some("replay") => replay(&RING_EDITOR, &parts),
+            Some("exit") => break,
+            Some(_) => run_command(&RING_EDITOR, &mut ring, &mut trace, &parts),
+            None => {}
+        }
+    }
+    *deque = ring.as_deque().clone();
+    println!("Deque: {:?}", deque);
+}
+
+/// Replay starts from `RingBuffer::default()`, so a trace begins with the
+/// commands that rebuild the ring as it is now: its capacity, its policy
+/// and a push of its contents.
+fn record_ring_setup(trace: &mut Trace, ring: &RingBuffer) {
+    let capacity = ring.capacity().to_string();
+    let policy = ring.policy().to_string();
+    trace.record(&["capacity", &capacity], 0, 0, Duration::ZERO);
+    trace.record(&["policy", &policy], 0, 0, Duration::ZERO);
+    if !ring.is_empty() {
+        let values: Vec<String> = ring.iter().map(|x| x.to_string()).collect();
+        let mut push = vec!["push"];
+        push.extend(values.iter().map(String::as_str));
+        trace.record(&push, 0, ring.len(), Duration::ZERO);
+    }
+}
+
+const RING_EDITOR: Editor<RingBuffer> = Editor {
+    name: "ring",
+    apply: ring_command,
+    state: |ring| trace::items(ring.iter()),
+};
+
+fn ring_command(ring: &mut RingBuffer, parts: &[&str]) {
+    match parts.first().copied() {
+        Some("push") => {
+            if parts.len() < 2 {
+                println!("✗ Usage: push <value>...");
+            }
+            for val_str in &parts[1..] {
+                match val_str.parse::<i32>() {
+                    Ok(val) => match ring.push(val) {
+                        Push::Stored => println!("✓ Pushed {}", val),
+                        Push::Evicted(old) => println!("✓ Pushed {}, overwrote {}", val, old),
+                        Push::Rejected => println!("✗ Rejected {}: buffer is full", val),
+                    },
+                    Err(_) => println!("✗ Invalid number '{}'", val_str),
                 }
             }
-            Some("show") => println!("List: {:?} (length: {})", list, list.len()),
+            println!("Ring: {:?} ({}/{})", ring.as_deque(), ring.len(), ring.capacity());
+        }
+        Some("pop") => {
+            if let Some(val) = ring.pop_oldest() {
+                println!("✓ Removed oldest {}. Ring: {:?}", val, ring.as_deque());
+            } else {
+                println!("✗ Ring is empty");
+            }
+        }
+        Some("stats") => match (ring.min(), ring.max(), ring.mean()) {
+            (Some(min), Some(max), Some(mean)) => println!(
+                "✓ window of {}: sum {} | mean {:.2} | min {} | max {}",
+                ring.len(),
+                ring.sum(),
+                mean,
+                min,
+                max
+            ),
+            _ => println!("Ring is empty"),
+        },
+        Some("capacity") => match parts.get(1).map(|n| n.parse::<usize>()) {
+            Some(Ok(n)) if n > 0 => {
+                let evicted = ring.set_capacity(n);
+                println!("✓ Capacity {}. Evicted: {:?}", n, evicted);
+            }
+            _ => println!("✗ Usage: capacity <n> (n > 0)"),
+        },
+        Some("policy") => match parts.get(1).and_then(|name| Policy::from_name(name)) {
+            Some(policy) => {
+                ring.set_policy(policy);
+                println!("✓ Policy: {}", policy);
+            }
+            None => println!("✗ Usage: policy overwrite|reject"),
+        },
+        Some("draw") => println!("{}", draw::vecdeque(ring.as_deque())),
+        Some("show") => println!(
+            "Ring: {:?} ({}/{}, {})",
+            ring.as_deque(),
+            ring.len(),
+            ring.capacity(),
+            ring.policy()
+        ),
+        _ => println!("Unknown command. Try: push, pop, stats, capacity, policy, draw, show, exit"),
+    }
+}
+
+fn interact_with_linkedlist() {
+    let mut list: LinkedList<i32> = LinkedList::new();
+    let mut trace = Trace::new("linkedlist");
+    println!("\n--- LINKEDLIST Editor ---");
+    println!("Commands: add_front <value> | add_back <value> | pop_front | pop_back | show | exit");
+    println!("{}", TOOLS_HELP);
+    
+    loop {
+        print!("list> ");
+        io::stdout().flush().unwrap();
+        
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        let input = input.trim();
+        
+        let parts: Vec<&str> = input.split_whitespace().collect();
+        
+        match parts.first().copied() {
+            Some("trace") => trace_command(&mut trace, &parts),
+            Some("replay") => replay(&LINKEDLIST_EDITOR, &parts),
             Some("exit") => break,
-            Some(_) => println!("Unknown command. Try: add_front, add_back, pop_front, pop_back, show, exit"),
+            Some(_) => run_command(&LINKEDLIST_EDITOR, &mut list, &mut trace, &parts),
             None => {}
         }
     }
 }
 
+const LINKEDLIST_EDITOR: Editor<LinkedList<i32>> = Editor {
+    name: "linkedlist",
+    apply: linkedlist_command,
+    state: |list| trace::items(list.iter()),
+};
+
+fn linkedlist_command(list: &mut LinkedList<i32>, parts: &[&str]) {
+    match parts.first().copied() {
+        Some("add_front") => {
+            if let Some(val_str) = parts.get(1) {
+                if let Ok(val) = val_str.parse::<i32>() {
+                    list.push_front(val);
+                    println!("✓ Added {} to front. List: {:?}", val, list);
+                } else {
+                    println!("✗ Invalid number");
+                }
+            } else {
+                println!("✗ Usage: add_front <value>");
+            }
+        }
+        Some("add_back") => {
+            if let Some(val_str) = parts.get(1) {
+                if let Ok(val) = val_str.parse::<i32>() {
+                    list.push_back(val);
+                    println!("✓ Added {} to back. List: {:?}", val, list);
+                } else {
+                    println!("✗ Invalid number");
+                }
+            } else {
+                println!("✗ Usage: add_back <value>");
+            }
+        }
+        Some("pop_front") => {
+            if let Some(val) = list.pop_front() {
+                println!("✓ Removed {} from front. List: {:?}", val, list);
+            } else {
+                println!("✗ List is empty");
+            }
+        }
+        Some("pop_back") => {
+            if let Some(val) = list.pop_back() {
+                println!("✓ Removed {} from back. List: {:?}", val, list);
+            } else {
+                println!("✗ List is empty");
+            }
+        }
+        Some("retain") => {
+            if let Some(predicate) = value_predicate(&parts[1..], list.iter().copied()) {
+                let before = list.len();
+                *list = std::mem::take(list)
+                    .into_iter()
+                    .filter(|&x| predicate.matches(x) == Ok(true))
+                    .collect();
+                println!("✓ Removed {} elements. List: {:?}", before - list.len(), list);
+            }
+        }
+        Some("find") => find_values(&parts[1..], list.iter().copied().collect(), true),
+        Some("count") => count_values(&parts[1..], list.iter().copied().collect()),
+        Some("pipe" | "map" | "filter") => {
+            if let Some(result) = pipe_values(parts, list.iter().copied().collect()) {
+                *list = result.into_iter().collect();
+                println!("✓ Stored {} elements. List: {:?}", list.len(), list);
+            }
+        }
+        Some("import") => {
+            if let Some(values) = import_values(&parts[1..]) {
+                list.extend(values);
+                println!("List length: {}", list.len());
+            }
+        }
+        Some("export") => export_values(&parts[1..], list.iter().copied().collect()),
+        Some("draw") => println!("{}", draw::linkedlist(list)),
+        Some("show") => println!("List: {:?} (length: {})", list, list.len()),
+        _ => println!("Unknown command. Try: add_front, add_back, pop_front, pop_back, show, {}, exit", TOOLS_HINT),
+    }
+}
+
 fn interact_with_hashmap() {
     let mut map: HashMap<String, i32> = HashMap::new();
+    let mut trace = Trace::new("hashmap");
     println!("\n--- HASHMAP Editor ---");
     // KEY METHODS: insert(), get(), remove(), len()
     // HASHMAP: Unordered, O(1) average, use for fast key-value lookups
     println!("Commands: add <key> <value> | get <key> | remove <key> | show | exit");
+    println!("{}", TOOLS_HELP);
     
     loop {
         print!("map> ");
@@ -367,61 +1402,100 @@ fn interact_with_hashmap() {
         
         let parts: Vec<&str> = input.split_whitespace().collect();
         
-        match parts.get(0).map(|s| *s) {
-            Some("add") => {
-                if let (Some(key), Some(val_str)) = (parts.get(1), parts.get(2)) {
-                    if let Ok(val) = val_str.parse::<i32>() {
-                        map.insert(key.to_string(), val);
-                        println!("✓ Set {} = {}. Map size: {}", key, val, map.len());
-                    } else {
-                        println!("✗ Invalid number");
-                    }
+        match parts.first().copied() {
+            Some("trace") => trace_command(&mut trace, &parts),
+            Some("replay") => replay(&HASHMAP_EDITOR, &parts),
+            Some("exit") => break,
+            Some(_) => run_command(&HASHMAP_EDITOR, &mut map, &mut trace, &parts),
+            None => {}
+        }
+    }
+}
+
+const HASHMAP_EDITOR: Editor<HashMap<String, i32>> = Editor {
+    name: "hashmap",
+    apply: hashmap_command,
+    state: |map| trace::entries(map.iter()),
+};
+
+fn hashmap_command(map: &mut HashMap<String, i32>, parts: &[&str]) {
+    match parts.first().copied() {
+        Some("add") => {
+            if let (Some(key), Some(val_str)) = (parts.get(1), parts.get(2)) {
+                if let Ok(val) = val_str.parse::<i32>() {
+                    map.insert(key.to_string(), val);
+                    println!("✓ Set {} = {}. Map size: {}", key, val, map.len());
                 } else {
-                    println!("✗ Usage: add <key> <value>");
+                    println!("✗ Invalid number");
                 }
+            } else {
+                println!("✗ Usage: add <key> <value>");
             }
-            Some("get") => {
-                if let Some(key) = parts.get(1) {
-                    if let Some(val) = map.get(*key) {
-                        println!("✓ {} = {}", key, val);
-                    } else {
-                        println!("✗ Key not found");
-                    }
+        }
+        Some("get") => {
+            if let Some(key) = parts.get(1) {
+                if let Some(val) = map.get(*key) {
+                    println!("✓ {} = {}", key, val);
                 } else {
-                    println!("✗ Usage: get <key>");
+                    println!("✗ Key not found");
                 }
+            } else {
+                println!("✗ Usage: get <key>");
             }
-            Some("remove") => {
-                if let Some(key) = parts.get(1) {
-                    if let Some(val) = map.remove(*key) {
-                        println!("✓ Removed {} (value was {}). Map size: {}", key, val, map.len());
-                    } else {
-                        println!("✗ Key not found");
-                    }
+        }
+        Some("remove") => {
+            if let Some(key) = parts.get(1) {
+                if let Some(val) = map.remove(*key) {
+                    println!("✓ Removed {} (value was {}). Map size: {}", key, val, map.len());
                 } else {
-                    println!("✗ Usage: remove <key>");
+                    println!("✗ Key not found");
                 }
+            } else {
+                println!("✗ Usage: remove <key>");
             }
-            Some("show") => {
-                if map.is_empty() {
-                    println!("Map is empty");
-                } else {
-                    println!("Map: {:?}", map);
-                }
+        }
+        Some("retain") => {
+            if let Some(predicate) = entry_predicate(&parts[1..], map.iter()) {
+                let before = map.len();
+                map.retain(|k, v| predicate.matches_entry(k, *v) == Ok(true));
+                println!("✓ Removed {} entries. Map size: {}", before - map.len(), map.len());
             }
-            Some("exit") => break,
-            Some(_) => println!("Unknown command. Try: add, get, remove, show, exit"),
-            None => {}
         }
+        Some("find") => find_entries(&parts[1..], map.iter().map(|(k, v)| (k.clone(), *v)).collect()),
+        Some("count") => count_entries(&parts[1..], map.iter().map(|(k, v)| (k.clone(), *v)).collect()),
+        Some("pipe" | "map" | "filter") => {
+            if let Some(result) = pipe_entries(parts, map.iter().map(|(k, v)| (k.clone(), *v)).collect()) {
+                *map = result.into_iter().collect();
+                println!("✓ Stored {} entries. Map: {:?}", map.len(), map);
+            }
+        }
+        Some("import") => {
+            if let Some(pairs) = import_pairs(&parts[1..]) {
+                map.extend(pairs);
+                println!("Map size: {}", map.len());
+            }
+        }
+        Some("export") => export_pairs(&parts[1..], map.iter().map(|(k, v)| (k.clone(), *v)).collect()),
+        Some("draw") => println!("{}", draw::hashmap(map)),
+        Some("show") => {
+            if map.is_empty() {
+                println!("Map is empty");
+            } else {
+                println!("Map: {:?}", map);
+            }
+        }
+        _ => println!("Unknown command. Try: add, get, remove, show, {}, exit", TOOLS_HINT),
     }
 }
 
 fn interact_with_btreemap() {
     let mut map: BTreeMap<String, i32> = BTreeMap::new();
+    let mut trace = Trace::new("btreemap");
     println!("\n--- BTREEMAP Editor (Sorted) ---");
     // KEY METHODS: insert(), get(), remove(), len(), range()
     // BTREEMAP: Ordered by keys, O(log n), use when you need sorted order or range queries
     println!("Commands: add <key> <value> | get <key> | remove <key> | show | exit");
+    println!("{}", TOOLS_HELP);
     
     loop {
         print!("btree_map> ");
@@ -433,61 +1507,101 @@ fn interact_with_btreemap() {
         
         let parts: Vec<&str> = input.split_whitespace().collect();
         
-        match parts.get(0).map(|s| *s) {
-            Some("add") => {
-                if let (Some(key), Some(val_str)) = (parts.get(1), parts.get(2)) {
-                    if let Ok(val) = val_str.parse::<i32>() {
-                        map.insert(key.to_string(), val);
-                        println!("✓ Set {} = {}. Map size: {}", key, val, map.len());
-                    } else {
-                        println!("✗ Invalid number");
-                    }
+        match parts.first().copied() {
+            Some("trace") => trace_command(&mut trace, &parts),
+            Some("replay") => replay(&BTREEMAP_EDITOR, &parts),
+            Some("exit") => break,
+            Some(_) => run_command(&BTREEMAP_EDITOR, &mut map, &mut trace, &parts),
+            None => {}
+        }
+    }
+}
+
+const BTREEMAP_EDITOR: Editor<BTreeMap<String, i32>> = Editor {
+    name: "btreemap",
+    apply: btreemap_command,
+    state: |map| trace::entries(map.iter()),
+};
+
+fn btreemap_command(map: &mut BTreeMap<String, i32>, parts: &[&str]) {
+    match parts.first().copied() {
+        Some("add") => {
+            if let (Some(key), Some(val_str)) = (parts.get(1), parts.get(2)) {
+                if let Ok(val) = val_str.parse::<i32>() {
+                    map.insert(key.to_string(), val);
+                    println!("✓ Set {} = {}. Map size: {}", key, val, map.len());
                 } else {
-                    println!("✗ Usage: add <key> <value>");
+                    println!("✗ Invalid number");
                 }
+            } else {
+                println!("✗ Usage: add <key> <value>");
             }
-            Some("get") => {
-                if let Some(key) = parts.get(1) {
-                    if let Some(val) = map.get(*key) {
-                        println!("✓ {} = {}", key, val);
-                    } else {
-                        println!("✗ Key not found");
-                    }
+        }
+        Some("get") => {
+            if let Some(key) = parts.get(1) {
+                if let Some(val) = map.get(*key) {
+                    println!("✓ {} = {}", key, val);
                 } else {
-                    println!("✗ Usage: get <key>");
+                    println!("✗ Key not found");
                 }
+            } else {
+                println!("✗ Usage: get <key>");
             }
-            Some("remove") => {
-                if let Some(key) = parts.get(1) {
-                    if let Some(val) = map.remove(*key) {
-                        println!("✓ Removed {} (value was {}). Map size: {}", key, val, map.len());
-                    } else {
-                        println!("✗ Key not found");
-                    }
+        }
+        Some("remove") => {
+            if let Some(key) = parts.get(1) {
+                if let Some(val) = map.remove(*key) {
+                    println!("✓ Removed {} (value was {}). Map size: {}", key, val, map.len());
                 } else {
-                    println!("✗ Usage: remove <key>");
+                    println!("✗ Key not found");
                 }
+            } else {
+                println!("✗ Usage: remove <key>");
             }
-            Some("show") => {
-                if map.is_empty() {
-                    println!("Map is empty");
-                } else {
-                    println!("Map (sorted): {:?}", map);
-                }
+        }
+        Some("retain") => {
+            if let Some(predicate) = entry_predicate(&parts[1..], map.iter()) {
+                let before = map.len();
+                map.retain(|k, v| predicate.matches_entry(k, *v) == Ok(true));
+                println!("✓ Removed {} entries. Map size: {}", before - map.len(), map.len());
+            }
+        }
+        Some("find") => find_entries(&parts[1..], map.iter().map(|(k, v)| (k.clone(), *v)).collect()),
+        Some("count") => count_entries(&parts[1..], map.iter().map(|(k, v)| (k.clone(), *v)).collect()),
+        Some("pipe" | "map" | "filter") => {
+            if let Some(result) = pipe_entries(parts, map.iter().map(|(k, v)| (k.clone(), *v)).collect()) {
+                *map = result.into_iter().collect();
+                println!("✓ Stored {} entries. Map: {:?}", map.len(), map);
+            }
+        }
+        Some("import") => {
+            if let Some(pairs) = import_pairs(&parts[1..]) {
+                map.extend(pairs);
+                println!("Map size: {}", map.len());
+            }
+        }
+        Some("export") => export_pairs(&parts[1..], map.iter().map(|(k, v)| (k.clone(), *v)).collect()),
+        Some("draw") => println!("{}", draw::btreemap(map)),
+        Some("show") => {
+            if map.is_empty() {
+                println!("Map is empty");
+            } else {
+                println!("Map (sorted): {:?}", map);
             }
-            Some("exit") => break,
-            Some(_) => println!("Unknown command. Try: add, get, remove, show, exit"),
-            None => {}
         }
+        _ => println!("Unknown command. Try: add, get, remove, show, {}, exit", TOOLS_HINT),
     }
 }
 
 fn interact_with_hashset() {
     let mut set: HashSet<i32> = HashSet::new();
+    let mut trace = Trace::new("hashset");
     println!("\n--- HASHSET Editor ---");
     // KEY METHODS: insert(), remove(), contains(), len()
     // HASHSET: Unordered, O(1) average, use for membership testing and deduplication
     println!("Commands: add <value> | remove <value> | contains <value> | show | exit");
+    println!("          sketch bloom|cuckoo [fp_rate] [save <path>] (probabilistic copy)");
+    println!("{}", TOOLS_HELP);
     
     loop {
         print!("set> ");
@@ -499,72 +1613,187 @@ fn interact_with_hashset() {
         
         let parts: Vec<&str> = input.split_whitespace().collect();
         
-        match parts.get(0).map(|s| *s) {
-            Some("add") => {
-                if let Some(val_str) = parts.get(1) {
-                    if let Ok(val) = val_str.parse::<i32>() {
-                        if set.insert(val) {
-                            println!("✓ Added {}. Set size: {}", val, set.len());
-                        } else {
-                            println!("⚠ {} already in set", val);
-                        }
+        match parts.first().copied() {
+            Some("trace") => trace_command(&mut trace, &parts),
+            Some("replay") => replay(&HASHSET_EDITOR, &parts),
+            Some("exit") => break,
+            Some(_) => run_command(&HASHSET_EDITOR, &mut set, &mut trace, &parts),
+            None => {}
+        }
+    }
+}
+
+const HASHSET_EDITOR: Editor<HashSet<i32>> = Editor {
+    name: "hashset",
+    apply: hashset_command,
+    state: |set| trace::items(set.iter()),
+};
+
+fn hashset_command(set: &mut HashSet<i32>, parts: &[&str]) {
+    match parts.first().copied() {
+        Some("add") => {
+            if let Some(val_str) = parts.get(1) {
+                if let Ok(val) = val_str.parse::<i32>() {
+                    if set.insert(val) {
+                        println!("✓ Added {}. Set size: {}", val, set.len());
                     } else {
-                        println!("✗ Invalid number");
+                        println!("⚠ {} already in set", val);
                     }
                 } else {
-                    println!("✗ Usage: add <value>");
+                    println!("✗ Invalid number");
                 }
+            } else {
+                println!("✗ Usage: add <value>");
             }
-            Some("remove") => {
-                if let Some(val_str) = parts.get(1) {
-                    if let Ok(val) = val_str.parse::<i32>() {
-                        if set.remove(&val) {
-                            println!("✓ Removed {}. Set size: {}", val, set.len());
-                        } else {
-                            println!("✗ {} not in set", val);
-                        }
+        }
+        Some("remove") => {
+            if let Some(val_str) = parts.get(1) {
+                if let Ok(val) = val_str.parse::<i32>() {
+                    if set.remove(&val) {
+                        println!("✓ Removed {}. Set size: {}", val, set.len());
                     } else {
-                        println!("✗ Invalid number");
+                        println!("✗ {} not in set", val);
                     }
                 } else {
-                    println!("✗ Usage: remove <value>");
+                    println!("✗ Invalid number");
                 }
+            } else {
+                println!("✗ Usage: remove <value>");
             }
-            Some("contains") => {
-                if let Some(val_str) = parts.get(1) {
-                    if let Ok(val) = val_str.parse::<i32>() {
-                        if set.contains(&val) {
-                            println!("✓ {} is in the set", val);
-                        } else {
-                            println!("✗ {} is NOT in the set", val);
-                        }
+        }
+        Some("contains") => {
+            if let Some(val_str) = parts.get(1) {
+                if let Ok(val) = val_str.parse::<i32>() {
+                    if set.contains(&val) {
+                        println!("✓ {} is in the set", val);
                     } else {
-                        println!("✗ Invalid number");
+                        println!("✗ {} is NOT in the set", val);
                     }
                 } else {
-                    println!("✗ Usage: contains <value>");
+                    println!("✗ Invalid number");
                 }
+            } else {
+                println!("✗ Usage: contains <value>");
             }
-            Some("show") => {
-                if set.is_empty() {
-                    println!("Set is empty");
-                } else {
-                    println!("Set: {:?}", set);
-                }
+        }
+        Some("retain") => {
+            if let Some(predicate) = value_predicate(&parts[1..], set.iter().copied()) {
+                let before = set.len();
+                set.retain(|&x| predicate.matches(x) == Ok(true));
+                println!("✓ Removed {} elements. Set size: {}", before - set.len(), set.len());
+            }
+        }
+        Some("find") => find_values(&parts[1..], set.iter().copied().collect(), false),
+        Some("count") => count_values(&parts[1..], set.iter().copied().collect()),
+        Some("pipe" | "map" | "filter") => {
+            if let Some(result) = pipe_values(parts, set.iter().copied().collect()) {
+                *set = result.into_iter().collect();
+                println!("✓ Stored {} distinct elements. Set: {:?}", set.len(), set);
             }
-            Some("exit") => break,
-            Some(_) => println!("Unknown command. Try: add, remove, contains, show, exit"),
-            None => {}
         }
+        Some("import") => {
+            if let Some(values) = import_values(&parts[1..]) {
+                set.extend(values);
+                println!("Set size: {}", set.len());
+            }
+        }
+        Some("export") => export_values(&parts[1..], set.iter().copied().collect()),
+        Some("draw") => println!("{}", draw::hashset(set)),
+        Some("sketch") => sketch_command(set, &parts[1..]),
+        Some("show") => {
+            if set.is_empty() {
+                println!("Set is empty");
+            } else {
+                println!("Set: {:?}", set);
+            }
+        }
+        _ => println!("Unknown command. Try: add, remove, contains, sketch, show, {}, exit", TOOLS_HINT),
+    }
+}
+
+/// Builds a Bloom or cuckoo filter from the set and compares it with the
+/// exact set: memory used and false positives measured on values not in it.
+fn sketch_command(set: &HashSet<i32>, args: &[&str]) {
+    let usage = "✗ Usage: sketch bloom|cuckoo [fp_rate] [save <path>]";
+    let Some((&kind, mut rest)) = args.split_first() else {
+        println!("{}", usage);
+        return;
+    };
+    let mut fp_rate = 0.01;
+    if let Some(rate) = rest.first().and_then(|r| r.parse::<f64>().ok()) {
+        if !(rate > 0.0 && rate < 1.0) {
+            println!("✗ fp_rate must be between 0 and 1");
+            return;
+        }
+        fp_rate = rate;
+        rest = &rest[1..];
+    }
+    let save_to = match rest {
+        [] => None,
+        ["save", path] => Some(*path),
+        _ => {
+            println!("{}", usage);
+            return;
+        }
+    };
+
+    let bytes = |x: i32| x.to_le_bytes();
+    let absent = (i32::MIN..).filter(|x| !set.contains(x)).take(10_000).map(bytes);
+    let exact_bytes = set.capacity() * std::mem::size_of::<i32>();
+    let saved = match kind {
+        "bloom" => {
+            let mut bloom = BloomFilter::with_rate(set.len(), fp_rate);
+            set.iter().for_each(|&x| bloom.insert(&bytes(x)));
+            println!(
+                "✓ Bloom filter: {} bits, {} hashes ({} bytes vs ~{} for the set)",
+                bloom.num_bits(),
+                bloom.hashes(),
+                bloom.num_bits().div_ceil(8),
+                exact_bytes
+            );
+            println!(
+                "  False positives: {:.3}% measured, {:.3}% expected",
+                false_positive_rate(&bloom, absent) * 100.0,
+                bloom.estimated_fp_rate() * 100.0
+            );
+            save_to.map(|path| (path, bloom.save(path)))
+        }
+        "cuckoo" => {
+            let mut cuckoo = CuckooFilter::with_capacity(set.len());
+            let failed = set.iter().filter(|&&x| cuckoo.insert(&bytes(x)).is_err()).count();
+            println!(
+                "✓ Cuckoo filter: {} slots, load {:.0}% ({} bytes vs ~{} for the set)",
+                cuckoo.capacity(),
+                cuckoo.load_factor() * 100.0,
+                cuckoo.capacity() * 2,
+                exact_bytes
+            );
+            if failed > 0 {
+                println!("⚠ {} values did not fit", failed);
+            }
+            println!("  False positives: {:.3}% measured", false_positive_rate(&cuckoo, absent) * 100.0);
+            save_to.map(|path| (path, cuckoo.save(path)))
+        }
+        _ => {
+            println!("{}", usage);
+            return;
+        }
+    };
+    match saved {
+        Some((path, Ok(()))) => println!("✓ Saved filter to {}", path),
+        Some((path, Err(e))) => println!("✗ Could not write {}: {}", path, e),
+        None => {}
     }
 }
 
 fn interact_with_btreeset() {
     let mut set: BTreeSet<i32> = BTreeSet::new();
+    let mut trace = Trace::new("btreeset");
     println!("\n--- BTREESET Editor (Sorted) ---");
     // KEY METHODS: insert(), remove(), contains(), len(), range()
     // BTREESET: Ordered, O(log n), use when you need sorted iteration or range queries
     println!("Commands: add <value> | remove <value> | contains <value> | show | exit");
+    println!("{}", TOOLS_HELP);
     
     loop {
         print!("btree_set> ");
@@ -576,73 +1805,112 @@ fn interact_with_btreeset() {
         
         let parts: Vec<&str> = input.split_whitespace().collect();
         
-        match parts.get(0).map(|s| *s) {
-            Some("add") => {
-                if let Some(val_str) = parts.get(1) {
-                    if let Ok(val) = val_str.parse::<i32>() {
-                        if set.insert(val) {
-                            println!("✓ Added {}. Set size: {}", val, set.len());
-                        } else {
-                            println!("⚠ {} already in set", val);
-                        }
+        match parts.first().copied() {
+            Some("trace") => trace_command(&mut trace, &parts),
+            Some("replay") => replay(&BTREESET_EDITOR, &parts),
+            Some("exit") => break,
+            Some(_) => run_command(&BTREESET_EDITOR, &mut set, &mut trace, &parts),
+            None => {}
+        }
+    }
+}
+
+const BTREESET_EDITOR: Editor<BTreeSet<i32>> = Editor {
+    name: "btreeset",
+    apply: btreeset_command,
+    state: |set| trace::items(set.iter()),
+};
+
+fn btreeset_command(set: &mut BTreeSet<i32>, parts: &[&str]) {
+    match parts.first().copied() {
+        Some("add") => {
+            if let Some(val_str) = parts.get(1) {
+                if let Ok(val) = val_str.parse::<i32>() {
+                    if set.insert(val) {
+                        println!("✓ Added {}. Set size: {}", val, set.len());
                     } else {
-                        println!("✗ Invalid number");
+                        println!("⚠ {} already in set", val);
                     }
                 } else {
-                    println!("✗ Usage: add <value>");
+                    println!("✗ Invalid number");
                 }
+            } else {
+                println!("✗ Usage: add <value>");
             }
-            Some("remove") => {
-                if let Some(val_str) = parts.get(1) {
-                    if let Ok(val) = val_str.parse::<i32>() {
-                        if set.remove(&val) {
-                            println!("✓ Removed {}. Set size: {}", val, set.len());
-                        } else {
-                            println!("✗ {} not in set", val);
-                        }
+        }
+        Some("remove") => {
+            if let Some(val_str) = parts.get(1) {
+                if let Ok(val) = val_str.parse::<i32>() {
+                    if set.remove(&val) {
+                        println!("✓ Removed {}. Set size: {}", val, set.len());
                     } else {
-                        println!("✗ Invalid number");
+                        println!("✗ {} not in set", val);
                     }
                 } else {
-                    println!("✗ Usage: remove <value>");
+                    println!("✗ Invalid number");
                 }
+            } else {
+                println!("✗ Usage: remove <value>");
             }
-            Some("contains") => {
-                if let Some(val_str) = parts.get(1) {
-                    if let Ok(val) = val_str.parse::<i32>() {
-                        if set.contains(&val) {
-                            println!("✓ {} is in the set", val);
-                        } else {
-                            println!("✗ {} is NOT in the set", val);
-                        }
+        }
+        Some("contains") => {
+            if let Some(val_str) = parts.get(1) {
+                if let Ok(val) = val_str.parse::<i32>() {
+                    if set.contains(&val) {
+                        println!("✓ {} is in the set", val);
                     } else {
-                        println!("✗ Invalid number");
+                        println!("✗ {} is NOT in the set", val);
                     }
                 } else {
-                    println!("✗ Usage: contains <value>");
+                    println!("✗ Invalid number");
                 }
+            } else {
+                println!("✗ Usage: contains <value>");
             }
-            Some("show") => {
-                if set.is_empty() {
-                    println!("Set is empty");
-                } else {
-                    println!("Set (sorted): {:?}", set);
-                }
+        }
+        Some("retain") => {
+            if let Some(predicate) = value_predicate(&parts[1..], set.iter().copied()) {
+                let before = set.len();
+                set.retain(|&x| predicate.matches(x) == Ok(true));
+                println!("✓ Removed {} elements. Set size: {}", before - set.len(), set.len());
+            }
+        }
+        Some("find") => find_values(&parts[1..], set.iter().copied().collect(), false),
+        Some("count") => count_values(&parts[1..], set.iter().copied().collect()),
+        Some("pipe" | "map" | "filter") => {
+            if let Some(result) = pipe_values(parts, set.iter().copied().collect()) {
+                *set = result.into_iter().collect();
+                println!("✓ Stored {} distinct elements. Set: {:?}", set.len(), set);
+            }
+        }
+        Some("import") => {
+            if let Some(values) = import_values(&parts[1..]) {
+                set.extend(values);
+                println!("Set size: {}", set.len());
+            }
+        }
+        Some("export") => export_values(&parts[1..], set.iter().copied().collect()),
+        Some("draw") => println!("{}", draw::btreeset(set)),
+        Some("show") => {
+            if set.is_empty() {
+                println!("Set is empty");
+            } else {
+                println!("Set (sorted): {:?}", set);
             }
-            Some("exit") => break,
-            Some(_) => println!("Unknown command. Try: add, remove, contains, show, exit"),
-            None => {}
         }
+        _ => println!("Unknown command. Try: add, remove, contains, show, {}, exit", TOOLS_HINT),
     }
 }
 
 fn interact_with_binaryheap() {
     let mut heap: BinaryHeap<i32> = BinaryHeap::new();
+    let mut trace = Trace::new("binaryheap");
     println!("\n--- BINARYHEAP Editor (Max-Heap) ---");
     // KEY METHODS: push(), pop(), peek(), len()
     // BINARYHEAP: Priority queue, O(1) peek, O(log n) pop. Max-element always at top.
     // Use cases: task scheduling, Dijkstra algorithm, Huffman coding, event simulation
     println!("Commands: add <value> | pop | peek | show | exit");
+    println!("{}", TOOLS_HELP);
     
     loop {
         print!("heap> ");
@@ -654,43 +1922,763 @@ fn interact_with_binaryheap() {
         
         let parts: Vec<&str> = input.split_whitespace().collect();
         
-        match parts.get(0).map(|s| *s) {
-            Some("add") => {
-                if let Some(val_str) = parts.get(1) {
-                    if let Ok(val) = val_str.parse::<i32>() {
-                        heap.push(val);
-                        println!("✓ Added {}. Heap size: {}", val, heap.len());
-                    } else {
-                        println!("✗ Invalid number");
-                    }
+        match parts.first().copied() {
+            Some("trace") => trace_command(&mut trace, &parts),
+            Some("replay") => replay(&BINARYHEAP_EDITOR, &parts),
+            Some("exit") => break,
+            Some(_) => run_command(&BINARYHEAP_EDITOR, &mut heap, &mut trace, &parts),
+            None => {}
+        }
+    }
+}
+
+const BINARYHEAP_EDITOR: Editor<BinaryHeap<i32>> = Editor {
+    name: "binaryheap",
+    apply: binaryheap_command,
+    state: |heap| trace::items(heap.iter()),
+};
+
+fn binaryheap_command(heap: &mut BinaryHeap<i32>, parts: &[&str]) {
+    match parts.first().copied() {
+        Some("add") => {
+            if let Some(val_str) = parts.get(1) {
+                if let Ok(val) = val_str.parse::<i32>() {
+                    heap.push(val);
+                    println!("✓ Added {}. Heap size: {}", val, heap.len());
                 } else {
-                    println!("✗ Usage: add <value>");
+                    println!("✗ Invalid number");
                 }
+            } else {
+                println!("✗ Usage: add <value>");
             }
-            Some("pop") => {
-                if let Some(val) = heap.pop() {
-                    println!("✓ Removed max element: {}. Heap size: {}", val, heap.len());
+        }
+        Some("pop") => {
+            if let Some(val) = heap.pop() {
+                println!("✓ Removed max element: {}. Heap size: {}", val, heap.len());
+            } else {
+                println!("✗ Heap is empty");
+            }
+        }
+        Some("peek") => {
+            if let Some(val) = heap.peek() {
+                println!("✓ Max element: {}", val);
+            } else {
+                println!("✗ Heap is empty");
+            }
+        }
+        Some("retain") => {
+            if let Some(predicate) = value_predicate(&parts[1..], heap.iter().copied()) {
+                let before = heap.len();
+                heap.retain(|&x| predicate.matches(x) == Ok(true));
+                println!("✓ Removed {} elements. Heap size: {}", before - heap.len(), heap.len());
+            }
+        }
+        Some("find") => find_values(&parts[1..], heap.iter().copied().collect(), false),
+        Some("count") => count_values(&parts[1..], heap.iter().copied().collect()),
+        Some("pipe" | "map" | "filter") => {
+            if let Some(result) = pipe_values(parts, heap.clone().into_sorted_vec().into_iter().rev().collect()) {
+                *heap = result.into();
+                println!("✓ Stored {} elements. Heap: {:?}", heap.len(), heap);
+            }
+        }
+        Some("import") => {
+            if let Some(values) = import_values(&parts[1..]) {
+                heap.extend(values);
+                println!("Heap size: {}", heap.len());
+            }
+        }
+        Some("export") => export_values(&parts[1..], heap.clone().into_sorted_vec().into_iter().rev().collect()),
+        Some("draw") => println!("{}", draw::binaryheap(heap)),
+        Some("show") => {
+            if heap.is_empty() {
+                println!("Heap is empty");
+            } else {
+                println!("Heap (max at top): {:?}", heap);
+            }
+        }
+        _ => println!("Unknown command. Try: add, pop, peek, show, {}, exit", TOOLS_HINT),
+    }
+}
+
+/// Named keyed collections, for commands that combine several of them.
+type Workspace = BTreeMap<String, Vec<(String, i32)>>;
+
+fn interact_with_workspace() {
+    let mut workspace = Workspace::new();
+    let mut trace = Trace::new("workspace");
+    println!("\n--- WORKSPACE Editor ---");
+    // Each collection is a list of key/value rows; keys may repeat.
+    println!("Commands: new <name> | add <name> <key> <value> | load <name> <path> [import options]");
+    println!("          list | show <name> | drop <name> | exit");
+    println!("          join <a> <b> on key|value [inner|left|right|full|anti] [hash|merge]");
+    println!("Tools: trace on|off|show|clear|save <path> | replay <path>");
+
+    loop {
+        print!("workspace> ");
+        io::stdout().flush().unwrap();
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        let input = input.trim();
+
+        let parts: Vec<&str> = input.split_whitespace().collect();
+
+        match parts.first().copied() {
+            Some("trace") => trace_command(&mut trace, &parts),
+            Some("replay") => replay(&WORKSPACE_EDITOR, &parts),
+            Some("exit") => break,
+            Some(_) => run_command(&WORKSPACE_EDITOR, &mut workspace, &mut trace, &parts),
+            None => {}
+        }
+    }
+}
+
+const WORKSPACE_EDITOR: Editor<Workspace> = Editor {
+    name: "workspace",
+    apply: workspace_command,
+    state: |workspace| {
+        workspace
+            .iter()
+            .flat_map(|(name, rows)| rows.iter().map(move |(k, v)| format!("{}:{}={}", name, k, v)))
+            .collect()
+    },
+};
+
+fn workspace_command(workspace: &mut Workspace, parts: &[&str]) {
+    match parts.first().copied() {
+        Some("new") => {
+            if let Some(name) = parts.get(1) {
+                if workspace.contains_key(*name) {
+                    println!("✗ '{}' already exists", name);
                 } else {
-                    println!("✗ Heap is empty");
+                    workspace.insert(name.to_string(), Vec::new());
+                    println!("✓ Created '{}'", name);
                 }
+            } else {
+                println!("✗ Usage: new <name>");
             }
-            Some("peek") => {
-                if let Some(val) = heap.peek() {
-                    println!("✓ Max element: {}", val);
-                } else {
-                    println!("✗ Heap is empty");
+        }
+        Some("add") => {
+            if let (Some(name), Some(key), Some(val_str)) = (parts.get(1), parts.get(2), parts.get(3)) {
+                match (workspace.get_mut(*name), val_str.parse::<i32>()) {
+                    (Some(rows), Ok(val)) => {
+                        rows.push((key.to_string(), val));
+                        println!("✓ Added {} = {} to '{}' ({} rows)", key, val, name, rows.len());
+                    }
+                    (None, _) => println!("✗ No collection named '{}'", name),
+                    (_, Err(_)) => println!("✗ Invalid number"),
                 }
+            } else {
+                println!("✗ Usage: add <name> <key> <value>");
             }
-            Some("show") => {
-                if heap.is_empty() {
-                    println!("Heap is empty");
+        }
+        Some("load") => {
+            if let Some(name) = parts.get(1) {
+                if let Some(pairs) = import_pairs(&parts[2..]) {
+                    let rows = workspace.entry(name.to_string()).or_default();
+                    rows.extend(pairs);
+                    println!("'{}' rows: {}", name, rows.len());
+                }
+            } else {
+                println!("✗ Usage: load <name> <path> [import options]");
+            }
+        }
+        Some("drop") => {
+            if let Some(name) = parts.get(1) {
+                match workspace.remove(*name) {
+                    Some(rows) => println!("✓ Dropped '{}' ({} rows)", name, rows.len()),
+                    None => println!("✗ No collection named '{}'", name),
+                }
+            } else {
+                println!("✗ Usage: drop <name>");
+            }
+        }
+        Some("list") => {
+            if workspace.is_empty() {
+                println!("Workspace is empty");
+            }
+            for (name, rows) in workspace.iter() {
+                println!("  {} ({} rows)", name, rows.len());
+            }
+        }
+        Some("show") => match parts.get(1).map(|name| (name, workspace.get(*name))) {
+            Some((_, Some(rows))) => {
+                for (k, v) in rows {
+                    println!("  {} = {}", k, v);
+                }
+                println!("({} rows)", rows.len());
+            }
+            Some((name, None)) => println!("✗ No collection named '{}'", name),
+            None => println!("✗ Usage: show <name>"),
+        },
+        Some("join") => join_command(workspace, &parts[1..]),
+        _ => println!("Unknown command. Try: new, add, load, list, show, drop, join, exit"),
+    }
+}
+
+/// `join <a> <b> on key|value [kind] [hash|merge]`.
+fn join_command(workspace: &Workspace, args: &[&str]) {
+    let usage = "✗ Usage: join <a> <b> on key|value [inner|left|right|full|anti] [hash|merge]";
+    let (Some(a), Some(b), Some(&"on"), Some(column)) = (args.first(), args.get(1), args.get(2), args.get(3)) else {
+        println!("{}", usage);
+        return;
+    };
+    let (Some(left), Some(right)) = (workspace.get(*a), workspace.get(*b)) else {
+        let missing = if workspace.contains_key(*a) { b } else { a };
+        println!("✗ No collection named '{}'", missing);
+        return;
+    };
+
+    let mut kind = JoinKind::Inner;
+    let mut strategy = Strategy::Hash;
+    for option in &args[4..] {
+        match (JoinKind::from_name(option), *option) {
+            (Some(k), _) => kind = k,
+            (None, "hash") => strategy = Strategy::Hash,
+            (None, "merge") => strategy = Strategy::SortMerge,
+            _ => {
+                println!("✗ Unknown join option '{}'", option);
+                println!("{}", usage);
+                return;
+            }
+        }
+    }
+
+    match *column {
+        "key" => print_join(a, b, join::join(left, right, kind, strategy)),
+        "value" => {
+            let by_value = |rows: &[(String, i32)]| -> Vec<(i32, String)> {
+                rows.iter().map(|(k, v)| (*v, k.clone())).collect()
+            };
+            print_join(a, b, join::join(&by_value(left), &by_value(right), kind, strategy));
+        }
+        _ => println!("{}", usage),
+    }
+}
+
+fn print_join<K: Display, A: Display, B: Display>(a: &str, b: &str, result: JoinResult<K, A, B>) {
+    let cell = |value: Option<String>| value.unwrap_or_else(|| String::from("-"));
+    println!("  {:<12} {:<12} {:<12}", "key", a, b);
+    for row in &result.rows {
+        println!(
+            "  {:<12} {:<12} {:<12}",
+            row.key.to_string(),
+            cell(row.left.as_ref().map(|v| v.to_string())),
+            cell(row.right.as_ref().map(|v| v.to_string()))
+        );
+    }
+    let keys = |keys: &[K]| keys.iter().map(|k| k.to_string()).collect::<Vec<_>>().join(", ");
+    println!("✓ {} rows, {} matched pairs", result.rows.len(), result.matches);
+    println!("  Unmatched in {}: [{}]", a, keys(&result.unmatched_left));
+    println!("  Unmatched in {}: [{}]", b, keys(&result.unmatched_right));
+}
+
+/// State of the cache editor: the cache under test and the manual clock its
+/// TTLs are measured against.
+struct CacheLab {
+    cache: Box<dyn Cache<String, i32>>,
+    clock: ManualClock,
+    kind: &'static str,
+}
+
+impl CacheLab {
+    fn new(kind: &'static str, capacity: usize, ttl: Option<Duration>) -> Self {
+        let clock = ManualClock::new();
+        let cache: Box<dyn Cache<String, i32>> = if kind == "lfu" {
+            Box::new(LfuCache::with_clock(capacity, ttl, clock.clone()))
+        } else {
+            Box::new(LruCache::with_clock(capacity, ttl, clock.clone()))
+        };
+        CacheLab { cache, clock, kind }
+    }
+}
+
+impl Default for CacheLab {
+    fn default() -> Self {
+        CacheLab::new("lru", 4, None)
+    }
+}
+
+fn interact_with_cache() {
+    let mut lab = CacheLab::default();
+    let mut trace = Trace::new("cache");
+    println!("\n--- CACHE Editor (LRU capacity 4) ---");
+    // LRU: HashMap + doubly linked list, evicts the least recently used key.
+    // LFU: HashMap + BTreeMap by use count, evicts the least used key.
+    println!("Commands: new lru|lfu <capacity> [ttl <secs>] | put <key> <value> [ttl <secs>]");
+    println!("          get <key> | peek <key> | remove <key> | tick <secs> | purge");
+    println!("          order | stats | show | exit");
+    println!("Tools: trace on|off|show|clear|save <path> | replay <path>");
+
+    loop {
+        print!("cache> ");
+        io::stdout().flush().unwrap();
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        let input = input.trim();
+
+        let parts: Vec<&str> = input.split_whitespace().collect();
+
+        match parts.first().copied() {
+            Some("trace") => trace_command(&mut trace, &parts),
+            Some("replay") => replay(&CACHE_EDITOR, &parts),
+            Some("exit") => break,
+            Some(_) => run_command(&CACHE_EDITOR, &mut lab, &mut trace, &parts),
+            None => {}
+        }
+    }
+}
+
+const CACHE_EDITOR: Editor<CacheLab> = Editor {
+    name: "cache",
+    apply: cache_command,
+    state: |lab| {
+        let mut keys = lab.cache.eviction_order();
+        keys.sort();
+        keys
+    },
+};
+
+/// Parses an optional trailing `ttl <secs>`.
+fn parse_ttl(args: &[&str]) -> Result<Option<Duration>, String> {
+    match args {
+        [] => Ok(None),
+        ["ttl", secs] => secs
+            .parse::<u64>()
+            .map(|s| Some(Duration::from_secs(s)))
+            .map_err(|_| format!("Invalid TTL '{}'", secs)),
+        _ => Err(String::from("Expected 'ttl <secs>'")),
+    }
+}
+
+fn cache_command(lab: &mut CacheLab, parts: &[&str]) {
+    match parts.first().copied() {
+        Some("new") => {
+            let kind = match parts.get(1).copied() {
+                Some("lru") => "lru",
+                Some("lfu") => "lfu",
+                _ => {
+                    println!("✗ Usage: new lru|lfu <capacity> [ttl <secs>]");
+                    return;
+                }
+            };
+            match (parts.get(2).map(|n| n.parse::<usize>()), parse_ttl(parts.get(3..).unwrap_or(&[]))) {
+                (Some(Ok(capacity)), Ok(ttl)) if capacity > 0 => {
+                    *lab = CacheLab::new(kind, capacity, ttl);
+                    println!("✓ New {} cache, capacity {}", kind.to_uppercase(), capacity);
+                }
+                (_, Err(e)) => println!("✗ {}", e),
+                _ => println!("✗ Capacity must be a positive number"),
+            }
+        }
+        Some("put") => {
+            if let (Some(key), Some(val_str)) = (parts.get(1), parts.get(2)) {
+                match (val_str.parse::<i32>(), parse_ttl(&parts[3..])) {
+                    (Ok(val), Ok(ttl)) => {
+                        let evicted = if ttl.is_some() {
+                            lab.cache.put_with_ttl(key.to_string(), val, ttl)
+                        } else {
+                            lab.cache.put(key.to_string(), val)
+                        };
+                        match evicted {
+                            Some((old_key, old_val)) => println!("✓ Put {} = {}, evicted {} = {}", key, val, old_key, old_val),
+                            None => println!("✓ Put {} = {}", key, val),
+                        }
+                    }
+                    (Err(_), _) => println!("✗ Invalid number"),
+                    (_, Err(e)) => println!("✗ {}", e),
+                }
+            } else {
+                println!("✗ Usage: put <key> <value> [ttl <secs>]");
+            }
+        }
+        Some("get") => {
+            if let Some(key) = parts.get(1) {
+                match lab.cache.get(&key.to_string()) {
+                    Some(val) => println!("✓ Hit: {} = {}", key, val),
+                    None => println!("✗ Miss: {}", key),
+                }
+            } else {
+                println!("✗ Usage: get <key>");
+            }
+        }
+        Some("peek") => {
+            if let Some(key) = parts.get(1) {
+                match lab.cache.peek(&key.to_string()) {
+                    Some(val) => println!("✓ {} = {} (order unchanged)", key, val),
+                    None => println!("✗ {} is not cached", key),
+                }
+            } else {
+                println!("✗ Usage: peek <key>");
+            }
+        }
+        Some("remove") => {
+            if let Some(key) = parts.get(1) {
+                match lab.cache.remove(&key.to_string()) {
+                    Some(val) => println!("✓ Removed {} (value was {})", key, val),
+                    None => println!("✗ Key not found"),
+                }
+            } else {
+                println!("✗ Usage: remove <key>");
+            }
+        }
+        Some("tick") => match parts.get(1).map(|s| s.parse::<u64>()) {
+            Some(Ok(secs)) => {
+                lab.clock.advance(Duration::from_secs(secs));
+                println!("✓ Clock at {}s", lab.clock.now().as_secs());
+            }
+            _ => println!("✗ Usage: tick <secs>"),
+        },
+        Some("purge") => println!("✓ Purged {} expired entries", lab.cache.purge_expired()),
+        Some("order") => println!("Eviction order (next first): {:?}", lab.cache.eviction_order()),
+        Some("stats") => {
+            let stats = lab.cache.stats();
+            let rate = stats.hit_rate().map_or(String::from("-"), |r| format!("{:.0}%", r * 100.0));
+            println!(
+                "Hits: {} | Misses: {} | Hit rate: {} | Evictions: {} | Expired: {}",
+                stats.hits, stats.misses, rate, stats.evictions, stats.expirations
+            );
+        }
+        Some("show") => {
+            let entries: Vec<String> = lab
+                .cache
+                .eviction_order()
+                .iter()
+                .filter_map(|k| lab.cache.peek(k).map(|v| format!("{} = {}", k, v)))
+                .collect();
+            println!(
+                "{} cache {}/{} at {}s: [{}]",
+                lab.kind.to_uppercase(),
+                lab.cache.len(),
+                lab.cache.capacity(),
+                lab.clock.now().as_secs(),
+                entries.join(", ")
+            );
+        }
+        _ => println!("Unknown command. Try: new, put, get, peek, remove, tick, purge, order, stats, show, exit"),
+    }
+}
+
+fn interact_with_trie() {
+    let mut trie: TrieMap<i32> = TrieMap::new();
+    let mut trace = Trace::new("trie");
+    println!("\n--- TRIE Editor ---");
+    // Keys share nodes with every key they are a prefix of; lookups cost
+    // O(key length) no matter how many keys are stored.
+    println!("Commands: add <key> <value> | get <key> | remove <key> | show | exit");
+    println!("          prefix <p> | longest <text> | complete <p> [n]");
+    println!("Tools: import <path> | trace on|off|show|clear|save <path> | replay <path>");
+
+    loop {
+        print!("trie> ");
+        io::stdout().flush().unwrap();
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        let input = input.trim();
+
+        let parts: Vec<&str> = input.split_whitespace().collect();
+
+        match parts.first().copied() {
+            Some("trace") => trace_command(&mut trace, &parts),
+            Some("replay") => replay(&TRIE_EDITOR, &parts),
+            Some("exit") => break,
+            Some(_) => run_command(&TRIE_EDITOR, &mut trie, &mut trace, &parts),
+            None => {}
+        }
+    }
+}
+
+const TRIE_EDITOR: Editor<TrieMap<i32>> = Editor {
+    name: "trie",
+    apply: trie_command,
+    state: |trie| trace::entries(trie.entries()),
+};
+
+fn trie_command(trie: &mut TrieMap<i32>, parts: &[&str]) {
+    let print_entries = |entries: Vec<(String, &i32)>| {
+        let shown: Vec<String> = entries.iter().map(|(k, v)| format!("{} = {}", k, v)).collect();
+        println!("✓ {} keys: {}", entries.len(), shown.join(", "));
+    };
+    match parts.first().copied() {
+        Some("add") => {
+            if let (Some(key), Some(val_str)) = (parts.get(1), parts.get(2)) {
+                if let Ok(val) = val_str.parse::<i32>() {
+                    trie.insert(key, val);
+                    println!("✓ Set {} = {}. Trie size: {}", key, val, trie.len());
                 } else {
-                    println!("Heap (max at top): {:?}", heap);
+                    println!("✗ Invalid number");
+                }
+            } else {
+                println!("✗ Usage: add <key> <value>");
+            }
+        }
+        Some("get") => {
+            if let Some(key) = parts.get(1) {
+                match trie.get(key) {
+                    Some(val) => println!("✓ {} = {}", key, val),
+                    None => println!("✗ Key not found"),
+                }
+            } else {
+                println!("✗ Usage: get <key>");
+            }
+        }
+        Some("remove") => {
+            if let Some(key) = parts.get(1) {
+                match trie.remove(key) {
+                    Some(val) => println!("✓ Removed {} (value was {}). Trie size: {}", key, val, trie.len()),
+                    None => println!("✗ Key not found"),
+                }
+            } else {
+                println!("✗ Usage: remove <key>");
+            }
+        }
+        Some("prefix") => print_entries(trie.with_prefix(parts.get(1).copied().unwrap_or(""))),
+        Some("longest") => {
+            if let Some(text) = parts.get(1) {
+                match trie.longest_prefix(text) {
+                    Some((key, val)) => println!("✓ Longest stored prefix of {}: {} = {}", text, key, val),
+                    None => println!("✗ No stored key is a prefix of {}", text),
+                }
+            } else {
+                println!("✗ Usage: longest <text>");
+            }
+        }
+        Some("complete") => {
+            let limit = match parts.get(2).map(|n| n.parse::<usize>()) {
+                None => 5,
+                Some(Ok(n)) => n,
+                Some(Err(_)) => {
+                    println!("✗ Usage: complete <prefix> [n]");
+                    return;
+                }
+            };
+            print_entries(trie.autocomplete(parts.get(1).copied().unwrap_or(""), limit));
+        }
+        Some("import") => {
+            if let Some(pairs) = import_pairs(&parts[1..]) {
+                for (key, val) in pairs {
+                    trie.insert(&key, val);
                 }
+                println!("Trie size: {}", trie.len());
             }
+        }
+        Some("show") => {
+            if trie.is_empty() {
+                println!("Trie is empty");
+            } else {
+                print_entries(trie.entries());
+            }
+        }
+        _ => println!("Unknown command. Try: add, get, remove, prefix, longest, complete, import, show, exit"),
+    }
+}
+
+fn interact_with_graph() {
+    let mut graph = Graph::default();
+    let mut trace = Trace::new("graph");
+    println!("\n--- GRAPH Editor ---");
+    // Adjacency lists: each node keeps its outgoing edges, so walking a
+    // node's neighbours costs O(degree) rather than O(nodes).
+    println!("Commands: new directed|undirected | edge <from> <to> [weight] | node <name> | show | exit");
+    println!("          bfs <start> | dfs <start> | path <from> <to> | topo | components");
+    println!("Tools: load <path> [undirected] | trace on|off|show|clear|save <path> | replay <path>");
+
+    loop {
+        print!("graph> ");
+        io::stdout().flush().unwrap();
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        let input = input.trim();
+
+        let parts: Vec<&str> = input.split_whitespace().collect();
+
+        match parts.first().copied() {
+            Some("trace") => trace_command(&mut trace, &parts),
+            Some("replay") => replay(&GRAPH_EDITOR, &parts),
             Some("exit") => break,
-            Some(_) => println!("Unknown command. Try: add, pop, peek, show, exit"),
+            Some(_) => run_command(&GRAPH_EDITOR, &mut graph, &mut trace, &parts),
             None => {}
         }
     }
 }
+
+const GRAPH_EDITOR: Editor<Graph> = Editor {
+    name: "graph",
+    apply: graph_command,
+    state: graph_edges,
+};
+
+/// One `from -> to (weight)` line per stored edge, plus isolated nodes.
+fn graph_edges(graph: &Graph) -> Vec<String> {
+    let arrow = if graph.is_directed() { "->" } else { "--" };
+    let mut lines = Vec::new();
+    for node in graph.nodes() {
+        let edges = graph.neighbors(node).unwrap_or_default();
+        if edges.is_empty() {
+            lines.push(node.clone());
+        }
+        for (next, weight) in edges {
+            // Undirected edges are stored both ways; list each once.
+            if graph.is_directed() || node.as_str() <= next {
+                lines.push(format!("{} {} {} ({})", node, arrow, next, weight));
+            }
+        }
+    }
+    lines
+}
+
+fn graph_command(graph: &mut Graph, parts: &[&str]) {
+    match parts.first().copied() {
+        Some("new") => match parts.get(1).copied() {
+            Some("directed") | None => {
+                *graph = Graph::new(true);
+                println!("✓ New directed graph");
+            }
+            Some("undirected") => {
+                *graph = Graph::new(false);
+                println!("✓ New undirected graph");
+            }
+            Some(_) => println!("✗ Usage: new directed|undirected"),
+        },
+        Some("edge") => {
+            if let (Some(from), Some(to)) = (parts.get(1), parts.get(2)) {
+                let weight = match parts.get(3).map(|w| w.parse::<u64>()) {
+                    None => 1,
+                    Some(Ok(w)) => w,
+                    Some(Err(_)) => {
+                        println!("✗ Weight must be a non-negative integer");
+                        return;
+                    }
+                };
+                graph.add_edge(from, to, weight);
+                println!("✓ Added {} -> {} ({}). Nodes: {}, edges: {}", from, to, weight, graph.node_count(), graph.edge_count());
+            } else {
+                println!("✗ Usage: edge <from> <to> [weight]");
+            }
+        }
+        Some("node") => {
+            if let Some(name) = parts.get(1) {
+                graph.add_node(name);
+                println!("✓ Node {}. Nodes: {}", name, graph.node_count());
+            } else {
+                println!("✗ Usage: node <name>");
+            }
+        }
+        Some(cmd @ ("bfs" | "dfs")) => {
+            if let Some(start) = parts.get(1) {
+                let order = if cmd == "bfs" { graph.bfs(start) } else { graph.dfs(start) };
+                match order {
+                    Ok(order) => println!("✓ {} from {}: {}", cmd.to_uppercase(), start, order.join(" -> ")),
+                    Err(e) => println!("✗ {}", e),
+                }
+            } else {
+                println!("✗ Usage: {} <start>", cmd);
+            }
+        }
+        Some("path") => {
+            if let (Some(from), Some(to)) = (parts.get(1), parts.get(2)) {
+                match graph.shortest_path(from, to) {
+                    Ok(Some(path)) => println!("✓ {} (cost {})", path.nodes.join(" -> "), path.cost),
+                    Ok(None) => println!("✗ No path from {} to {}", from, to),
+                    Err(e) => println!("✗ {}", e),
+                }
+            } else {
+                println!("✗ Usage: path <from> <to>");
+            }
+        }
+        Some("topo") => match graph.topological_sort() {
+            Ok(order) => println!("✓ Topological order: {}", order.join(", ")),
+            Err(e) => println!("✗ {}", e),
+        },
+        Some("components") => {
+            let components = graph.components();
+            println!("✓ {} component(s)", components.len());
+            for (i, nodes) in components.iter().enumerate() {
+                println!("  {}. {}", i + 1, nodes.join(", "));
+            }
+        }
+        Some("load") => {
+            if let Some(path) = parts.get(1) {
+                let directed = parts.get(2) != Some(&"undirected");
+                match Graph::load_edge_csv(path, directed) {
+                    Ok(loaded) => {
+                        *graph = loaded;
+                        println!("✓ Loaded {}: {} nodes, {} edges", path, graph.node_count(), graph.edge_count());
+                    }
+                    Err(e) => println!("✗ Could not load {}: {}", path, e),
+                }
+            } else {
+                println!("✗ Usage: load <path> [undirected]");
+            }
+        }
+        Some("show") => {
+            if graph.node_count() == 0 {
+                println!("Graph is empty");
+            } else {
+                let kind = if graph.is_directed() { "Directed" } else { "Undirected" };
+                println!("{} graph: {} nodes, {} edges", kind, graph.node_count(), graph.edge_count());
+                for line in graph_edges(graph) {
+                    println!("  {}", line);
+                }
+            }
+        }
+        _ => println!("Unknown command. Try: new, edge, node, bfs, dfs, path, topo, components, load, show, exit"),
+    }
+}
+
+fn collection_advisor() {
+    println!("\n--- COLLECTION ADVISOR ---");
+    println!("Answer a few questions about your workload (y/n unless stated).");
+
+    let workload = Workload {
+        inserts: ask_number("Relative number of inserts (e.g. 10): "),
+        lookups: ask_number("Relative number of lookups (e.g. 90): "),
+        key_value: ask_yes_no("Do you store key/value pairs? "),
+        ordered: ask_yes_no("Do you need sorted iteration? "),
+        range_queries: ask_yes_no("Do you need range queries? "),
+        front_access: ask_yes_no("Do you push/pop at the front? "),
+        back_access: ask_yes_no("Do you push/pop at the back? "),
+        duplicates: ask_yes_no("Can the same value appear more than once? "),
+        priority: ask_yes_no("Do you always take the largest element first? "),
+    };
+
+    println!("\nRecommendations (best first):");
+    for (rank, rec) in advisor::recommend(&workload).iter().enumerate() {
+        println!("{}. {} (score {})", rank + 1, rec.collection, rec.score);
+        for reason in &rec.reasons {
+            println!("     {}", reason);
+        }
+    }
+}
+
+fn ask_yes_no(prompt: &str) -> bool {
+    loop {
+        print!("{}", prompt);
+        io::stdout().flush().unwrap();
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        match input.trim().to_lowercase().as_str() {
+            "y" | "yes" => return true,
+            "n" | "no" => return false,
+            _ => println!("✗ Please answer y or n"),
+        }
+    }
+}
+
+fn ask_number(prompt: &str) -> u32 {
+    loop {
+        print!("{}", prompt);
+        io::stdout().flush().unwrap();
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        match input.trim().parse::<u32>() {
+            Ok(num) => return num,
+            Err(_) => println!("✗ Invalid number"),
+        }
+    }
+}
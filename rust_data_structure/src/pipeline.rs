@@ -0,0 +1,387 @@
+//! Chained transformations for the editors' `pipe` command.
+//!
+//! ```text
+//! map x * 2 | filter x > 10 | sum
+//! filter key startswith "a" | map value + 1 | store
+//! ```
+//!
+//! Stages run left to right over a copy of the collection. The last stage
+//! may be a reducer (`sum`, `product`, `count`, `min`, `max`, `mean`,
+//! `fold <op> <init>`), which turns the elements into one scalar, or `store`,
+//! which asks the editor to replace its contents with the result. All
+//! arithmetic goes through `trsut`, so overflow and division by zero are
+//! reported as errors rather than panicking or wrapping; `mean` sums in
+//! `i64` instead, since its result always fits.
+
+use std::fmt;
+
+use trsut::ArithmeticError;
+
+use crate::predicate::{EvalError, Expression, Predicate};
+
+/// One transforming stage.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stage {
+    Map(Expression),
+    Filter(Predicate),
+    Take(usize),
+    Skip(usize),
+    Sort,
+    Reverse,
+}
+
+/// How `fold` combines the accumulator with each element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FoldOp {
+    Add,
+    Mul,
+    Div,
+    Min,
+    Max,
+}
+
+impl FoldOp {
+    pub fn from_name(name: &str) -> Option<FoldOp> {
+        match name {
+            "+" | "add" => Some(FoldOp::Add),
+            "*" | "mul" => Some(FoldOp::Mul),
+            "/" | "div" => Some(FoldOp::Div),
+            "min" => Some(FoldOp::Min),
+            "max" => Some(FoldOp::Max),
+            _ => None,
+        }
+    }
+
+    fn apply(self, acc: i32, x: i32) -> Result<i32, ArithmeticError> {
+        match self {
+            FoldOp::Add => trsut::checked_add(acc, x),
+            FoldOp::Mul => trsut::checked_mul(acc, x),
+            FoldOp::Div => trsut::checked_div(acc, x),
+            FoldOp::Min => Ok(acc.min(x)),
+            FoldOp::Max => Ok(acc.max(x)),
+        }
+    }
+}
+
+/// A stage that ends the pipeline with a single value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reducer {
+    Sum,
+    Product,
+    Count,
+    Min,
+    Max,
+    /// Sum divided by count, truncated toward zero. The sum is kept in
+    /// `i64`, so only the mean itself has to fit.
+    Mean,
+    Fold(FoldOp, i32),
+}
+
+/// What the pipeline ends with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sink {
+    /// Show the resulting elements without touching the collection.
+    Preview,
+    /// Replace the collection with the resulting elements.
+    Store,
+    Reduce(Reducer),
+}
+
+/// Why a pipeline could not be parsed or run. `stage` is 1-based.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PipelineError {
+    Empty,
+    Parse {
+        stage: usize,
+        text: String,
+        message: String,
+    },
+    Eval {
+        stage: usize,
+        text: String,
+        error: EvalError,
+        value: i32,
+    },
+    /// `count` or `mean` over more elements than fit in `i32`.
+    TooMany {
+        stage: usize,
+        text: String,
+        count: usize,
+    },
+}
+
+impl fmt::Display for PipelineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PipelineError::Empty => f.write_str("empty pipeline"),
+            PipelineError::Parse {
+                stage,
+                text,
+                message,
+            } => write!(f, "stage {} '{}': {}", stage, text, message),
+            PipelineError::Eval {
+                stage,
+                text,
+                error,
+                value,
+            } => write!(
+                f,
+                "stage {} '{}': {} at element {}",
+                stage, text, error, value
+            ),
+            PipelineError::TooMany { stage, text, count } => write!(
+                f,
+                "stage {} '{}': {} elements are too many to count",
+                stage, text, count
+            ),
+        }
+    }
+}
+
+/// Result of running a pipeline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Output {
+    Values(Vec<i32>),
+    Entries(Vec<(String, i32)>),
+    /// A reduced value; `None` for `min`, `max` and `mean` of nothing.
+    Scalar(Option<i32>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pipeline {
+    stages: Vec<(String, Stage)>,
+    sink: Sink,
+}
+
+impl Pipeline {
+    /// Parses `|`-separated stages.
+    pub fn parse(text: &str) -> Result<Pipeline, PipelineError> {
+        let pieces: Vec<&str> = text.split('|').map(str::trim).collect();
+        if pieces.iter().all(|p| p.is_empty()) {
+            return Err(PipelineError::Empty);
+        }
+
+        let mut stages = Vec::new();
+        let mut sink = Sink::Preview;
+        for (i, piece) in pieces.iter().enumerate() {
+            let fail = |message: String| PipelineError::Parse {
+                stage: i + 1,
+                text: piece.to_string(),
+                message,
+            };
+            if sink != Sink::Preview {
+                return Err(fail(String::from(
+                    "nothing may follow a reducer or 'store'",
+                )));
+            }
+            match parse_piece(piece).map_err(fail)? {
+                Piece::Stage(stage) => stages.push((piece.to_string(), stage)),
+                Piece::Sink(end) => sink = end,
+            }
+        }
+        Ok(Pipeline { stages, sink })
+    }
+
+    pub fn stages(&self) -> impl Iterator<Item = &Stage> {
+        self.stages.iter().map(|(_, stage)| stage)
+    }
+
+    pub fn sink(&self) -> Sink {
+        self.sink
+    }
+
+    /// True when the pipeline ends with `store`.
+    pub fn stores(&self) -> bool {
+        self.sink == Sink::Store
+    }
+
+    /// True when any stage refers to `key`.
+    pub fn uses_key(&self) -> bool {
+        self.stages().any(|stage| match stage {
+            Stage::Map(e) => e.uses_key(),
+            Stage::Filter(p) => p.uses_key(),
+            _ => false,
+        })
+    }
+
+    /// Runs the pipeline over plain elements.
+    pub fn run(&self, values: Vec<i32>) -> Result<Output, PipelineError> {
+        let rows = values.into_iter().map(|v| (None, v)).collect();
+        let rows = self.transform(rows)?;
+        Ok(match self.sink {
+            Sink::Reduce(reducer) => Output::Scalar(self.reduce(reducer, &rows)?),
+            _ => Output::Values(rows.into_iter().map(|(_, v)| v).collect()),
+        })
+    }
+
+    /// Runs the pipeline over map entries; `map` replaces values and keeps
+    /// keys, and `sort` orders by value.
+    pub fn run_entries(&self, entries: Vec<(String, i32)>) -> Result<Output, PipelineError> {
+        let rows = entries.into_iter().map(|(k, v)| (Some(k), v)).collect();
+        let rows = self.transform(rows)?;
+        Ok(match self.sink {
+            Sink::Reduce(reducer) => Output::Scalar(self.reduce(reducer, &rows)?),
+            _ => Output::Entries(
+                rows.into_iter()
+                    .map(|(k, v)| (k.unwrap_or_default(), v))
+                    .collect(),
+            ),
+        })
+    }
+
+    fn transform(&self, mut rows: Vec<Row>) -> Result<Vec<Row>, PipelineError> {
+        for (i, (text, stage)) in self.stages.iter().enumerate() {
+            let fail = |error: EvalError, value: i32| PipelineError::Eval {
+                stage: i + 1,
+                text: text.clone(),
+                error,
+                value,
+            };
+            rows = match stage {
+                Stage::Map(expr) => rows
+                    .into_iter()
+                    .map(|(k, v)| {
+                        let result = match &k {
+                            Some(key) => expr.eval_entry(key, v),
+                            None => expr.eval(v),
+                        };
+                        result.map(|v| (k, v)).map_err(|e| fail(e, v))
+                    })
+                    .collect::<Result<_, _>>()?,
+                Stage::Filter(predicate) => {
+                    let mut kept = Vec::new();
+                    for (k, v) in rows {
+                        let keep = match &k {
+                            Some(key) => predicate.matches_entry(key, v),
+                            None => predicate.matches(v),
+                        };
+                        if keep.map_err(|e| fail(e, v))? {
+                            kept.push((k, v));
+                        }
+                    }
+                    kept
+                }
+                Stage::Take(n) => rows.into_iter().take(*n).collect(),
+                Stage::Skip(n) => rows.into_iter().skip(*n).collect(),
+                Stage::Sort => {
+                    rows.sort_by_key(|(_, v)| *v);
+                    rows
+                }
+                Stage::Reverse => {
+                    rows.reverse();
+                    rows
+                }
+            };
+        }
+        Ok(rows)
+    }
+
+    fn reduce(&self, reducer: Reducer, rows: &[Row]) -> Result<Option<i32>, PipelineError> {
+        let stage = self.stages.len() + 1;
+        let fold = |op: FoldOp, init: i32| {
+            rows.iter().try_fold(init, |acc, (_, v)| {
+                op.apply(acc, *v).map_err(|e| PipelineError::Eval {
+                    stage,
+                    text: reducer.to_string(),
+                    error: e.into(),
+                    value: *v,
+                })
+            })
+        };
+        let count = || {
+            i32::try_from(rows.len()).map_err(|_| PipelineError::TooMany {
+                stage,
+                text: reducer.to_string(),
+                count: rows.len(),
+            })
+        };
+        let values = rows.iter().map(|(_, v)| *v);
+        Ok(match reducer {
+            Reducer::Sum => Some(fold(FoldOp::Add, 0)?),
+            Reducer::Product => Some(fold(FoldOp::Mul, 1)?),
+            Reducer::Count => Some(count()?),
+            Reducer::Min => values.min(),
+            Reducer::Max => values.max(),
+            Reducer::Mean if rows.is_empty() => None,
+            Reducer::Mean => {
+                // At most i32::MAX values of at most 2^31 each: fits in i64.
+                let count = i64::from(count()?);
+                let sum: i64 = values.map(i64::from).sum();
+                Some(i32::try_from(sum / count).expect("a mean lies between min and max"))
+            }
+            Reducer::Fold(op, init) => Some(fold(op, init)?),
+        })
+    }
+}
+
+impl fmt::Display for Reducer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reducer::Sum => f.write_str("sum"),
+            Reducer::Product => f.write_str("product"),
+            Reducer::Count => f.write_str("count"),
+            Reducer::Min => f.write_str("min"),
+            Reducer::Max => f.write_str("max"),
+            Reducer::Mean => f.write_str("mean"),
+            Reducer::Fold(op, init) => {
+                let op = match op {
+                    FoldOp::Add => "+",
+                    FoldOp::Mul => "*",
+                    FoldOp::Div => "/",
+                    FoldOp::Min => "min",
+                    FoldOp::Max => "max",
+                };
+                write!(f, "fold {} {}", op, init)
+            }
+        }
+    }
+}
+
+enum Piece {
+    Stage(Stage),
+    Sink(Sink),
+}
+
+fn parse_piece(piece: &str) -> Result<Piece, String> {
+    let (name, rest) = piece.split_once(char::is_whitespace).unwrap_or((piece, ""));
+    let rest = rest.trim();
+    let count = || {
+        rest.parse::<usize>()
+            .map_err(|_| format!("usage: {} <n>", name))
+    };
+    if !rest.is_empty() && !matches!(name, "map" | "filter" | "take" | "skip" | "fold") {
+        return Err(format!("'{}' takes no arguments", name));
+    }
+    let piece = match name {
+        "map" => Piece::Stage(Stage::Map(
+            Expression::parse(rest).map_err(|e| e.to_string())?,
+        )),
+        "filter" => Piece::Stage(Stage::Filter(
+            Predicate::parse(rest).map_err(|e| e.to_string())?,
+        )),
+        "take" => Piece::Stage(Stage::Take(count()?)),
+        "skip" => Piece::Stage(Stage::Skip(count()?)),
+        "sort" => Piece::Stage(Stage::Sort),
+        "reverse" => Piece::Stage(Stage::Reverse),
+        "store" => Piece::Sink(Sink::Store),
+        "sum" => Piece::Sink(Sink::Reduce(Reducer::Sum)),
+        "product" => Piece::Sink(Sink::Reduce(Reducer::Product)),
+        "count" => Piece::Sink(Sink::Reduce(Reducer::Count)),
+        "min" => Piece::Sink(Sink::Reduce(Reducer::Min)),
+        "max" => Piece::Sink(Sink::Reduce(Reducer::Max)),
+        "mean" => Piece::Sink(Sink::Reduce(Reducer::Mean)),
+        "fold" => {
+            let usage = || String::from("usage: fold <+|*|/|min|max> <init>");
+            let (op, init) = rest.split_once(char::is_whitespace).ok_or_else(usage)?;
+            let op = FoldOp::from_name(op).ok_or_else(usage)?;
+            let init = init.trim().parse::<i32>().map_err(|_| usage())?;
+            Piece::Sink(Sink::Reduce(Reducer::Fold(op, init)))
+        }
+        "" => return Err(String::from("empty stage")),
+        _ => return Err(format!("unknown stage '{}'", name)),
+    };
+    Ok(piece)
+}
+
+/// An element on its way through the stages, with its key in map editors.
+type Row = (Option<String>, i32);
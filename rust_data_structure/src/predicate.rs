@@ -0,0 +1,714 @@
+//! A small predicate language for `retain`, `find` and `count`, and the
+//! integer expressions used by pipeline `map` stages.
+//!
+//! ```text
+//! x > 10 and x % 2 == 0
+//! key startswith "user_"
+//! value between 5 and 9
+//! not (x == 0 or x == 1)
+//! ```
+//!
+//! `x` and `value` name the element (the value, in map editors) and `key`
+//! names the map key. Comparisons work on numbers and strings; `startswith`,
+//! `endswith` and `contains` on strings; `between a and b` is inclusive.
+//! Arithmetic (`+ - * / %`) is on `i32` and reports overflow and division by
+//! zero instead of panicking; `+`, `*` and `/` go through `trsut`. A
+//! predicate starting with an operator, such as `> 10`, compares `x`. Types
+//! are checked while parsing, so a predicate that parses can only fail at run
+//! time on arithmetic.
+
+use std::fmt;
+
+use trsut::ArithmeticError;
+
+/// A parse or type error, positioned at a 1-based character column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    pub column: usize,
+}
+
+impl ParseError {
+    /// Renders `source` with a caret under the offending column.
+    pub fn pointer(&self, source: &str) -> String {
+        format!("{}\n{}^", source, " ".repeat(self.column.saturating_sub(1)))
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "column {}: {}", self.column, self.message)
+    }
+}
+
+/// Why a parsed predicate could not be evaluated for one element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvalError {
+    Overflow,
+    DivideByZero,
+    /// The predicate uses `key` but the element has none.
+    MissingKey,
+}
+
+impl From<ArithmeticError> for EvalError {
+    fn from(e: ArithmeticError) -> Self {
+        match e {
+            ArithmeticError::Overflow => EvalError::Overflow,
+            ArithmeticError::DivideByZero => EvalError::DivideByZero,
+        }
+    }
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::Overflow => f.write_str("arithmetic overflow"),
+            EvalError::DivideByZero => f.write_str("division by zero"),
+            EvalError::MissingKey => f.write_str("'key' is only available for maps"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArithOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextOp {
+    StartsWith,
+    EndsWith,
+    Contains,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Var {
+    Value,
+    Key,
+}
+
+/// Parsed predicate syntax tree.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Int(i32),
+    Str(String),
+    Bool(bool),
+    Var(Var),
+    Neg(Box<Expr>),
+    Not(Box<Expr>),
+    Arith(ArithOp, Box<Expr>, Box<Expr>),
+    Compare(CompareOp, Box<Expr>, Box<Expr>),
+    Text(TextOp, Box<Expr>, Box<Expr>),
+    Between(Box<Expr>, Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Type {
+    Int,
+    Str,
+    Bool,
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Type::Int => "a number",
+            Type::Str => "a string",
+            Type::Bool => "a condition",
+        })
+    }
+}
+
+/// Runtime value of a sub-expression.
+#[derive(Debug, Clone, PartialEq)]
+enum Value<'a> {
+    Int(i32),
+    Str(&'a str),
+    Bool(bool),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Predicate {
+    source: String,
+    expr: Expr,
+}
+
+impl Predicate {
+    pub fn parse(text: &str) -> Result<Predicate, ParseError> {
+        let expr = parse_typed(text, Type::Bool, "a condition such as 'x > 10'")?;
+        Ok(Predicate {
+            source: text.trim().to_string(),
+            expr,
+        })
+    }
+
+    pub fn expr(&self) -> &Expr {
+        &self.expr
+    }
+
+    /// True when the predicate refers to `key`.
+    pub fn uses_key(&self) -> bool {
+        uses_key(&self.expr)
+    }
+
+    /// Evaluates the predicate for a plain element.
+    pub fn matches(&self, value: i32) -> Result<bool, EvalError> {
+        self.eval(None, value)
+    }
+
+    /// Evaluates the predicate for a map entry.
+    pub fn matches_entry(&self, key: &str, value: i32) -> Result<bool, EvalError> {
+        self.eval(Some(key), value)
+    }
+
+    fn eval(&self, key: Option<&str>, value: i32) -> Result<bool, EvalError> {
+        match eval(&self.expr, key, value)? {
+            Value::Bool(b) => Ok(b),
+            _ => unreachable!("type checked as a condition"),
+        }
+    }
+}
+
+impl fmt::Display for Predicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// An integer expression such as `x * 2 + 1`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
+    source: String,
+    expr: Expr,
+}
+
+impl Expression {
+    pub fn parse(text: &str) -> Result<Expression, ParseError> {
+        let expr = parse_typed(text, Type::Int, "a number such as 'x * 2'")?;
+        Ok(Expression {
+            source: text.trim().to_string(),
+            expr,
+        })
+    }
+
+    pub fn expr(&self) -> &Expr {
+        &self.expr
+    }
+
+    /// True when the expression refers to `key`.
+    pub fn uses_key(&self) -> bool {
+        uses_key(&self.expr)
+    }
+
+    /// Evaluates the expression for a plain element.
+    pub fn eval(&self, value: i32) -> Result<i32, EvalError> {
+        self.eval_with(None, value)
+    }
+
+    /// Evaluates the expression for a map entry.
+    pub fn eval_entry(&self, key: &str, value: i32) -> Result<i32, EvalError> {
+        self.eval_with(Some(key), value)
+    }
+
+    fn eval_with(&self, key: Option<&str>, value: i32) -> Result<i32, EvalError> {
+        match eval(&self.expr, key, value)? {
+            Value::Int(n) => Ok(n),
+            _ => unreachable!("type checked as a number"),
+        }
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+fn parse_typed(text: &str, want: Type, example: &str) -> Result<Expr, ParseError> {
+    let tokens = tokenize(text)?;
+    let implicit_x = want == Type::Bool
+        && matches!(tokens.first(), Some((Token::Op(op), _)) if compare_op(op).is_some());
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        end: text.chars().count() + 1,
+        implicit_x,
+        depth: 0,
+    };
+    let (expr, ty) = parser.or()?;
+    if let Some((token, column)) = parser.tokens.get(parser.pos) {
+        return Err(error(*column, format!("unexpected {}", token)));
+    }
+    if ty != want {
+        return Err(error(1, format!("expected {}, found {}", example, ty)));
+    }
+    Ok(expr)
+}
+
+fn uses_key(expr: &Expr) -> bool {
+    match expr {
+        Expr::Var(Var::Key) => true,
+        Expr::Int(_) | Expr::Str(_) | Expr::Bool(_) | Expr::Var(_) => false,
+        Expr::Neg(e) | Expr::Not(e) => uses_key(e),
+        Expr::Arith(_, a, b)
+        | Expr::Compare(_, a, b)
+        | Expr::Text(_, a, b)
+        | Expr::And(a, b)
+        | Expr::Or(a, b) => uses_key(a) || uses_key(b),
+        Expr::Between(a, b, c) => uses_key(a) || uses_key(b) || uses_key(c),
+    }
+}
+
+fn eval<'a>(expr: &'a Expr, key: Option<&'a str>, value: i32) -> Result<Value<'a>, EvalError> {
+    let int = |e: &'a Expr| -> Result<i32, EvalError> {
+        match eval(e, key, value)? {
+            Value::Int(n) => Ok(n),
+            _ => unreachable!("type checked as a number"),
+        }
+    };
+    let boolean = |e: &'a Expr| -> Result<bool, EvalError> {
+        match eval(e, key, value)? {
+            Value::Bool(b) => Ok(b),
+            _ => unreachable!("type checked as a condition"),
+        }
+    };
+
+    Ok(match expr {
+        Expr::Int(n) => Value::Int(*n),
+        Expr::Str(s) => Value::Str(s),
+        Expr::Bool(b) => Value::Bool(*b),
+        Expr::Var(Var::Value) => Value::Int(value),
+        Expr::Var(Var::Key) => Value::Str(key.ok_or(EvalError::MissingKey)?),
+        Expr::Neg(e) => Value::Int(int(e)?.checked_neg().ok_or(EvalError::Overflow)?),
+        Expr::Not(e) => Value::Bool(!boolean(e)?),
+        Expr::And(a, b) => Value::Bool(boolean(a)? && boolean(b)?),
+        Expr::Or(a, b) => Value::Bool(boolean(a)? || boolean(b)?),
+        Expr::Arith(op, a, b) => Value::Int(arith(*op, int(a)?, int(b)?)?),
+        Expr::Between(x, lo, hi) => {
+            let x = int(x)?;
+            Value::Bool(int(lo)? <= x && x <= int(hi)?)
+        }
+        Expr::Compare(op, a, b) => {
+            let ordering = match (eval(a, key, value)?, eval(b, key, value)?) {
+                (Value::Int(a), Value::Int(b)) => a.cmp(&b),
+                (Value::Str(a), Value::Str(b)) => a.cmp(b),
+                (Value::Bool(a), Value::Bool(b)) => a.cmp(&b),
+                _ => unreachable!("type checked as matching operands"),
+            };
+            Value::Bool(match op {
+                CompareOp::Lt => ordering.is_lt(),
+                CompareOp::Le => ordering.is_le(),
+                CompareOp::Gt => ordering.is_gt(),
+                CompareOp::Ge => ordering.is_ge(),
+                CompareOp::Eq => ordering.is_eq(),
+                CompareOp::Ne => ordering.is_ne(),
+            })
+        }
+        Expr::Text(op, a, b) => match (eval(a, key, value)?, eval(b, key, value)?) {
+            (Value::Str(a), Value::Str(b)) => Value::Bool(match op {
+                TextOp::StartsWith => a.starts_with(b),
+                TextOp::EndsWith => a.ends_with(b),
+                TextOp::Contains => a.contains(b),
+            }),
+            _ => unreachable!("type checked as strings"),
+        },
+    })
+}
+
+fn arith(op: ArithOp, a: i32, b: i32) -> Result<i32, EvalError> {
+    let result = match op {
+        ArithOp::Add => trsut::checked_add(a, b)?,
+        ArithOp::Mul => trsut::checked_mul(a, b)?,
+        ArithOp::Div => trsut::checked_div(a, b)?,
+        ArithOp::Sub => a.checked_sub(b).ok_or(EvalError::Overflow)?,
+        ArithOp::Rem if b == 0 => return Err(EvalError::DivideByZero),
+        ArithOp::Rem => a.checked_rem(b).ok_or(EvalError::Overflow)?,
+    };
+    Ok(result)
+}
+
+fn error(column: usize, message: String) -> ParseError {
+    ParseError { message, column }
+}
+
+/// A number literal as `i32`.
+fn narrow(n: i64, column: usize) -> Result<i32, ParseError> {
+    i32::try_from(n).map_err(|_| error(column, format!("number {} is out of range", n)))
+}
+
+/// Deepest nesting of parentheses, `not` and unary `-` the parser accepts,
+/// so hostile input cannot overflow the stack.
+const MAX_DEPTH: usize = 64;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// Kept wide so that `-2147483648` can be negated before narrowing.
+    Int(i64),
+    Str(String),
+    Word(String),
+    Op(String),
+    Open,
+    Close,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Int(n) => write!(f, "number {}", n),
+            Token::Str(s) => write!(f, "string \"{}\"", s),
+            Token::Word(w) => write!(f, "'{}'", w),
+            Token::Op(op) => write!(f, "'{}'", op),
+            Token::Open => f.write_str("'('"),
+            Token::Close => f.write_str("')'"),
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let column = i + 1;
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                i += 1;
+            }
+            let digits: String = chars[start..i].iter().collect();
+            let n = digits
+                .parse::<i64>()
+                .map_err(|_| error(column, format!("number {} is out of range", digits)))?;
+            tokens.push((Token::Int(n), column));
+        } else if c == '"' {
+            let start = i + 1;
+            i += 1;
+            while i < chars.len() && chars[i] != '"' {
+                i += 1;
+            }
+            if i == chars.len() {
+                return Err(error(column, String::from("unterminated string")));
+            }
+            tokens.push((Token::Str(chars[start..i].iter().collect()), column));
+            i += 1;
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push((Token::Word(chars[start..i].iter().collect()), column));
+        } else if c == '(' || c == ')' {
+            tokens.push((if c == '(' { Token::Open } else { Token::Close }, column));
+            i += 1;
+        } else {
+            let two: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+            let op = if ["<=", ">=", "==", "!="].contains(&two.as_str()) {
+                two
+            } else if "<>=+-*/%".contains(c) {
+                c.to_string()
+            } else {
+                return Err(error(column, format!("unexpected character '{}'", c)));
+            };
+            i += op.len();
+            tokens.push((Token::Op(op), column));
+        }
+    }
+    Ok(tokens)
+}
+
+fn compare_op(op: &str) -> Option<CompareOp> {
+    match op {
+        "<" => Some(CompareOp::Lt),
+        "<=" => Some(CompareOp::Le),
+        ">" => Some(CompareOp::Gt),
+        ">=" => Some(CompareOp::Ge),
+        "==" | "=" => Some(CompareOp::Eq),
+        "!=" => Some(CompareOp::Ne),
+        _ => None,
+    }
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    /// Column just past the input, used for "unexpected end" errors.
+    end: usize,
+    /// Supply `x` as the left operand of a leading comparison.
+    implicit_x: bool,
+    /// Current nesting, checked against [`MAX_DEPTH`].
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn column(&self) -> usize {
+        self.tokens.get(self.pos).map_or(self.end, |(_, c)| *c)
+    }
+
+    fn keyword(&mut self, word: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Word(w)) if w == word) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Runs `parse` one nesting level deeper.
+    fn nested<T>(
+        &mut self,
+        column: usize,
+        parse: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        if self.depth == MAX_DEPTH {
+            return Err(error(
+                column,
+                format!("nested more than {} levels deep", MAX_DEPTH),
+            ));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn expect_type(
+        &self,
+        column: usize,
+        ty: Type,
+        want: Type,
+        what: &str,
+    ) -> Result<(), ParseError> {
+        if ty == want {
+            Ok(())
+        } else {
+            Err(error(
+                column,
+                format!("{} needs {}, found {}", what, want, ty),
+            ))
+        }
+    }
+
+    fn or(&mut self) -> Result<(Expr, Type), ParseError> {
+        let column = self.column();
+        let (mut left, mut ty) = self.and()?;
+        while matches!(self.peek(), Some(Token::Word(w)) if w == "or") {
+            self.pos += 1;
+            self.expect_type(column, ty, Type::Bool, "'or'")?;
+            let right_column = self.column();
+            let (right, right_ty) = self.and()?;
+            self.expect_type(right_column, right_ty, Type::Bool, "'or'")?;
+            left = Expr::Or(Box::new(left), Box::new(right));
+            ty = Type::Bool;
+        }
+        Ok((left, ty))
+    }
+
+    fn and(&mut self) -> Result<(Expr, Type), ParseError> {
+        let column = self.column();
+        let (mut left, mut ty) = self.not()?;
+        while matches!(self.peek(), Some(Token::Word(w)) if w == "and") {
+            self.pos += 1;
+            self.expect_type(column, ty, Type::Bool, "'and'")?;
+            let right_column = self.column();
+            let (right, right_ty) = self.not()?;
+            self.expect_type(right_column, right_ty, Type::Bool, "'and'")?;
+            left = Expr::And(Box::new(left), Box::new(right));
+            ty = Type::Bool;
+        }
+        Ok((left, ty))
+    }
+
+    fn not(&mut self) -> Result<(Expr, Type), ParseError> {
+        if self.keyword("not") {
+            let column = self.column();
+            let (operand, ty) = self.nested(column, Self::not)?;
+            self.expect_type(column, ty, Type::Bool, "'not'")?;
+            return Ok((Expr::Not(Box::new(operand)), Type::Bool));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<(Expr, Type), ParseError> {
+        let column = self.column();
+        let (left, ty) = if std::mem::take(&mut self.implicit_x) {
+            (Expr::Var(Var::Value), Type::Int)
+        } else {
+            self.additive()?
+        };
+
+        let op_column = self.column();
+        match self.peek().cloned() {
+            Some(Token::Op(op)) if compare_op(&op).is_some() => {
+                self.pos += 1;
+                let right_column = self.column();
+                let (right, right_ty) = self.additive()?;
+                if right_ty != ty {
+                    return Err(error(
+                        right_column,
+                        format!("cannot compare {} with {}", ty, right_ty),
+                    ));
+                }
+                let op = compare_op(&op).expect("checked above");
+                if ty == Type::Bool && !matches!(op, CompareOp::Eq | CompareOp::Ne) {
+                    return Err(error(
+                        op_column,
+                        String::from("conditions can only be compared with == or !="),
+                    ));
+                }
+                Ok((
+                    Expr::Compare(op, Box::new(left), Box::new(right)),
+                    Type::Bool,
+                ))
+            }
+            Some(Token::Word(w)) if w == "between" => {
+                self.pos += 1;
+                self.expect_type(column, ty, Type::Int, "'between'")?;
+                let lo_column = self.column();
+                let (lo, lo_ty) = self.additive()?;
+                self.expect_type(lo_column, lo_ty, Type::Int, "'between'")?;
+                if !self.keyword("and") {
+                    return Err(error(
+                        self.column(),
+                        String::from("expected 'and' in 'between a and b'"),
+                    ));
+                }
+                let hi_column = self.column();
+                let (hi, hi_ty) = self.additive()?;
+                self.expect_type(hi_column, hi_ty, Type::Int, "'between'")?;
+                Ok((
+                    Expr::Between(Box::new(left), Box::new(lo), Box::new(hi)),
+                    Type::Bool,
+                ))
+            }
+            Some(Token::Word(w))
+                if ["startswith", "endswith", "contains"].contains(&w.as_str()) =>
+            {
+                self.pos += 1;
+                let op = match w.as_str() {
+                    "startswith" => TextOp::StartsWith,
+                    "endswith" => TextOp::EndsWith,
+                    _ => TextOp::Contains,
+                };
+                let what = format!("'{}'", w);
+                self.expect_type(column, ty, Type::Str, &what)?;
+                let right_column = self.column();
+                let (right, right_ty) = self.additive()?;
+                self.expect_type(right_column, right_ty, Type::Str, &what)?;
+                Ok((Expr::Text(op, Box::new(left), Box::new(right)), Type::Bool))
+            }
+            _ => Ok((left, ty)),
+        }
+    }
+
+    fn additive(&mut self) -> Result<(Expr, Type), ParseError> {
+        let column = self.column();
+        let (mut left, ty) = self.multiplicative()?;
+        while let Some(Token::Op(op)) = self.peek().cloned() {
+            let op = match op.as_str() {
+                "+" => ArithOp::Add,
+                "-" => ArithOp::Sub,
+                _ => break,
+            };
+            self.pos += 1;
+            self.expect_type(column, ty, Type::Int, "arithmetic")?;
+            let right_column = self.column();
+            let (right, right_ty) = self.multiplicative()?;
+            self.expect_type(right_column, right_ty, Type::Int, "arithmetic")?;
+            left = Expr::Arith(op, Box::new(left), Box::new(right));
+        }
+        Ok((left, ty))
+    }
+
+    fn multiplicative(&mut self) -> Result<(Expr, Type), ParseError> {
+        let column = self.column();
+        let (mut left, ty) = self.unary()?;
+        while let Some(Token::Op(op)) = self.peek().cloned() {
+            let op = match op.as_str() {
+                "*" => ArithOp::Mul,
+                "/" => ArithOp::Div,
+                "%" => ArithOp::Rem,
+                _ => break,
+            };
+            self.pos += 1;
+            self.expect_type(column, ty, Type::Int, "arithmetic")?;
+            let right_column = self.column();
+            let (right, right_ty) = self.unary()?;
+            self.expect_type(right_column, right_ty, Type::Int, "arithmetic")?;
+            left = Expr::Arith(op, Box::new(left), Box::new(right));
+        }
+        Ok((left, ty))
+    }
+
+    fn unary(&mut self) -> Result<(Expr, Type), ParseError> {
+        if matches!(self.peek(), Some(Token::Op(op)) if op == "-") {
+            let minus_column = self.column();
+            self.pos += 1;
+            if let Some(&Token::Int(n)) = self.peek() {
+                self.pos += 1;
+                return Ok((Expr::Int(narrow(-n, minus_column)?), Type::Int));
+            }
+            let column = self.column();
+            let (operand, ty) = self.nested(column, Self::unary)?;
+            self.expect_type(column, ty, Type::Int, "'-'")?;
+            return Ok((Expr::Neg(Box::new(operand)), Type::Int));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<(Expr, Type), ParseError> {
+        let column = self.column();
+        let Some(token) = self.peek().cloned() else {
+            return Err(error(column, String::from("unexpected end of predicate")));
+        };
+        self.pos += 1;
+        match token {
+            Token::Int(n) => Ok((Expr::Int(narrow(n, column)?), Type::Int)),
+            Token::Str(s) => Ok((Expr::Str(s), Type::Str)),
+            Token::Word(w) => match w.as_str() {
+                "x" | "value" => Ok((Expr::Var(Var::Value), Type::Int)),
+                "key" => Ok((Expr::Var(Var::Key), Type::Str)),
+                "true" => Ok((Expr::Bool(true), Type::Bool)),
+                "false" => Ok((Expr::Bool(false), Type::Bool)),
+                _ => Err(error(
+                    column,
+                    format!("unknown name '{}' (use x, value or key)", w),
+                )),
+            },
+            Token::Open => {
+                let inner = self.nested(column, Self::or)?;
+                if self.peek() != Some(&Token::Close) {
+                    return Err(error(self.column(), String::from("expected ')'")));
+                }
+                self.pos += 1;
+                Ok(inner)
+            }
+            other => {
+                self.pos -= 1;
+                Err(error(column, format!("expected a value, found {}", other)))
+            }
+        }
+    }
+}
@@ -0,0 +1,192 @@
+//! A fixed-capacity ring buffer on top of [`VecDeque`], for log tailing and
+//! sliding-window metrics.
+//!
+//! When the buffer is full a push either overwrites the oldest element or is
+//! rejected, depending on the [`Policy`]. Sum, mean, min and max over the
+//! current window are kept up to date on every push and eviction: the sum
+//! is a running total, and min and max use monotonic queues, so every
+//! aggregate is O(1) to read and O(1) amortised to maintain.
+
+use std::collections::VecDeque;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Policy {
+    /// Drop the oldest element to make room.
+    Overwrite,
+    /// Refuse the new element.
+    Reject,
+}
+
+impl Policy {
+    pub fn from_name(name: &str) -> Option<Policy> {
+        match name.to_lowercase().as_str() {
+            "overwrite" | "overwrite-oldest" => Some(Policy::Overwrite),
+            "reject" | "reject-newest" => Some(Policy::Reject),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Policy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Policy::Overwrite => "overwrite-oldest",
+            Policy::Reject => "reject-newest",
+        })
+    }
+}
+
+/// What happened to a pushed value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Push {
+    Stored,
+    /// Stored after evicting the contained oldest value.
+    Evicted(i32),
+    /// Not stored because the buffer is full.
+    Rejected,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RingBuffer {
+    items: VecDeque<i32>,
+    capacity: usize,
+    policy: Policy,
+    sum: i64,
+    /// Candidates for the minimum, non-decreasing from front to back.
+    mins: VecDeque<i32>,
+    /// Candidates for the maximum, non-increasing from front to back.
+    maxs: VecDeque<i32>,
+}
+
+impl RingBuffer {
+    /// Creates an empty buffer. Panics if `capacity` is zero.
+    pub fn new(capacity: usize, policy: Policy) -> Self {
+        assert!(capacity > 0, "ring buffer capacity must be positive");
+        RingBuffer {
+            items: VecDeque::with_capacity(capacity),
+            capacity,
+            policy,
+            sum: 0,
+            mins: VecDeque::new(),
+            maxs: VecDeque::new(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn policy(&self) -> Policy {
+        self.policy
+    }
+
+    pub fn set_policy(&mut self, policy: Policy) {
+        self.policy = policy;
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.items.len() == self.capacity
+    }
+
+    /// Elements from oldest to newest.
+    pub fn iter(&self) -> impl Iterator<Item = &i32> {
+        self.items.iter()
+    }
+
+    pub fn as_deque(&self) -> &VecDeque<i32> {
+        &self.items
+    }
+
+    /// Changes the capacity, evicting the oldest elements that no longer
+    /// fit, and returns them. Panics if `capacity` is zero.
+    pub fn set_capacity(&mut self, capacity: usize) -> Vec<i32> {
+        assert!(capacity > 0, "ring buffer capacity must be positive");
+        self.capacity = capacity;
+        let mut evicted = Vec::new();
+        while self.items.len() > capacity {
+            evicted.extend(self.pop_oldest());
+        }
+        evicted
+    }
+
+    pub fn push(&mut self, value: i32) -> Push {
+        let mut result = Push::Stored;
+        if self.is_full() {
+            match self.policy {
+                Policy::Reject => return Push::Rejected,
+                Policy::Overwrite => {
+                    if let Some(oldest) = self.pop_oldest() {
+                        result = Push::Evicted(oldest);
+                    }
+                }
+            }
+        }
+        self.items.push_back(value);
+        self.sum += i64::from(value);
+        while self.mins.back().is_some_and(|&m| m > value) {
+            self.mins.pop_back();
+        }
+        self.mins.push_back(value);
+        while self.maxs.back().is_some_and(|&m| m < value) {
+            self.maxs.pop_back();
+        }
+        self.maxs.push_back(value);
+        result
+    }
+
+    pub fn pop_oldest(&mut self) -> Option<i32> {
+        let value = self.items.pop_front()?;
+        self.sum -= i64::from(value);
+        if self.mins.front() == Some(&value) {
+            self.mins.pop_front();
+        }
+        if self.maxs.front() == Some(&value) {
+            self.maxs.pop_front();
+        }
+        Some(value)
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.mins.clear();
+        self.maxs.clear();
+        self.sum = 0;
+    }
+
+    /// Sum of the window; `i64` so a full window of `i32`s cannot overflow.
+    pub fn sum(&self) -> i64 {
+        self.sum
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.sum as f64 / self.items.len() as f64)
+        }
+    }
+
+    pub fn min(&self) -> Option<i32> {
+        self.mins.front().copied()
+    }
+
+    pub fn max(&self) -> Option<i32> {
+        self.maxs.front().copied()
+    }
+}
+
+impl Default for RingBuffer {
+    /// An eight-slot buffer that overwrites its oldest element.
+    fn default() -> Self {
+        RingBuffer::new(8, Policy::Overwrite)
+    }
+}
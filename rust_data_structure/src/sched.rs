@@ -0,0 +1,480 @@
+//! A task scheduler for dependency graphs of jobs.
+//!
+//! Tasks name the tasks they depend on; the dependencies must form a DAG,
+//! which is checked with [`Graph::topological_sort`] before anything runs.
+//! Ready tasks wait in a [`BinaryHeap`] ordered by priority (then by the
+//! order they were added) and are handed to idle workers one at a time, so
+//! a high-priority task never queues behind a low-priority one that has not
+//! started yet. A failing task is retried per its [`RetryPolicy`]; if it
+//! still fails, everything downstream of it is skipped.
+
+use std::any::Any;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::process::Command;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::graph::{Graph, GraphError};
+
+type Closure = Box<dyn Fn(u32) -> Result<(), String> + Send + Sync>;
+
+/// What a task does when it runs.
+pub enum Action {
+    /// Run through `sh -c`; a non-zero exit status is a failure.
+    Shell(String),
+    /// Called with the attempt number, starting at 1.
+    Closure(Closure),
+}
+
+impl Action {
+    fn run(&self, attempt: u32) -> Result<(), String> {
+        match self {
+            Action::Closure(f) => f(attempt),
+            Action::Shell(command) => {
+                let output = Command::new("sh")
+                    .arg("-c")
+                    .arg(command)
+                    .output()
+                    .map_err(|e| format!("could not start: {}", e))?;
+                if output.status.success() {
+                    return Ok(());
+                }
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                match stderr.lines().last() {
+                    Some(line) => Err(format!("{}: {}", output.status, line.trim())),
+                    None => Err(output.status.to_string()),
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Shell(command) => f.debug_tuple("Shell").field(command).finish(),
+            Action::Closure(_) => f.write_str("Closure(..)"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total tries, including the first; 0 counts as 1.
+    pub max_attempts: u32,
+    /// Pause before each retry.
+    pub backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    /// One attempt, no retries.
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            backoff: Duration::ZERO,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Task {
+    pub name: String,
+    /// Higher runs first among ready tasks.
+    pub priority: i32,
+    pub dependencies: Vec<String>,
+    pub retry: RetryPolicy,
+    pub action: Action,
+}
+
+impl Task {
+    pub fn new(name: impl Into<String>, action: Action) -> Self {
+        Task {
+            name: name.into(),
+            priority: 0,
+            dependencies: Vec::new(),
+            retry: RetryPolicy::default(),
+            action,
+        }
+    }
+
+    pub fn shell(name: impl Into<String>, command: impl Into<String>) -> Self {
+        Task::new(name, Action::Shell(command.into()))
+    }
+
+    pub fn closure(
+        name: impl Into<String>,
+        f: impl Fn(u32) -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        Task::new(name, Action::Closure(Box::new(f)))
+    }
+
+    pub fn priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Runs only after `dependency` has succeeded.
+    pub fn after(mut self, dependency: impl Into<String>) -> Self {
+        self.dependencies.push(dependency.into());
+        self
+    }
+
+    pub fn retry(mut self, max_attempts: u32, backoff: Duration) -> Self {
+        self.retry = RetryPolicy {
+            max_attempts: max_attempts.max(1),
+            backoff,
+        };
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScheduleError {
+    Duplicate(String),
+    UnknownDependency { task: String, dependency: String },
+    Cycle(Vec<String>),
+}
+
+impl fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScheduleError::Duplicate(name) => write!(f, "task '{}' is defined twice", name),
+            ScheduleError::UnknownDependency { task, dependency } => {
+                write!(
+                    f,
+                    "task '{}' depends on unknown task '{}'",
+                    task, dependency
+                )
+            }
+            ScheduleError::Cycle(tasks) => {
+                write!(f, "dependency cycle among {}", tasks.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScheduleError {}
+
+/// One attempt at running a task. Times are measured from the start of
+/// [`Scheduler::run`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry {
+    pub task: String,
+    pub worker: usize,
+    pub attempt: u32,
+    pub started: Duration,
+    pub finished: Duration,
+    pub result: Result<(), String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Succeeded {
+        attempts: u32,
+    },
+    Failed {
+        attempts: u32,
+        error: String,
+    },
+    /// Not run because the named dependency did not succeed.
+    Skipped {
+        dependency: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    /// Every attempt, in the order attempts finished.
+    pub log: Vec<LogEntry>,
+    /// Final status of each task, in the order tasks were added.
+    pub statuses: Vec<(String, Status)>,
+}
+
+impl Report {
+    pub fn status(&self, task: &str) -> Option<&Status> {
+        self.statuses
+            .iter()
+            .find(|(name, _)| name == task)
+            .map(|(_, status)| status)
+    }
+
+    pub fn succeeded(&self) -> bool {
+        self.statuses
+            .iter()
+            .all(|(_, s)| matches!(s, Status::Succeeded { .. }))
+    }
+
+    /// Task names in the order their final attempt started.
+    pub fn start_order(&self) -> Vec<&str> {
+        let mut last: Vec<&LogEntry> = Vec::new();
+        for entry in &self.log {
+            last.retain(|e| e.task != entry.task);
+            last.push(entry);
+        }
+        last.sort_by_key(|e| (e.started, e.worker));
+        last.iter().map(|e| e.task.as_str()).collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct Scheduler {
+    workers: usize,
+    tasks: Vec<Task>,
+}
+
+impl Scheduler {
+    pub fn new(workers: usize) -> Self {
+        Scheduler {
+            workers: workers.max(1),
+            tasks: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, task: Task) -> Result<(), ScheduleError> {
+        if self.tasks.iter().any(|t| t.name == task.name) {
+            return Err(ScheduleError::Duplicate(task.name));
+        }
+        self.tasks.push(task);
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Checks that every dependency exists and that there are no cycles,
+    /// returning one valid execution order.
+    pub fn validate(&self) -> Result<Vec<String>, ScheduleError> {
+        let mut graph = Graph::new(true);
+        for task in &self.tasks {
+            graph.add_node(&task.name);
+        }
+        for task in &self.tasks {
+            for dependency in &task.dependencies {
+                if !self.tasks.iter().any(|t| &t.name == dependency) {
+                    return Err(ScheduleError::UnknownDependency {
+                        task: task.name.clone(),
+                        dependency: dependency.clone(),
+                    });
+                }
+                graph.add_edge(dependency, &task.name, 1);
+            }
+        }
+        graph.topological_sort().map_err(|e| match e {
+            GraphError::Cycle(tasks) => ScheduleError::Cycle(tasks),
+            other => unreachable!("directed graph of known tasks: {}", other),
+        })
+    }
+
+    /// Runs every task on the worker pool and waits for them to finish.
+    pub fn run(&self) -> Result<Report, ScheduleError> {
+        self.validate()?;
+        let index: HashMap<&str, usize> = self
+            .tasks
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (t.name.as_str(), i))
+            .collect();
+        let mut dependents = vec![Vec::new(); self.tasks.len()];
+        let mut waiting_on: Vec<usize> = vec![0; self.tasks.len()];
+        for (i, task) in self.tasks.iter().enumerate() {
+            for dependency in &task.dependencies {
+                dependents[index[dependency.as_str()]].push(i);
+                waiting_on[i] += 1;
+            }
+        }
+
+        let start = Instant::now();
+        let mut statuses: Vec<Option<Status>> = vec![None; self.tasks.len()];
+        let mut log = Vec::new();
+        let mut ready: BinaryHeap<(i32, Reverse<usize>)> = (0..self.tasks.len())
+            .filter(|&i| waiting_on[i] == 0)
+            .map(|i| (self.tasks[i].priority, Reverse(i)))
+            .collect();
+
+        thread::scope(|scope| {
+            let (done_tx, done_rx) = mpsc::channel::<(usize, Vec<LogEntry>)>();
+            let mut job_senders = Vec::new();
+            for worker in 0..self.workers {
+                let (job_tx, job_rx) = mpsc::channel::<usize>();
+                job_senders.push(job_tx);
+                let done_tx = done_tx.clone();
+                let tasks = &self.tasks;
+                scope.spawn(move || {
+                    for job in job_rx {
+                        let entries = attempt(&tasks[job], worker, start);
+                        if done_tx.send((job, entries)).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+
+            // Lowest-numbered idle worker first, for a reproducible log.
+            let mut idle: BinaryHeap<Reverse<usize>> = (0..self.workers).map(Reverse).collect();
+            let mut running = 0;
+            loop {
+                while let (Some(_), Some(_)) = (ready.peek(), idle.peek()) {
+                    let (_, Reverse(job)) = ready.pop().expect("peeked");
+                    let Reverse(worker) = idle.pop().expect("peeked");
+                    job_senders[worker].send(job).expect("worker alive");
+                    running += 1;
+                }
+                if running == 0 {
+                    break;
+                }
+                let (job, entries) = done_rx.recv().expect("workers alive");
+                running -= 1;
+                let last = entries.last().expect("at least one attempt");
+                idle.push(Reverse(last.worker));
+                let attempts = last.attempt;
+                statuses[job] = Some(match &last.result {
+                    Ok(()) => Status::Succeeded { attempts },
+                    Err(error) => Status::Failed {
+                        attempts,
+                        error: error.clone(),
+                    },
+                });
+                let succeeded = last.result.is_ok();
+                log.extend(entries);
+
+                if succeeded {
+                    for &next in &dependents[job] {
+                        waiting_on[next] -= 1;
+                        // Already skipped if another dependency failed.
+                        if waiting_on[next] == 0 && statuses[next].is_none() {
+                            ready.push((self.tasks[next].priority, Reverse(next)));
+                        }
+                    }
+                } else {
+                    skip_downstream(job, &self.tasks, &dependents, &mut statuses);
+                }
+            }
+            // Dropping the senders ends each worker's loop.
+            drop(job_senders);
+        });
+
+        let statuses = self
+            .tasks
+            .iter()
+            .zip(statuses)
+            .map(|(task, status)| (task.name.clone(), status.expect("every task settled")))
+            .collect();
+        Ok(Report { log, statuses })
+    }
+}
+
+/// Runs `task` until it succeeds or its attempts are used up.
+fn attempt(task: &Task, worker: usize, start: Instant) -> Vec<LogEntry> {
+    let mut entries = Vec::new();
+    for attempt in 1..=task.retry.max_attempts.max(1) {
+        if attempt > 1 {
+            thread::sleep(task.retry.backoff);
+        }
+        let started = start.elapsed();
+        // A panicking closure must not take its worker down with it: the
+        // coordinator would wait forever for the result.
+        let result = panic::catch_unwind(AssertUnwindSafe(|| task.action.run(attempt)))
+            .unwrap_or_else(|payload| Err(format!("panicked: {}", panic_message(&*payload))));
+        let ok = result.is_ok();
+        entries.push(LogEntry {
+            task: task.name.clone(),
+            worker,
+            attempt,
+            started,
+            finished: start.elapsed(),
+            result,
+        });
+        if ok {
+            break;
+        }
+    }
+    entries
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown cause"
+    }
+}
+
+fn skip_downstream(
+    failed: usize,
+    tasks: &[Task],
+    dependents: &[Vec<usize>],
+    statuses: &mut [Option<Status>],
+) {
+    let mut stack = vec![failed];
+    while let Some(job) = stack.pop() {
+        for &next in &dependents[job] {
+            if statuses[next].is_none() {
+                statuses[next] = Some(Status::Skipped {
+                    dependency: tasks[job].name.clone(),
+                });
+                stack.push(next);
+            }
+        }
+    }
+}
+
+/// Parses a job file: one task per line as
+/// `name [priority=N] [after=a,b] [retries=N] [backoff=MS]: shell command`.
+/// Blank lines and lines starting with `#` are ignored.
+pub fn parse_jobs(text: &str) -> Result<Vec<Task>, String> {
+    let mut tasks = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fail = |message: &str| format!("line {}: {}", i + 1, message);
+        let (head, command) = line
+            .split_once(':')
+            .ok_or_else(|| fail("expected '<name> [options]: <command>'"))?;
+        let mut words = head.split_whitespace();
+        let name = words.next().ok_or_else(|| fail("missing task name"))?;
+        let mut task = Task::shell(name, command.trim());
+        let (mut retries, mut backoff) = (0, 0);
+        for option in words {
+            let (key, value) = option
+                .split_once('=')
+                .ok_or_else(|| fail(&format!("expected key=value, found '{}'", option)))?;
+            let number = || {
+                value
+                    .parse::<u64>()
+                    .map_err(|_| fail(&format!("'{}' needs a whole number", key)))
+            };
+            match key {
+                "priority" => {
+                    task.priority = value
+                        .parse()
+                        .map_err(|_| fail("priority must be an integer"))?
+                }
+                "after" => task
+                    .dependencies
+                    .extend(value.split(',').filter(|d| !d.is_empty()).map(String::from)),
+                "retries" => retries = number()?,
+                "backoff" => backoff = number()?,
+                _ => return Err(fail(&format!("unknown option '{}'", key))),
+            }
+        }
+        let attempts = retries
+            .checked_add(1)
+            .and_then(|n| u32::try_from(n).ok())
+            .ok_or_else(|| fail("too many retries"))?;
+        tasks.push(task.retry(attempts, Duration::from_millis(backoff)));
+    }
+    Ok(tasks)
+}
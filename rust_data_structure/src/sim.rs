@@ -0,0 +1,284 @@
+//! Discrete event simulation.
+//!
+//! [`Simulation`] keeps future events in a [`BinaryHeap`] ordered by
+//! `Reverse((time, sequence))`: the earliest event comes out first, and
+//! events scheduled for the same tick run in the order they were scheduled.
+//! Handlers get the simulation back so they can schedule follow-up events.
+//!
+//! [`QueueModel`] is a sample multi-server queue driven by a seeded [`Rng`],
+//! so a given configuration always produces the same report.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, VecDeque};
+
+/// A small, seedable pseudo-random generator (SplitMix64). Not for crypto.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform in `[low, high)`. Panics if the range is empty.
+    pub fn range(&mut self, low: u64, high: u64) -> u64 {
+        assert!(low < high, "empty range {}..{}", low, high);
+        low + self.next_u64() % (high - low)
+    }
+
+    /// Exponentially distributed with the given mean, rounded to whole ticks
+    /// and clamped to `0..=u64::MAX`.
+    pub fn exponential(&mut self, mean: f64) -> u64 {
+        let ticks = (-(1.0 - self.next_f64()).ln() * mean).round();
+        if ticks.is_nan() {
+            0
+        } else {
+            ticks.clamp(0.0, u64::MAX as f64) as u64
+        }
+    }
+}
+
+struct Scheduled<E> {
+    time: u64,
+    seq: u64,
+    event: E,
+}
+
+// Ordered by (time, seq) only; the payload does not need to be comparable.
+impl<E> PartialEq for Scheduled<E> {
+    fn eq(&self, other: &Self) -> bool {
+        (self.time, self.seq) == (other.time, other.seq)
+    }
+}
+
+impl<E> Eq for Scheduled<E> {}
+
+impl<E> PartialOrd for Scheduled<E> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<E> Ord for Scheduled<E> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.time, self.seq).cmp(&(other.time, other.seq))
+    }
+}
+
+/// A clock plus a queue of events waiting to happen.
+pub struct Simulation<E> {
+    now: u64,
+    next_seq: u64,
+    processed: u64,
+    queue: BinaryHeap<Reverse<Scheduled<E>>>,
+}
+
+impl<E> Default for Simulation<E> {
+    fn default() -> Self {
+        Simulation::new()
+    }
+}
+
+impl<E> Simulation<E> {
+    pub fn new() -> Self {
+        Simulation {
+            now: 0,
+            next_seq: 0,
+            processed: 0,
+            queue: BinaryHeap::new(),
+        }
+    }
+
+    /// The time of the event being (or last) handled.
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    /// Events scheduled but not yet handled.
+    pub fn pending(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Events handled so far.
+    pub fn processed(&self) -> u64 {
+        self.processed
+    }
+
+    /// Schedules `event` at `time`. Times in the past are clamped to now,
+    /// since the clock never runs backwards.
+    pub fn schedule_at(&mut self, time: u64, event: E) {
+        let scheduled = Scheduled {
+            time: time.max(self.now),
+            seq: self.next_seq,
+            event,
+        };
+        self.next_seq += 1;
+        self.queue.push(Reverse(scheduled));
+    }
+
+    pub fn schedule_in(&mut self, delay: u64, event: E) {
+        self.schedule_at(self.now.saturating_add(delay), event);
+    }
+
+    /// Time of the next event, if any.
+    pub fn peek_time(&self) -> Option<u64> {
+        self.queue.peek().map(|Reverse(s)| s.time)
+    }
+
+    /// Pops the next event and advances the clock to it.
+    pub fn step(&mut self) -> Option<(u64, E)> {
+        let Reverse(next) = self.queue.pop()?;
+        self.now = next.time;
+        self.processed += 1;
+        Some((next.time, next.event))
+    }
+
+    /// Handles events until none are left.
+    pub fn run(&mut self, handler: impl FnMut(&mut Self, E)) {
+        self.run_until(u64::MAX, handler);
+    }
+
+    /// Handles events up to and including time `end`; later events stay
+    /// queued.
+    pub fn run_until(&mut self, end: u64, mut handler: impl FnMut(&mut Self, E)) {
+        while self.peek_time().is_some_and(|t| t <= end) {
+            let (_, event) = self.step().expect("peeked an event");
+            handler(self, event);
+        }
+    }
+}
+
+/// A queue in front of identical servers: customers arrive with
+/// exponential gaps, wait first-come first-served for a free server and
+/// are served for an exponential time. Times are in ticks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueueModel {
+    pub servers: usize,
+    pub mean_interarrival: f64,
+    pub mean_service: f64,
+    pub customers: usize,
+    pub seed: u64,
+}
+
+impl Default for QueueModel {
+    fn default() -> Self {
+        QueueModel {
+            servers: 2,
+            mean_interarrival: 10.0,
+            mean_service: 15.0,
+            customers: 1000,
+            seed: 42,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueueReport {
+    pub served: usize,
+    pub end_time: u64,
+    pub mean_wait: f64,
+    pub median_wait: u64,
+    pub p95_wait: u64,
+    pub max_wait: u64,
+    pub max_queue: usize,
+    /// Fraction of server time spent busy.
+    pub utilization: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum QueueEvent {
+    Arrival(usize),
+    Departure,
+}
+
+impl QueueModel {
+    pub fn run(&self) -> QueueReport {
+        assert!(self.servers > 0, "a queue needs at least one server");
+        let mut rng = Rng::new(self.seed);
+        let mut sim = Simulation::new();
+        let mut waiting: VecDeque<u64> = VecDeque::new();
+        let mut idle = self.servers;
+        let mut waits = Vec::new();
+        let mut busy_time = 0u64;
+        let mut max_queue = 0;
+
+        if self.customers > 0 {
+            sim.schedule_at(0, QueueEvent::Arrival(0));
+        }
+        sim.run(|sim, event| {
+            // Arrival time of the customer who gets a server now, if any.
+            let starts = match event {
+                QueueEvent::Arrival(id) => {
+                    if id + 1 < self.customers {
+                        let gap = rng.exponential(self.mean_interarrival);
+                        sim.schedule_in(gap, QueueEvent::Arrival(id + 1));
+                    }
+                    if idle > 0 {
+                        idle -= 1;
+                        Some(sim.now())
+                    } else {
+                        waiting.push_back(sim.now());
+                        max_queue = max_queue.max(waiting.len());
+                        None
+                    }
+                }
+                QueueEvent::Departure => {
+                    let next = waiting.pop_front();
+                    if next.is_none() {
+                        idle += 1;
+                    }
+                    next
+                }
+            };
+            if let Some(arrived) = starts {
+                waits.push(sim.now() - arrived);
+                let service = rng.exponential(self.mean_service);
+                busy_time = busy_time.saturating_add(service);
+                sim.schedule_in(service, QueueEvent::Departure);
+            }
+        });
+
+        let end_time = sim.now();
+        let mean_wait = if waits.is_empty() {
+            0.0
+        } else {
+            let total = waits.iter().fold(0u64, |sum, &w| sum.saturating_add(w));
+            total as f64 / waits.len() as f64
+        };
+        let served = waits.len();
+        waits.sort_unstable();
+        let percentile = |p: usize| match waits.len() {
+            0 => 0,
+            n => waits[(n - 1) * p / 100],
+        };
+        QueueReport {
+            served,
+            end_time,
+            mean_wait,
+            median_wait: percentile(50),
+            p95_wait: percentile(95),
+            max_wait: waits.last().copied().unwrap_or(0),
+            max_queue,
+            utilization: if end_time == 0 {
+                0.0
+            } else {
+                busy_time as f64 / (end_time as f64 * self.servers as f64)
+            },
+        }
+    }
+}
@@ -0,0 +1,270 @@
+//! Streaming adapters for record processing.
+//!
+//! Every adapter is lazy and pulls from its source only as far as it needs
+//! to, so they can be chained over inputs that do not fit in memory:
+//!
+//! ```text
+//! records.sample_rate(100, 1_000, |r| r.time)
+//!        .dedup_by_key_bounded(10_000, |r| r.key.clone())
+//!        .batches(500)
+//! ```
+//!
+//! The adapters are generic over the item type; [`Record`] is the keyed,
+//! timestamped row the rest of the crate works with.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// A keyed value observed at `time` (any monotonic unit, e.g. milliseconds).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub key: String,
+    pub value: i32,
+    pub time: u64,
+}
+
+impl Record {
+    pub fn new(key: &str, value: i32, time: u64) -> Self {
+        Record {
+            key: key.to_string(),
+            value,
+            time,
+        }
+    }
+}
+
+/// Adapter methods available on every iterator.
+pub trait StreamExt: Iterator + Sized {
+    /// Groups items into consecutive batches of `size`; the last batch may
+    /// be shorter. Panics if `size` is zero.
+    fn batches(self, size: usize) -> Batches<Self> {
+        assert!(size > 0, "batch size must be positive");
+        Batches { inner: self, size }
+    }
+
+    /// Yields every run of `size` consecutive items, advancing by one. A
+    /// stream shorter than `size` yields nothing. Panics if `size` is zero.
+    fn sliding(self, size: usize) -> Sliding<Self>
+    where
+        Self::Item: Clone,
+    {
+        assert!(size > 0, "window size must be positive");
+        Sliding {
+            inner: self,
+            size,
+            window: VecDeque::with_capacity(size),
+        }
+    }
+
+    /// Drops items whose key was seen among the last `capacity` distinct
+    /// keys. Memory stays bounded by `capacity`, so a key that was evicted
+    /// can pass again.
+    fn dedup_by_key_bounded<K, F>(self, capacity: usize, key: F) -> DedupByKey<Self, K, F>
+    where
+        K: Hash + Eq + Clone,
+        F: FnMut(&Self::Item) -> K,
+    {
+        DedupByKey {
+            inner: self,
+            key,
+            capacity,
+            seen: HashSet::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Passes at most `limit` items per time window of `window` units, as
+    /// read by `time`; windows start at multiples of `window`. Panics if
+    /// `window` is zero.
+    fn sample_rate<F>(self, limit: usize, window: u64, time: F) -> SampleRate<Self, F>
+    where
+        F: FnMut(&Self::Item) -> u64,
+    {
+        assert!(window > 0, "sampling window must be positive");
+        SampleRate {
+            inner: self,
+            time,
+            limit,
+            window,
+            current: None,
+            passed: 0,
+        }
+    }
+}
+
+impl<I: Iterator> StreamExt for I {}
+
+pub struct Batches<I> {
+    inner: I,
+    size: usize,
+}
+
+impl<I: Iterator> Iterator for Batches<I> {
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let batch: Vec<I::Item> = self.inner.by_ref().take(self.size).collect();
+        if batch.is_empty() {
+            None
+        } else {
+            Some(batch)
+        }
+    }
+}
+
+pub struct Sliding<I: Iterator> {
+    inner: I,
+    size: usize,
+    window: VecDeque<I::Item>,
+}
+
+impl<I> Iterator for Sliding<I>
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.window.len() == self.size {
+            self.window.pop_front();
+        }
+        while self.window.len() < self.size {
+            self.window.push_back(self.inner.next()?);
+        }
+        Some(self.window.iter().cloned().collect())
+    }
+}
+
+pub struct DedupByKey<I, K, F> {
+    inner: I,
+    key: F,
+    capacity: usize,
+    seen: HashSet<K>,
+    /// Remembered keys, oldest first.
+    order: VecDeque<K>,
+}
+
+impl<I, K, F> Iterator for DedupByKey<I, K, F>
+where
+    I: Iterator,
+    K: Hash + Eq + Clone,
+    F: FnMut(&I::Item) -> K,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let item = self.inner.next()?;
+            let key = (self.key)(&item);
+            if self.seen.contains(&key) {
+                continue;
+            }
+            if self.capacity > 0 {
+                if self.order.len() == self.capacity {
+                    if let Some(oldest) = self.order.pop_front() {
+                        self.seen.remove(&oldest);
+                    }
+                }
+                self.seen.insert(key.clone());
+                self.order.push_back(key);
+            }
+            return Some(item);
+        }
+    }
+}
+
+pub struct SampleRate<I, F> {
+    inner: I,
+    time: F,
+    limit: usize,
+    window: u64,
+    current: Option<u64>,
+    passed: usize,
+}
+
+impl<I, F> Iterator for SampleRate<I, F>
+where
+    I: Iterator,
+    F: FnMut(&I::Item) -> u64,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let item = self.inner.next()?;
+            let bucket = (self.time)(&item) / self.window;
+            if self.current != Some(bucket) {
+                self.current = Some(bucket);
+                self.passed = 0;
+            }
+            if self.passed < self.limit {
+                self.passed += 1;
+                return Some(item);
+            }
+        }
+    }
+}
+
+/// Merges streams that are each sorted by `key` into one sorted stream,
+/// holding one pending item per stream in a [`BinaryHeap`]. Equal keys come
+/// out in stream order.
+pub fn merge_sorted<I, K, F>(streams: Vec<I>, key: F) -> MergeSorted<I, K, F>
+where
+    I: Iterator,
+    K: Ord,
+    F: FnMut(&I::Item) -> K,
+{
+    let mut merge = MergeSorted {
+        streams,
+        key,
+        pending: Vec::new(),
+        heap: BinaryHeap::new(),
+    };
+    for i in 0..merge.streams.len() {
+        let item = merge.streams[i].next();
+        merge.pending.push(None);
+        merge.refill(i, item);
+    }
+    merge
+}
+
+pub struct MergeSorted<I: Iterator, K, F> {
+    streams: Vec<I>,
+    key: F,
+    /// The next item of each stream, waiting in the heap.
+    pending: Vec<Option<I::Item>>,
+    heap: BinaryHeap<Reverse<(K, usize)>>,
+}
+
+impl<I, K, F> MergeSorted<I, K, F>
+where
+    I: Iterator,
+    K: Ord,
+    F: FnMut(&I::Item) -> K,
+{
+    fn refill(&mut self, stream: usize, item: Option<I::Item>) {
+        if let Some(item) = item {
+            self.heap.push(Reverse(((self.key)(&item), stream)));
+            self.pending[stream] = Some(item);
+        }
+    }
+}
+
+impl<I, K, F> Iterator for MergeSorted<I, K, F>
+where
+    I: Iterator,
+    K: Ord,
+    F: FnMut(&I::Item) -> K,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((_, stream)) = self.heap.pop()?;
+        let item = self.pending[stream].take();
+        let next = self.streams[stream].next();
+        self.refill(stream, next);
+        item
+    }
+}
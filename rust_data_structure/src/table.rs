@@ -0,0 +1,817 @@
+//! A columnar in-memory table.
+//!
+//! Each [`Column`] stores its values in one typed vector plus a validity
+//! bitmap, one bit per row, marking which rows are null; null slots hold a
+//! placeholder in the vector so row `i` is always at index `i`. Reading a
+//! whole column touches contiguous memory of a single type, which is what
+//! aggregations and filters want. [`Table::row`] reassembles a row of
+//! [`Value`]s when row-wise access is more convenient.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataType {
+    Int,
+    Float,
+    Str,
+    Bool,
+    /// Fixed-point with `scale` digits after the point, at most
+    /// [`Decimal::MAX_SCALE`].
+    Decimal {
+        scale: u8,
+    },
+}
+
+impl fmt::Display for DataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataType::Int => f.write_str("int"),
+            DataType::Float => f.write_str("float"),
+            DataType::Str => f.write_str("string"),
+            DataType::Bool => f.write_str("bool"),
+            DataType::Decimal { scale } => write!(f, "decimal({})", scale),
+        }
+    }
+}
+
+/// An exact decimal number: `units / 10^scale`. Equality and ordering are
+/// by value, so `1.0` equals `1.00`.
+#[derive(Debug, Clone, Copy)]
+pub struct Decimal {
+    units: i128,
+    scale: u8,
+}
+
+impl Decimal {
+    /// The most digits after the point; keeps `10^scale` inside `i128`.
+    pub const MAX_SCALE: u8 = 30;
+
+    /// Panics if `scale` is above [`Decimal::MAX_SCALE`].
+    pub fn new(units: i128, scale: u8) -> Self {
+        assert!(
+            scale <= Self::MAX_SCALE,
+            "decimal scale {} is above the maximum of {}",
+            scale,
+            Self::MAX_SCALE
+        );
+        Decimal { units, scale }
+    }
+
+    pub fn units(&self) -> i128 {
+        self.units
+    }
+
+    pub fn scale(&self) -> u8 {
+        self.scale
+    }
+
+    /// Parses `-12.5` and the like, rounding half away from zero to
+    /// `scale` digits.
+    pub fn parse(text: &str, scale: u8) -> Option<Decimal> {
+        if scale > Self::MAX_SCALE {
+            return None;
+        }
+        let text = text.trim();
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text.strip_prefix('+').unwrap_or(text)),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        if whole.is_empty() && fraction.is_empty()
+            || !whole
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+        let mut units: i128 = 0;
+        for c in whole.chars() {
+            units = units.checked_mul(10)?.checked_add(digit(c))?;
+        }
+        let mut kept = fraction.chars();
+        for _ in 0..scale {
+            units = units
+                .checked_mul(10)?
+                .checked_add(kept.next().map_or(0, digit))?;
+        }
+        if kept.next().is_some_and(|c| c >= '5') {
+            units = units.checked_add(1)?;
+        }
+        Some(Decimal::new(if negative { -units } else { units }, scale))
+    }
+
+    /// The same number with `scale` digits, rounding half away from zero
+    /// when digits are dropped. `None` on overflow or if `scale` is above
+    /// [`Decimal::MAX_SCALE`].
+    pub fn rescale(self, scale: u8) -> Option<Decimal> {
+        if scale > Self::MAX_SCALE {
+            return None;
+        }
+        match scale.cmp(&self.scale) {
+            Ordering::Equal => Some(self),
+            Ordering::Greater => {
+                let factor = 10i128.checked_pow(u32::from(scale - self.scale))?;
+                Some(Decimal::new(self.units.checked_mul(factor)?, scale))
+            }
+            Ordering::Less => {
+                let factor = 10i128.pow(u32::from(self.scale - scale));
+                let (quotient, remainder) = (self.units / factor, self.units % factor);
+                let rounded = if remainder.abs() * 2 >= factor {
+                    quotient + self.units.signum()
+                } else {
+                    quotient
+                };
+                Some(Decimal::new(rounded, scale))
+            }
+        }
+    }
+}
+
+fn digit(c: char) -> i128 {
+    i128::from(c as u8 - b'0')
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let scale = self.scale.max(other.scale);
+        match (self.rescale(scale), other.rescale(scale)) {
+            (Some(a), Some(b)) => a.units.cmp(&b.units),
+            // Only a number too large to widen can fail to rescale.
+            (None, _) => self.units.signum().cmp(&0),
+            (_, None) => 0.cmp(&other.units.signum()),
+        }
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.scale == 0 {
+            return write!(f, "{}", self.units);
+        }
+        let factor = 10u128.pow(u32::from(self.scale));
+        let magnitude = self.units.unsigned_abs();
+        let sign = if self.units < 0 { "-" } else { "" };
+        write!(
+            f,
+            "{}{}.{:0width$}",
+            sign,
+            magnitude / factor,
+            magnitude % factor,
+            width = self.scale as usize
+        )
+    }
+}
+
+/// One cell.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Bool(bool),
+    Decimal(Decimal),
+}
+
+impl Value {
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    /// The type of a non-null value.
+    pub fn data_type(&self) -> Option<DataType> {
+        match self {
+            Value::Null => None,
+            Value::Int(_) => Some(DataType::Int),
+            Value::Float(_) => Some(DataType::Float),
+            Value::Str(_) => Some(DataType::Str),
+            Value::Bool(_) => Some(DataType::Bool),
+            Value::Decimal(d) => Some(DataType::Decimal { scale: d.scale }),
+        }
+    }
+
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            Value::Int(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            Value::Float(x) => Some(*x),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_decimal(&self) -> Option<Decimal> {
+        match self {
+            Value::Decimal(d) => Some(*d),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => f.write_str("null"),
+            Value::Int(n) => write!(f, "{}", n),
+            Value::Float(x) => write!(f, "{}", x),
+            Value::Str(s) => f.write_str(s),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Decimal(d) => write!(f, "{}", d),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TableError {
+    UnknownColumn(String),
+    DuplicateColumn(String),
+    TypeMismatch {
+        column: String,
+        expected: DataType,
+        found: DataType,
+    },
+    RowLength {
+        expected: usize,
+        found: usize,
+    },
+    /// A decimal column with more than [`Decimal::MAX_SCALE`] digits.
+    InvalidScale {
+        column: String,
+        scale: u8,
+    },
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableError::UnknownColumn(name) => write!(f, "no column named '{}'", name),
+            TableError::DuplicateColumn(name) => write!(f, "column '{}' appears twice", name),
+            TableError::TypeMismatch {
+                column,
+                expected,
+                found,
+            } => write!(f, "column '{}' holds {}, not {}", column, expected, found),
+            TableError::RowLength { expected, found } => {
+                write!(
+                    f,
+                    "expected {} values in the row, found {}",
+                    expected, found
+                )
+            }
+            TableError::InvalidScale { column, scale } => write!(
+                f,
+                "column '{}' has scale {}, above the maximum of {}",
+                column,
+                scale,
+                Decimal::MAX_SCALE
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TableError {}
+
+/// One bit per row; a set bit means the row holds a value.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Bitmap {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl Bitmap {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push(&mut self, bit: bool) {
+        if self.len.is_multiple_of(64) {
+            self.words.push(0);
+        }
+        if bit {
+            self.words[self.len / 64] |= 1 << (self.len % 64);
+        }
+        self.len += 1;
+    }
+
+    pub fn get(&self, i: usize) -> bool {
+        i < self.len && self.words[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    /// Number of unset bits.
+    pub fn count_zeros(&self) -> usize {
+        self.len
+            - self
+                .words
+                .iter()
+                .map(|w| w.count_ones() as usize)
+                .sum::<usize>()
+    }
+}
+
+/// The typed storage behind a column.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnData {
+    Int(Vec<i64>),
+    Float(Vec<f64>),
+    Str(Vec<String>),
+    Bool(Vec<bool>),
+    /// Units at the column's scale.
+    Decimal(Vec<i128>, u8),
+}
+
+impl ColumnData {
+    fn empty(data_type: DataType) -> Self {
+        match data_type {
+            DataType::Int => ColumnData::Int(Vec::new()),
+            DataType::Float => ColumnData::Float(Vec::new()),
+            DataType::Str => ColumnData::Str(Vec::new()),
+            DataType::Bool => ColumnData::Bool(Vec::new()),
+            DataType::Decimal { scale } => ColumnData::Decimal(Vec::new(), scale),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    name: String,
+    data: ColumnData,
+    validity: Bitmap,
+}
+
+impl Column {
+    /// Panics if a decimal `data_type` has a scale above
+    /// [`Decimal::MAX_SCALE`]; [`Table::new`] reports that as an error.
+    pub fn new(name: impl Into<String>, data_type: DataType) -> Self {
+        if let DataType::Decimal { scale } = data_type {
+            assert!(
+                scale <= Decimal::MAX_SCALE,
+                "decimal scale {} is above the maximum of {}",
+                scale,
+                Decimal::MAX_SCALE
+            );
+        }
+        Column {
+            name: name.into(),
+            data: ColumnData::empty(data_type),
+            validity: Bitmap::default(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn data_type(&self) -> DataType {
+        match &self.data {
+            ColumnData::Int(_) => DataType::Int,
+            ColumnData::Float(_) => DataType::Float,
+            ColumnData::Str(_) => DataType::Str,
+            ColumnData::Bool(_) => DataType::Bool,
+            ColumnData::Decimal(_, scale) => DataType::Decimal { scale: *scale },
+        }
+    }
+
+    /// The raw values; null rows hold a placeholder.
+    pub fn data(&self) -> &ColumnData {
+        &self.data
+    }
+
+    pub fn validity(&self) -> &Bitmap {
+        &self.validity
+    }
+
+    pub fn len(&self) -> usize {
+        self.validity.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.validity.is_empty()
+    }
+
+    pub fn null_count(&self) -> usize {
+        self.validity.count_zeros()
+    }
+
+    pub fn is_null(&self, row: usize) -> bool {
+        !self.validity.get(row)
+    }
+
+    /// The value at `row`, or `None` past the end.
+    pub fn get(&self, row: usize) -> Option<Value> {
+        if row >= self.len() {
+            return None;
+        }
+        if self.is_null(row) {
+            return Some(Value::Null);
+        }
+        Some(match &self.data {
+            ColumnData::Int(v) => Value::Int(v[row]),
+            ColumnData::Float(v) => Value::Float(v[row]),
+            ColumnData::Str(v) => Value::Str(v[row].clone()),
+            ColumnData::Bool(v) => Value::Bool(v[row]),
+            ColumnData::Decimal(v, scale) => Value::Decimal(Decimal::new(v[row], *scale)),
+        })
+    }
+
+    /// Orders the non-null values at rows `a` and `b`, straight from the
+    /// typed storage.
+    fn compare_rows(&self, a: usize, b: usize) -> Ordering {
+        match &self.data {
+            ColumnData::Int(v) => v[a].cmp(&v[b]),
+            ColumnData::Float(v) => v[a].total_cmp(&v[b]),
+            ColumnData::Str(v) => v[a].cmp(&v[b]),
+            ColumnData::Bool(v) => v[a].cmp(&v[b]),
+            ColumnData::Decimal(v, _) => v[a].cmp(&v[b]),
+        }
+    }
+
+    /// A column of the rows at `indices`, copied vector to vector.
+    fn take(&self, indices: &[usize]) -> Column {
+        fn pick<T: Clone>(values: &[T], indices: &[usize]) -> Vec<T> {
+            indices.iter().map(|&i| values[i].clone()).collect()
+        }
+        let data = match &self.data {
+            ColumnData::Int(v) => ColumnData::Int(pick(v, indices)),
+            ColumnData::Float(v) => ColumnData::Float(pick(v, indices)),
+            ColumnData::Str(v) => ColumnData::Str(pick(v, indices)),
+            ColumnData::Bool(v) => ColumnData::Bool(pick(v, indices)),
+            ColumnData::Decimal(v, scale) => ColumnData::Decimal(pick(v, indices), *scale),
+        };
+        let mut validity = Bitmap::default();
+        for &i in indices {
+            validity.push(self.validity.get(i));
+        }
+        Column {
+            name: self.name.clone(),
+            data,
+            validity,
+        }
+    }
+
+    /// Whether `value` can be pushed: it is null or of the column's type.
+    /// Ints are accepted into float columns and decimals are rescaled to
+    /// the column's scale.
+    pub fn check(&self, value: &Value) -> Result<(), TableError> {
+        let fits = match (&self.data, value) {
+            (_, Value::Null) => true,
+            (ColumnData::Decimal(_, scale), Value::Decimal(d)) => d.rescale(*scale).is_some(),
+            (ColumnData::Float(_), Value::Int(_)) => true,
+            (ColumnData::Int(_), Value::Int(_))
+            | (ColumnData::Float(_), Value::Float(_))
+            | (ColumnData::Str(_), Value::Str(_))
+            | (ColumnData::Bool(_), Value::Bool(_)) => true,
+            _ => false,
+        };
+        if fits {
+            Ok(())
+        } else {
+            Err(TableError::TypeMismatch {
+                column: self.name.clone(),
+                expected: self.data_type(),
+                found: value.data_type().expect("not null"),
+            })
+        }
+    }
+
+    /// Appends a value; see [`Column::check`] for what is accepted.
+    pub fn push(&mut self, value: Value) -> Result<(), TableError> {
+        self.check(&value)?;
+        self.validity.push(!value.is_null());
+        match (&mut self.data, value) {
+            (ColumnData::Int(v), Value::Null) => v.push(0),
+            (ColumnData::Float(v), Value::Null) => v.push(0.0),
+            (ColumnData::Str(v), Value::Null) => v.push(String::new()),
+            (ColumnData::Bool(v), Value::Null) => v.push(false),
+            (ColumnData::Decimal(v, _), Value::Null) => v.push(0),
+            (ColumnData::Int(v), Value::Int(n)) => v.push(n),
+            (ColumnData::Float(v), Value::Float(x)) => v.push(x),
+            (ColumnData::Float(v), Value::Int(n)) => v.push(n as f64),
+            (ColumnData::Str(v), Value::Str(s)) => v.push(s),
+            (ColumnData::Bool(v), Value::Bool(b)) => v.push(b),
+            (ColumnData::Decimal(v, scale), Value::Decimal(d)) => {
+                v.push(d.rescale(*scale).expect("checked").units)
+            }
+            _ => unreachable!("checked above"),
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: String,
+    pub data_type: DataType,
+    /// Whether any row is null.
+    pub has_nulls: bool,
+}
+
+/// Column names and types, in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schema {
+    pub fields: Vec<Field>,
+}
+
+impl fmt::Display for Schema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, field) in self.fields.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}: {}", field.name, field.data_type)?;
+            if field.has_nulls {
+                f.write_str("?")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One sort key for [`Table::sort_by`]. Nulls sort last either way.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortKey {
+    pub column: String,
+    pub descending: bool,
+}
+
+impl SortKey {
+    pub fn asc(column: impl Into<String>) -> Self {
+        SortKey {
+            column: column.into(),
+            descending: false,
+        }
+    }
+
+    pub fn desc(column: impl Into<String>) -> Self {
+        SortKey {
+            column: column.into(),
+            descending: true,
+        }
+    }
+}
+
+/// A borrowed view of one row, for predicates.
+#[derive(Debug, Clone, Copy)]
+pub struct Row<'t> {
+    table: &'t Table,
+    index: usize,
+}
+
+impl Row<'_> {
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The value in column `name`; `Null` if there is no such column.
+    pub fn get(&self, name: &str) -> Value {
+        self.table
+            .column(name)
+            .and_then(|c| c.get(self.index))
+            .unwrap_or(Value::Null)
+    }
+
+    pub fn values(&self) -> Vec<Value> {
+        self.table.row(self.index).expect("row in range")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Table {
+    columns: Vec<Column>,
+    rows: usize,
+}
+
+impl Table {
+    /// An empty table with the given columns.
+    pub fn new(columns: &[(&str, DataType)]) -> Result<Table, TableError> {
+        let mut table = Table::default();
+        for &(name, data_type) in columns {
+            if let DataType::Decimal { scale } = data_type {
+                if scale > Decimal::MAX_SCALE {
+                    return Err(TableError::InvalidScale {
+                        column: name.to_string(),
+                        scale,
+                    });
+                }
+            }
+            table.add_column(Column::new(name, data_type))?;
+        }
+        Ok(table)
+    }
+
+    /// Adds a column, which must have one value per existing row.
+    pub fn add_column(&mut self, column: Column) -> Result<(), TableError> {
+        if self.column(column.name()).is_some() {
+            return Err(TableError::DuplicateColumn(column.name().to_string()));
+        }
+        if !self.columns.is_empty() && column.len() != self.rows {
+            return Err(TableError::RowLength {
+                expected: self.rows,
+                found: column.len(),
+            });
+        }
+        self.rows = column.len();
+        self.columns.push(column);
+        Ok(())
+    }
+
+    /// Appends a row; on error the table is left unchanged.
+    pub fn push_row(&mut self, values: Vec<Value>) -> Result<(), TableError> {
+        if values.len() != self.columns.len() {
+            return Err(TableError::RowLength {
+                expected: self.columns.len(),
+                found: values.len(),
+            });
+        }
+        for (column, value) in self.columns.iter().zip(&values) {
+            column.check(value)?;
+        }
+        for (column, value) in self.columns.iter_mut().zip(values) {
+            column.push(value).expect("checked");
+        }
+        self.rows += 1;
+        Ok(())
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn num_columns(&self) -> usize {
+        self.columns.len()
+    }
+
+    pub fn schema(&self) -> Schema {
+        Schema {
+            fields: self
+                .columns
+                .iter()
+                .map(|c| Field {
+                    name: c.name().to_string(),
+                    data_type: c.data_type(),
+                    has_nulls: c.null_count() > 0,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    pub fn column(&self, name: &str) -> Option<&Column> {
+        self.columns.iter().find(|c| c.name() == name)
+    }
+
+    /// The values of row `index`, in column order.
+    pub fn row(&self, index: usize) -> Option<Vec<Value>> {
+        if index >= self.rows {
+            return None;
+        }
+        Some(
+            self.columns
+                .iter()
+                .map(|c| c.get(index).expect("in range"))
+                .collect(),
+        )
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = Row<'_>> {
+        (0..self.rows).map(move |index| Row { table: self, index })
+    }
+
+    /// A new table with just the named columns, in the order given.
+    pub fn project(&self, names: &[&str]) -> Result<Table, TableError> {
+        let mut table = Table::default();
+        for name in names {
+            let column = self
+                .column(name)
+                .ok_or_else(|| TableError::UnknownColumn(name.to_string()))?;
+            table.add_column(column.clone())?;
+        }
+        Ok(table)
+    }
+
+    /// A new table with the rows for which `keep` returns true.
+    pub fn filter(&self, keep: impl Fn(&Row) -> bool) -> Table {
+        let indices: Vec<usize> = self.rows().filter(|r| keep(r)).map(|r| r.index).collect();
+        self.take(&indices)
+    }
+
+    /// A new table sorted by `keys`, the first key most significant. The
+    /// sort is stable, so rows equal on every key keep their order.
+    pub fn sort_by(&self, keys: &[SortKey]) -> Result<Table, TableError> {
+        let columns = keys
+            .iter()
+            .map(|k| {
+                self.column(&k.column)
+                    .map(|c| (c, k.descending))
+                    .ok_or_else(|| TableError::UnknownColumn(k.column.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut indices: Vec<usize> = (0..self.rows).collect();
+        indices.sort_by(|&a, &b| {
+            for &(column, descending) in &columns {
+                let order = match (column.is_null(a), column.is_null(b)) {
+                    (true, true) => Ordering::Equal,
+                    (true, false) => Ordering::Greater,
+                    (false, true) => Ordering::Less,
+                    (false, false) if descending => column.compare_rows(b, a),
+                    (false, false) => column.compare_rows(a, b),
+                };
+                if order != Ordering::Equal {
+                    return order;
+                }
+            }
+            Ordering::Equal
+        });
+        Ok(self.take(&indices))
+    }
+
+    /// A new table with the given rows, in the given order.
+    pub fn take(&self, indices: &[usize]) -> Table {
+        Table {
+            columns: self.columns.iter().map(|c| c.take(indices)).collect(),
+            rows: indices.len(),
+        }
+    }
+}
+
+impl fmt::Display for Table {
+    /// An aligned text grid with a header row.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cells: Vec<Vec<String>> = (0..self.rows)
+            .map(|i| {
+                self.columns
+                    .iter()
+                    .map(|c| c.get(i).expect("in range").to_string())
+                    .collect()
+            })
+            .collect();
+        let widths: Vec<usize> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(j, c)| {
+                cells
+                    .iter()
+                    .map(|row| row[j].chars().count())
+                    .chain([c.name().chars().count()])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let line = |f: &mut fmt::Formatter<'_>, row: Vec<&str>| -> fmt::Result {
+            let padded: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, &w)| format!("{:<w$}", cell, w = w))
+                .collect();
+            writeln!(f, "{}", padded.join(" | ").trim_end())
+        };
+        line(f, self.columns.iter().map(|c| c.name()).collect())?;
+        let rule: Vec<String> = widths.iter().map(|&w| "-".repeat(w)).collect();
+        writeln!(f, "{}", rule.join("-+-"))?;
+        for row in &cells {
+            line(f, row.iter().map(String::as_str).collect())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,207 @@
+//! Operation traces recorded by the editors and replayed step by step.
+//!
+//! A trace file is plain text: a `# trace <editor>` header followed by one
+//! tab-separated line per event (`op`, `args`, `size_before`, `size_after`,
+//! `elapsed_us`).
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+const HEADER: &str = "# trace ";
+
+/// One editor command as it was executed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub op: String,
+    pub args: Vec<String>,
+    pub size_before: usize,
+    pub size_after: usize,
+    pub elapsed: Duration,
+}
+
+impl Event {
+    /// The command line that produced this event, split like editor input.
+    pub fn command(&self) -> Vec<&str> {
+        let mut parts = vec![self.op.as_str()];
+        parts.extend(self.args.iter().map(String::as_str));
+        parts
+    }
+}
+
+/// Events recorded in one editor, with recording switched on and off.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trace {
+    editor: String,
+    recording: bool,
+    events: Vec<Event>,
+}
+
+impl Trace {
+    /// Creates an empty trace for `editor`, not yet recording.
+    pub fn new(editor: &str) -> Self {
+        Trace {
+            editor: editor.to_string(),
+            recording: false,
+            events: Vec::new(),
+        }
+    }
+
+    pub fn editor(&self) -> &str {
+        &self.editor
+    }
+
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    pub fn start(&mut self) {
+        self.recording = true;
+    }
+
+    pub fn stop(&mut self) {
+        self.recording = false;
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    /// Appends an event for `parts` if recording is on.
+    pub fn record(
+        &mut self,
+        parts: &[&str],
+        size_before: usize,
+        size_after: usize,
+        elapsed: Duration,
+    ) {
+        if !self.recording {
+            return;
+        }
+        if let Some((op, args)) = parts.split_first() {
+            self.events.push(Event {
+                op: op.to_string(),
+                args: args.iter().map(|a| a.to_string()).collect(),
+                size_before,
+                size_after,
+                elapsed,
+            });
+        }
+    }
+
+    /// Serializes the trace in the text format described in the module docs.
+    pub fn to_text(&self) -> String {
+        let mut out = format!("{}{}\n", HEADER, self.editor);
+        for e in &self.events {
+            out.push_str(&format!(
+                "{}\t{}\t{}\t{}\t{}\n",
+                e.op,
+                e.args.join(" "),
+                e.size_before,
+                e.size_after,
+                e.elapsed.as_micros()
+            ));
+        }
+        out
+    }
+
+    /// Parses a trace produced by [`Trace::to_text`].
+    pub fn from_text(text: &str) -> io::Result<Trace> {
+        let mut lines = text.lines();
+        let editor = lines
+            .next()
+            .and_then(|l| l.strip_prefix(HEADER))
+            .ok_or_else(|| invalid(1, "missing '# trace <editor>' header"))?;
+        let mut trace = Trace::new(editor.trim());
+
+        for (i, line) in lines.enumerate() {
+            let line_no = i + 2;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() != 5 || fields[0].is_empty() {
+                return Err(invalid(line_no, "expected 5 tab-separated fields"));
+            }
+            let number = |field: &str| {
+                field
+                    .parse::<u64>()
+                    .map_err(|_| invalid(line_no, "size and elapsed fields must be numbers"))
+            };
+            trace.events.push(Event {
+                op: fields[0].to_string(),
+                args: fields[1].split_whitespace().map(String::from).collect(),
+                size_before: number(fields[2])? as usize,
+                size_after: number(fields[3])? as usize,
+                elapsed: Duration::from_micros(number(fields[4])?),
+            });
+        }
+        Ok(trace)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_text())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Trace> {
+        Trace::from_text(&fs::read_to_string(path)?)
+    }
+}
+
+fn invalid(line: usize, message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("line {}: {}", line, message),
+    )
+}
+
+/// Renders values as the item list used by [`diff`].
+pub fn items<T: Display>(values: impl IntoIterator<Item = T>) -> Vec<String> {
+    values.into_iter().map(|v| v.to_string()).collect()
+}
+
+/// Renders key/value pairs as `key=value` items for [`diff`].
+pub fn entries<K: Display, V: Display>(pairs: impl IntoIterator<Item = (K, V)>) -> Vec<String> {
+    pairs
+        .into_iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect()
+}
+
+/// Lists items that disappeared (`- x`) or appeared (`+ x`) between two
+/// states, counting duplicates, so the result does not depend on order.
+pub fn diff(before: &[String], after: &[String]) -> Vec<String> {
+    let mut counts: HashMap<&str, i64> = HashMap::new();
+    for item in before {
+        *counts.entry(item).or_insert(0) -= 1;
+    }
+    for item in after {
+        *counts.entry(item).or_insert(0) += 1;
+    }
+
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+    let mut seen = HashSet::new();
+    for item in before.iter().chain(after) {
+        if !seen.insert(item.as_str()) {
+            continue;
+        }
+        let count = counts[item.as_str()];
+        for _ in 0..count.unsigned_abs() {
+            if count < 0 {
+                removed.push(format!("- {}", item));
+            } else {
+                added.push(format!("+ {}", item));
+            }
+        }
+    }
+    removed.extend(added);
+    removed
+}
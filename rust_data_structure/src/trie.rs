@@ -0,0 +1,156 @@
+//! A trie-backed map from string keys, for prefix lookups that `HashMap`
+//! and `BTreeMap` cannot answer directly.
+//!
+//! Each node holds its children in a `BTreeMap<char, _>`, so enumeration
+//! comes out in key order. Lookups cost O(key length) regardless of how
+//! many keys are stored.
+
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Node<V> {
+    value: Option<V>,
+    children: BTreeMap<char, Node<V>>,
+}
+
+impl<V> Node<V> {
+    fn new() -> Self {
+        Node {
+            value: None,
+            children: BTreeMap::new(),
+        }
+    }
+
+    fn collect<'a>(&'a self, key: &mut String, out: &mut Vec<(String, &'a V)>) {
+        if let Some(value) = &self.value {
+            out.push((key.clone(), value));
+        }
+        for (&c, child) in &self.children {
+            key.push(c);
+            child.collect(key, out);
+            key.pop();
+        }
+    }
+
+    /// Removes `key` below this node, pruning children left empty.
+    fn remove(&mut self, mut chars: std::str::Chars<'_>) -> Option<V> {
+        match chars.next() {
+            None => self.value.take(),
+            Some(c) => {
+                let child = self.children.get_mut(&c)?;
+                let removed = child.remove(chars);
+                if child.value.is_none() && child.children.is_empty() {
+                    self.children.remove(&c);
+                }
+                removed
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrieMap<V> {
+    root: Node<V>,
+    len: usize,
+}
+
+impl<V> Default for TrieMap<V> {
+    fn default() -> Self {
+        TrieMap::new()
+    }
+}
+
+impl<V> TrieMap<V> {
+    pub fn new() -> Self {
+        TrieMap {
+            root: Node::new(),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts `key`, returning the value it replaced.
+    pub fn insert(&mut self, key: &str, value: V) -> Option<V> {
+        let mut node = &mut self.root;
+        for c in key.chars() {
+            node = node.children.entry(c).or_insert_with(Node::new);
+        }
+        let old = node.value.replace(value);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    pub fn get(&self, key: &str) -> Option<&V> {
+        self.node(key)?.value.as_ref()
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<V> {
+        let removed = self.root.remove(key.chars());
+        if removed.is_some() {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    /// Every key starting with `prefix`, in key order.
+    pub fn with_prefix(&self, prefix: &str) -> Vec<(String, &V)> {
+        let mut out = Vec::new();
+        if let Some(node) = self.node(prefix) {
+            node.collect(&mut prefix.to_string(), &mut out);
+        }
+        out
+    }
+
+    /// All entries in key order.
+    pub fn entries(&self) -> Vec<(String, &V)> {
+        self.with_prefix("")
+    }
+
+    /// The longest stored key that is a prefix of `text`.
+    pub fn longest_prefix<'t>(&self, text: &'t str) -> Option<(&'t str, &V)> {
+        let mut node = &self.root;
+        let mut best = node.value.as_ref().map(|v| (&text[..0], v));
+        for (i, c) in text.char_indices() {
+            match node.children.get(&c) {
+                Some(child) => node = child,
+                None => break,
+            }
+            if let Some(value) = &node.value {
+                best = Some((&text[..i + c.len_utf8()], value));
+            }
+        }
+        best
+    }
+
+    fn node(&self, key: &str) -> Option<&Node<V>> {
+        let mut node = &self.root;
+        for c in key.chars() {
+            node = node.children.get(&c)?;
+        }
+        Some(node)
+    }
+}
+
+impl<V: Ord> TrieMap<V> {
+    /// Up to `limit` completions of `prefix`, highest value first; equal
+    /// values are ordered by key.
+    pub fn autocomplete(&self, prefix: &str, limit: usize) -> Vec<(String, &V)> {
+        let mut found = self.with_prefix(prefix);
+        found.sort_by(|(ka, va), (kb, vb)| vb.cmp(va).then_with(|| ka.cmp(kb)));
+        found.truncate(limit);
+        found
+    }
+}
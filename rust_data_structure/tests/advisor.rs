@@ -0,0 +1,105 @@
+use print_data_structs::advisor::{recommend, Collection, Workload};
+
+fn best(workload: &Workload) -> Collection {
+    recommend(workload)[0].collection
+}
+
+#[test]
+fn recommends_every_collection_once() {
+    let ranked = recommend(&Workload::default());
+    assert_eq!(ranked.len(), Collection::ALL.len());
+    for collection in Collection::ALL {
+        assert_eq!(
+            ranked.iter().filter(|r| r.collection == collection).count(),
+            1
+        );
+    }
+}
+
+#[test]
+fn ranking_is_sorted_by_score() {
+    let ranked = recommend(&Workload {
+        inserts: 1,
+        lookups: 9,
+        key_value: true,
+        ..Workload::default()
+    });
+    assert!(ranked.windows(2).all(|w| w[0].score >= w[1].score));
+}
+
+#[test]
+fn matches_the_reflection_notes() {
+    assert_eq!(
+        best(&Workload {
+            back_access: true,
+            duplicates: true,
+            ..Workload::default()
+        }),
+        Collection::Vec
+    );
+    assert_eq!(
+        best(&Workload {
+            front_access: true,
+            back_access: true,
+            ..Workload::default()
+        }),
+        Collection::VecDeque
+    );
+    assert_eq!(
+        best(&Workload {
+            inserts: 1,
+            lookups: 10,
+            key_value: true,
+            ..Workload::default()
+        }),
+        Collection::HashMap
+    );
+    assert_eq!(
+        best(&Workload {
+            lookups: 5,
+            key_value: true,
+            ordered: true,
+            range_queries: true,
+            ..Workload::default()
+        }),
+        Collection::BTreeMap
+    );
+    assert_eq!(
+        best(&Workload {
+            inserts: 1,
+            lookups: 10,
+            ..Workload::default()
+        }),
+        Collection::HashSet
+    );
+    assert_eq!(
+        best(&Workload {
+            ordered: true,
+            range_queries: true,
+            ..Workload::default()
+        }),
+        Collection::BTreeSet
+    );
+    assert_eq!(
+        best(&Workload {
+            inserts: 5,
+            priority: true,
+            duplicates: true,
+            ..Workload::default()
+        }),
+        Collection::BinaryHeap
+    );
+}
+
+#[test]
+fn every_adjustment_is_justified() {
+    for rec in recommend(&Workload {
+        inserts: 3,
+        lookups: 3,
+        ordered: true,
+        front_access: true,
+        ..Workload::default()
+    }) {
+        assert!(!rec.reasons.is_empty(), "{} has no reasons", rec.collection);
+    }
+}
@@ -0,0 +1,134 @@
+use print_data_structs::balance::{
+    self, parse_servers, Balancer, ConsistentHash, LeastConnections, Request, Server, Strategy,
+    Weighted,
+};
+
+fn servers() -> Vec<Server> {
+    parse_servers("big:3,small,tiny:1").unwrap()
+}
+
+fn picks(balancer: &mut dyn Balancer, n: usize) -> Vec<usize> {
+    (0..n)
+        .map(|i| balancer.pick(&format!("client-{}", i)))
+        .collect()
+}
+
+#[test]
+fn least_connections_tracks_releases() {
+    let mut lc = LeastConnections::new(3);
+    assert_eq!(picks(&mut lc, 3), vec![0, 1, 2]);
+    lc.release(1);
+    assert_eq!(lc.pick("x"), 1);
+    lc.release(2);
+    lc.release(0);
+    assert_eq!(picks(&mut lc, 2), vec![0, 2]);
+    assert_eq!(
+        (lc.connections(0), lc.connections(1), lc.connections(2)),
+        (1, 1, 1)
+    );
+    lc.release(2);
+    lc.release(2);
+    assert_eq!(lc.connections(2), 0);
+}
+
+#[test]
+fn weighted_interleaves_by_weight() {
+    let mut weighted = Weighted::new(&servers());
+    assert_eq!(picks(&mut weighted, 5), vec![0, 1, 0, 2, 0]);
+    let mut counts = [0; 3];
+    for server in picks(&mut weighted, 500) {
+        counts[server] += 1;
+    }
+    assert_eq!(counts, [300, 100, 100]);
+}
+
+#[test]
+fn consistent_hash_keeps_most_keys_when_a_server_leaves() {
+    let servers = parse_servers("a,b,c,d").unwrap();
+    let mut ring = ConsistentHash::new(&servers, 100);
+    let keys: Vec<String> = (0..2000).map(|i| format!("user-{}", i)).collect();
+    let before: Vec<usize> = keys.iter().map(|k| ring.owner(k).unwrap()).collect();
+    assert_eq!(ring.pick(&keys[7]), before[7]);
+    for server in 0..4 {
+        let share = before.iter().filter(|&&s| s == server).count();
+        assert!(
+            (300..700).contains(&share),
+            "server {} got {}",
+            server,
+            share
+        );
+    }
+
+    ring.remove(3);
+    for (key, &old) in keys.iter().zip(&before) {
+        let new = ring.owner(key).unwrap();
+        assert!(new != 3);
+        if old != 3 {
+            assert_eq!(new, old, "{} moved off a surviving server", key);
+        }
+    }
+}
+
+#[test]
+fn extreme_inputs_do_not_overflow_or_empty_the_ring() {
+    let servers = parse_servers("a,b").unwrap();
+    let trace = vec![
+        Request {
+            time: u64::MAX - 1,
+            key: "x".into(),
+            duration: u64::MAX,
+        },
+        Request {
+            time: u64::MAX,
+            key: "y".into(),
+            duration: u64::MAX,
+        },
+    ];
+    let report = balance::simulate(&servers, &trace, Strategy::RoundRobin);
+    assert_eq!(report.servers[0].busy, u64::MAX);
+    assert_eq!(report.servers[1].peak, 1);
+
+    assert!(std::panic::catch_unwind(|| ConsistentHash::new(&servers, 0)).is_err());
+}
+
+#[test]
+fn simulator_reports_load_and_skew() {
+    let trace: Vec<Request> = (0..300)
+        .map(|i| Request {
+            time: i,
+            key: format!("c{}", i % 7),
+            duration: if i % 3 == 0 { 30 } else { 2 },
+        })
+        .collect();
+    let servers = parse_servers("a,b,c").unwrap();
+
+    let rr = balance::simulate(&servers, &trace, Strategy::RoundRobin);
+    assert_eq!(rr.skew(), 1.0);
+    // Every long request lands on server a under round-robin.
+    assert!(rr.servers[0].busy > rr.servers[1].busy * 4);
+
+    let lc = balance::simulate(&servers, &trace, Strategy::LeastConnections);
+    let peaks: Vec<u64> = lc.servers.iter().map(|s| s.peak).collect();
+    assert!(peaks.iter().max().unwrap() - peaks.iter().min().unwrap() <= 1);
+    assert!(lc.servers[0].peak < rr.servers[0].peak);
+
+    let hashed = balance::simulate(&servers, &trace, Strategy::ConsistentHash);
+    assert_eq!(hashed.servers.iter().map(|s| s.requests).sum::<u64>(), 300);
+    assert!(hashed.skew() >= 1.0);
+}
+
+#[test]
+fn parses_servers_and_traces() {
+    assert_eq!(servers()[0], Server::new("big", 3));
+    assert!(parse_servers("a,a").is_err());
+    assert!(parse_servers("a:0").is_err());
+    assert_eq!(
+        Strategy::from_name("least"),
+        Some(Strategy::LeastConnections)
+    );
+
+    let trace = balance::parse_trace("time,key,duration\n0,alice,5\n3,\"bob\",1\n").unwrap();
+    assert_eq!(trace.len(), 2);
+    assert_eq!(trace[1].key, "bob");
+    assert!(balance::parse_trace("0,a,1\n1,b\n").is_err());
+}
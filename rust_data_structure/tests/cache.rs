@@ -0,0 +1,139 @@
+use std::time::Duration;
+
+use print_data_structs::cache::{Cache, LfuCache, LruCache, ManualClock, Stats};
+
+#[test]
+fn lru_evicts_least_recently_used() {
+    let mut cache = LruCache::new(2);
+    cache.put("a", 1);
+    cache.put("b", 2);
+    assert_eq!(cache.get(&"a"), Some(&1));
+    assert_eq!(cache.put("c", 3), Some(("b", 2)));
+    assert_eq!(cache.eviction_order(), vec!["a", "c"]);
+    assert_eq!(cache.get(&"b"), None);
+    assert_eq!(
+        cache.stats(),
+        Stats {
+            hits: 1,
+            misses: 1,
+            evictions: 1,
+            expirations: 0
+        }
+    );
+}
+
+#[test]
+fn lfu_evicts_least_frequently_used() {
+    let mut cache = LfuCache::new(2);
+    cache.put("a", 1);
+    cache.put("b", 2);
+    cache.get(&"a");
+    cache.get(&"a");
+    cache.get(&"b");
+    assert_eq!(cache.uses(&"a"), Some(3));
+    // "b" has fewer uses even though it was read last.
+    assert_eq!(cache.put("c", 3), Some(("b", 2)));
+    // Ties go to the least recently used entry.
+    cache.get(&"c");
+    cache.get(&"c");
+    assert_eq!(cache.eviction_order(), vec!["a", "c"]);
+    assert_eq!(cache.put("d", 4), Some(("a", 1)));
+}
+
+#[test]
+fn entries_expire_on_the_injected_clock() {
+    let clock = ManualClock::new();
+    let mut lru = LruCache::with_clock(3, Some(Duration::from_secs(10)), clock.clone());
+    let mut lfu = LfuCache::with_clock(3, Some(Duration::from_secs(10)), clock.clone());
+    for cache in [&mut lru as &mut dyn Cache<&str, i32>, &mut lfu] {
+        cache.put("short", 1);
+        cache.put_with_ttl("long", 2, Some(Duration::from_secs(60)));
+        cache.put_with_ttl("forever", 3, None);
+    }
+    clock.advance(Duration::from_secs(10));
+    for cache in [&mut lru as &mut dyn Cache<&str, i32>, &mut lfu] {
+        assert_eq!(cache.peek(&"short"), None);
+        assert_eq!(cache.get(&"short"), None);
+        assert_eq!(cache.get(&"long"), Some(&2));
+        assert_eq!(cache.stats().expirations, 1);
+        assert_eq!(cache.len(), 2);
+    }
+}
+
+#[test]
+fn huge_ttls_never_expire_and_the_clock_saturates() {
+    let clock = ManualClock::new();
+    clock.advance(Duration::from_secs(1));
+    let huge = Some(Duration::from_secs(u64::MAX));
+    let mut lru = LruCache::with_clock(2, huge, clock.clone());
+    let mut lfu = LfuCache::with_clock(2, huge, clock.clone());
+    for cache in [&mut lru as &mut dyn Cache<&str, i32>, &mut lfu] {
+        cache.put("a", 1);
+        cache.put_with_ttl("b", 2, Some(Duration::MAX));
+    }
+    clock.advance(Duration::MAX);
+    for cache in [&mut lru as &mut dyn Cache<&str, i32>, &mut lfu] {
+        assert_eq!(cache.purge_expired(), 0);
+        assert_eq!(cache.get(&"a"), Some(&1));
+        assert_eq!(cache.get(&"b"), Some(&2));
+    }
+}
+
+#[test]
+fn expired_entries_make_room_before_live_ones_are_evicted() {
+    let clock = ManualClock::new();
+    let mut cache = LruCache::with_clock(2, None, clock.clone());
+    cache.put_with_ttl("old", 1, Some(Duration::from_secs(1)));
+    cache.put("live", 2);
+    clock.advance(Duration::from_secs(2));
+    assert_eq!(cache.put("new", 3), None);
+    assert_eq!(cache.stats().evictions, 0);
+    assert_eq!(cache.stats().expirations, 1);
+    assert_eq!(cache.eviction_order(), vec!["live", "new"]);
+}
+
+#[test]
+fn updates_and_removals_keep_the_order_consistent() {
+    let mut cache = LruCache::new(3);
+    for (k, v) in [("a", 1), ("b", 2), ("c", 3)] {
+        cache.put(k, v);
+    }
+    assert_eq!(cache.put("a", 10), None);
+    assert_eq!(cache.remove(&"b"), Some(2));
+    cache.put("d", 4);
+    cache.put("e", 5);
+    assert_eq!(cache.eviction_order(), vec!["a", "d", "e"]);
+    assert_eq!(cache.peek(&"a"), Some(&10));
+    assert_eq!(cache.stats().hit_rate(), None);
+}
+
+#[test]
+fn rewritten_and_evicted_entries_leave_the_expiry_index() {
+    let clock = ManualClock::new();
+    let mut lru = LruCache::with_clock(2, Some(Duration::from_secs(5)), clock.clone());
+    let mut lfu = LfuCache::with_clock(2, Some(Duration::from_secs(5)), clock.clone());
+    for cache in [&mut lru as &mut dyn Cache<&str, i32>, &mut lfu] {
+        cache.put("a", 1);
+        cache.put("b", 2);
+        // Rewriting "a" pushes its expiry back; evicting "b" forgets it.
+        cache.put_with_ttl("a", 10, Some(Duration::from_secs(20)));
+        cache.get(&"a");
+        assert_eq!(cache.put_with_ttl("c", 3, None), Some(("b", 2)));
+    }
+    clock.advance(Duration::from_secs(10));
+    for cache in [&mut lru as &mut dyn Cache<&str, i32>, &mut lfu] {
+        assert_eq!(cache.purge_expired(), 0);
+        assert_eq!(cache.peek(&"a"), Some(&10));
+        // A zero TTL is already expired, so it is the first to make room.
+        cache.remove(&"c");
+        cache.put_with_ttl("now", 4, Some(Duration::ZERO));
+        assert_eq!(cache.put("d", 5), None);
+        assert_eq!(cache.stats().expirations, 1);
+        assert_eq!(cache.len(), 2);
+    }
+    clock.advance(Duration::from_secs(30));
+    for cache in [&mut lru as &mut dyn Cache<&str, i32>, &mut lfu] {
+        assert_eq!(cache.purge_expired(), 2);
+        assert!(cache.is_empty());
+    }
+}
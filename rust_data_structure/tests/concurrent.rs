@@ -0,0 +1,213 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use print_data_structs::concurrent::{BoundedQueue, PriorityQueue, PushError, ShardedMap};
+
+const THREADS: usize = 8;
+const PER_THREAD: usize = 5_000;
+
+#[test]
+fn sharded_map_survives_concurrent_writers() {
+    let map = ShardedMap::with_shards(8);
+    thread::scope(|scope| {
+        for t in 0..THREADS {
+            let map = &map;
+            scope.spawn(move || {
+                for i in 0..PER_THREAD {
+                    map.insert(t * PER_THREAD + i, t);
+                    // Every thread also bumps the same few hot counters.
+                    map.update(usize::MAX - i % 4, || 0, |n| *n += 1);
+                }
+            });
+        }
+    });
+    assert_eq!(map.len(), THREADS * PER_THREAD + 4);
+    for hot in 0..4 {
+        assert_eq!(map.get(&(usize::MAX - hot)), Some(THREADS * PER_THREAD / 4));
+    }
+    assert!(map
+        .shard_sizes()
+        .iter()
+        .all(|&n| n > THREADS * PER_THREAD / 16));
+    assert_eq!(map.remove(&0), Some(0));
+    assert!(!map.contains_key(&0));
+    assert_eq!(map.snapshot().len(), map.len());
+}
+
+#[test]
+fn a_panicking_closure_does_not_poison_the_map() {
+    let map = ShardedMap::with_shards(1);
+    map.insert("a", 1);
+    let panicked = std::panic::catch_unwind(|| {
+        map.update(
+            "a",
+            || 0,
+            |n| {
+                *n += 1;
+                panic!("halfway");
+            },
+        )
+    });
+    assert!(panicked.is_err());
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            assert_eq!(map.get(&"a"), Some(2));
+            map.update("a", || 0, |n| *n += 1);
+            map.insert("b", 5);
+        });
+    });
+    assert_eq!(map.read(&"a", |n| n.copied()), Some(3));
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.snapshot().len(), 2);
+}
+
+#[test]
+fn bounded_queue_delivers_every_item_exactly_once() {
+    let queue = Arc::new(BoundedQueue::new(16));
+    let producers: Vec<_> = (0..THREADS)
+        .map(|t| {
+            let queue = Arc::clone(&queue);
+            thread::spawn(move || {
+                for i in 0..PER_THREAD {
+                    queue.push(t * PER_THREAD + i).unwrap();
+                }
+            })
+        })
+        .collect();
+    let consumers: Vec<_> = (0..THREADS / 2)
+        .map(|_| {
+            let queue = Arc::clone(&queue);
+            thread::spawn(move || {
+                let mut got = Vec::new();
+                while let Some(item) = queue.pop() {
+                    assert!(queue.len() <= queue.capacity());
+                    got.push(item);
+                }
+                got
+            })
+        })
+        .collect();
+
+    for producer in producers {
+        producer.join().unwrap();
+    }
+    queue.close();
+    let mut seen = HashSet::new();
+    for consumer in consumers {
+        for item in consumer.join().unwrap() {
+            assert!(seen.insert(item), "{} delivered twice", item);
+        }
+    }
+    assert_eq!(seen.len(), THREADS * PER_THREAD);
+}
+
+#[test]
+fn bounded_queue_keeps_fifo_and_honours_timeouts() {
+    let queue = BoundedQueue::new(2);
+    queue.push(1).unwrap();
+    queue.try_push(2).unwrap();
+    assert_eq!(queue.try_push(3), Err(PushError::Full(3)));
+
+    let started = Instant::now();
+    assert_eq!(
+        queue.push_timeout(3, Duration::from_millis(50)),
+        Err(PushError::Full(3))
+    );
+    assert!(started.elapsed() >= Duration::from_millis(50));
+
+    assert_eq!(queue.pop(), Some(1));
+    assert_eq!(queue.try_pop(), Some(2));
+    assert_eq!(queue.pop_timeout(Duration::from_millis(20)), None);
+
+    // A blocked consumer is woken by a late producer.
+    thread::scope(|scope| {
+        let waiter = scope.spawn(|| queue.pop_timeout(Duration::from_secs(5)));
+        thread::sleep(Duration::from_millis(20));
+        queue.push(7).unwrap();
+        assert_eq!(waiter.join().unwrap(), Some(7));
+    });
+
+    queue.push(8).unwrap();
+    queue.close();
+    assert_eq!(queue.push(9).map_err(PushError::into_inner), Err(9));
+    assert_eq!(queue.pop(), Some(8));
+    assert_eq!(queue.pop(), None);
+}
+
+#[test]
+fn close_wakes_blocked_threads() {
+    let queue = BoundedQueue::new(1);
+    queue.push(0).unwrap();
+    let heap: PriorityQueue<i32> = PriorityQueue::new();
+    thread::scope(|scope| {
+        let producer = scope.spawn(|| queue.push(1));
+        let consumer = scope.spawn(|| heap.pop());
+        thread::sleep(Duration::from_millis(20));
+        queue.close();
+        heap.close();
+        assert_eq!(producer.join().unwrap(), Err(PushError::Closed(1)));
+        assert_eq!(consumer.join().unwrap(), None);
+    });
+}
+
+#[test]
+fn unrepresentable_timeouts_wait_without_a_deadline() {
+    let queue = BoundedQueue::new(1);
+    queue.push_timeout(1, Duration::MAX).unwrap();
+    assert_eq!(queue.pop_timeout(Duration::MAX), Some(1));
+    let heap: PriorityQueue<i32> = PriorityQueue::new();
+    thread::scope(|scope| {
+        let consumer = scope.spawn(|| queue.pop_timeout(Duration::MAX));
+        let waiter = scope.spawn(|| heap.pop_timeout(Duration::MAX));
+        thread::sleep(Duration::from_millis(20));
+        queue.push(2).unwrap();
+        heap.close();
+        assert_eq!(consumer.join().unwrap(), Some(2));
+        assert_eq!(waiter.join().unwrap(), None);
+    });
+}
+
+#[test]
+fn priority_queue_serves_highest_first_under_contention() {
+    let heap = PriorityQueue::new();
+    thread::scope(|scope| {
+        for t in 0..THREADS {
+            let heap = &heap;
+            scope.spawn(move || {
+                for i in 0..PER_THREAD {
+                    heap.push(i * THREADS + t).unwrap();
+                }
+            });
+        }
+    });
+    assert_eq!(heap.len(), THREADS * PER_THREAD);
+    assert_eq!(heap.peek(), Some(THREADS * PER_THREAD - 1));
+
+    heap.close();
+    let drained: Vec<Vec<usize>> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut got = Vec::new();
+                    while let Some(item) = heap.pop() {
+                        got.push(item);
+                    }
+                    got
+                })
+            })
+            .collect();
+        workers.into_iter().map(|w| w.join().unwrap()).collect()
+    });
+    let mut total = 0;
+    for got in &drained {
+        assert!(
+            got.windows(2).all(|w| w[0] > w[1]),
+            "each consumer sees descending order"
+        );
+        total += got.len();
+    }
+    assert_eq!(total, THREADS * PER_THREAD);
+    assert_eq!(heap.push(1), Err(1));
+}
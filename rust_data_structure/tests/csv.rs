@@ -0,0 +1,193 @@
+use print_data_structs::csv::{
+    infer_type, read_table, CsvError, CsvOptions, Quoting, Reader, TypedReader, Writer,
+};
+use print_data_structs::table::{DataType, Decimal, Table, Value};
+
+fn records(text: &str, options: &CsvOptions) -> Result<Vec<Vec<String>>, CsvError> {
+    Reader::new(text.as_bytes(), options).collect()
+}
+
+#[test]
+fn reader_handles_quotes_newlines_and_delimiters() {
+    let text = "name;note\r\n\"Smith; J\";\"said \"\"hi\"\"\nthen left\"\n\nplain;\n";
+    let rows = records(text, &CsvOptions::default().delimiter(';')).unwrap();
+    assert_eq!(
+        rows,
+        vec![
+            vec!["name", "note"],
+            vec!["Smith; J", "said \"hi\"\nthen left"],
+            vec!["plain", ""],
+        ]
+    );
+}
+
+#[test]
+fn reader_errors_carry_positions() {
+    let err = records("a,b\n1,\"open\n", &CsvOptions::default()).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            CsvError::Parse {
+                row: 2,
+                column: 2,
+                ..
+            }
+        ),
+        "{}",
+        err
+    );
+
+    let err = records("a,b\n1,\"x\"y\n", &CsvOptions::default()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "row 2, column 2: unexpected 'y' after closing quote"
+    );
+}
+
+#[test]
+fn infers_types_from_samples() {
+    assert_eq!(infer_type(["1", "", "-3"]), DataType::Int);
+    assert_eq!(
+        infer_type(["1.5", "2", "0.25"]),
+        DataType::Decimal { scale: 2 }
+    );
+    assert_eq!(infer_type(["1e3", "2.5"]), DataType::Float);
+    assert_eq!(infer_type(["TRUE", "false"]), DataType::Bool);
+    assert_eq!(infer_type(["1", "x"]), DataType::Str);
+    assert_eq!(infer_type([""]), DataType::Str);
+    // Each fits alone, but not both at the widest scale.
+    let tiny = "0.000000000000000000000000000001";
+    assert_eq!(infer_type([tiny, "12345678901.5"]), DataType::Float);
+    let wide = "123456789012345678901234567890123456789.5";
+    assert_eq!(infer_type([wide]), DataType::Float);
+    let text = format!("x\n{}\n12345678901.5\n", tiny);
+    let table = read_table(text.as_bytes(), &CsvOptions::default()).unwrap();
+    assert_eq!(table.row(1), Some(vec![Value::Float(12345678901.5)]));
+
+    let text = "id,price,tag\n1,2.50,a\n2,3,\n";
+    let reader = TypedReader::new(text.as_bytes(), &CsvOptions::default()).unwrap();
+    let types: Vec<DataType> = reader.schema().iter().map(|(_, t)| *t).collect();
+    assert_eq!(
+        types,
+        vec![DataType::Int, DataType::Decimal { scale: 2 }, DataType::Str]
+    );
+    let rows: Vec<Vec<Value>> = reader.collect::<Result<_, _>>().unwrap();
+    assert_eq!(rows[1][1], Value::Decimal(Decimal::new(300, 2)));
+    assert_eq!(rows[1][2], Value::Null);
+}
+
+#[test]
+fn overrides_and_late_type_errors() {
+    // Only the first row is sampled, so "x" in row 2 fails conversion.
+    let text = "1,10\n2,x\n";
+    let options = CsvOptions::default()
+        .has_header(false)
+        .sample_rows(1)
+        .with_type("column1", DataType::Float);
+    let mut reader = TypedReader::new(text.as_bytes(), &options).unwrap();
+    assert_eq!(
+        reader.schema(),
+        &[
+            ("column1".to_string(), DataType::Float),
+            ("column2".to_string(), DataType::Int),
+        ]
+    );
+    assert_eq!(
+        reader.read_values().unwrap(),
+        Some(vec![Value::Float(1.0), Value::Int(10)])
+    );
+    let err = reader.read_values().unwrap_err();
+    assert!(
+        matches!(
+            err,
+            CsvError::Parse {
+                row: 2,
+                column: 2,
+                ..
+            }
+        ),
+        "{}",
+        err
+    );
+
+    let options = CsvOptions::default().with_type("missing", DataType::Int);
+    assert!(TypedReader::new("a\n1\n".as_bytes(), &options).is_err());
+
+    let err = read_table("a,b\n1\n".as_bytes(), &CsvOptions::default()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "row 2, column 2: expected 2 fields, found 1"
+    );
+}
+
+#[test]
+fn writer_round_trips_tables() {
+    let text = "name,qty,paid\n\"Lee, A\",3,true\nBo,,false\n";
+    let table = read_table(text.as_bytes(), &CsvOptions::default()).unwrap();
+    assert_eq!(table.num_rows(), 2);
+
+    let mut writer = Writer::new(Vec::new());
+    writer.write_table(&table).unwrap();
+    assert_eq!(String::from_utf8(writer.into_inner()).unwrap(), text);
+
+    let mut writer = Writer::new(Vec::new()).quoting(Quoting::NonNumeric);
+    writer.write_table(&table).unwrap();
+    assert_eq!(
+        String::from_utf8(writer.into_inner()).unwrap(),
+        "\"name\",\"qty\",\"paid\"\n\"Lee, A\",3,\"true\"\n\"Bo\",,\"false\"\n"
+    );
+
+    let mut writer = Writer::new(Vec::new())
+        .delimiter('\t')
+        .quoting(Quoting::Always);
+    writer.write_record(&["a\"b", "c"]).unwrap();
+    assert_eq!(writer.into_inner(), b"\"a\"\"b\"\t\"c\"\n");
+
+    let mut writer = Writer::new(Vec::new()).quoting(Quoting::Never);
+    assert!(writer.write_record(&["a,b"]).is_err());
+}
+
+#[test]
+fn nulls_and_empty_strings_survive_a_round_trip() {
+    let mut table = Table::new(&[("note", DataType::Str)]).unwrap();
+    for value in [
+        Value::Str("a".into()),
+        Value::Null,
+        Value::Str(String::new()),
+        Value::Null,
+    ] {
+        table.push_row(vec![value]).unwrap();
+    }
+    for quoting in [Quoting::Minimal, Quoting::Always, Quoting::NonNumeric] {
+        let mut writer = Writer::new(Vec::new()).quoting(quoting);
+        writer.write_table(&table).unwrap();
+        let text = writer.into_inner();
+        if quoting == Quoting::Minimal {
+            assert_eq!(text, b"note\na\n\n\"\"\n\n");
+        }
+        let back = read_table(&text[..], &CsvOptions::default()).unwrap();
+        assert_eq!(back.num_rows(), 4, "{:?}", quoting);
+        assert_eq!(back.row(1), Some(vec![Value::Null]));
+        assert_eq!(back.row(2), Some(vec![Value::Str(String::new())]));
+    }
+
+    let mut table = Table::new(&[("id", DataType::Int), ("name", DataType::Str)]).unwrap();
+    table
+        .push_row(vec![Value::Null, Value::Str(String::new())])
+        .unwrap();
+    table.push_row(vec![Value::Int(2), Value::Null]).unwrap();
+    let mut writer = Writer::new(Vec::new());
+    writer.write_table(&table).unwrap();
+    let text = writer.into_inner();
+    assert_eq!(text, b"id,name\n,\"\"\n2,\n");
+    let options = CsvOptions::default().with_type("name", DataType::Str);
+    let back = read_table(&text[..], &options).unwrap();
+    assert_eq!(
+        back.row(0),
+        Some(vec![Value::Null, Value::Str(String::new())])
+    );
+    assert_eq!(back.row(1), Some(vec![Value::Int(2), Value::Null]));
+
+    let mut writer = Writer::new(Vec::new()).quoting(Quoting::Never);
+    assert!(writer.write_table(&table).is_err());
+}
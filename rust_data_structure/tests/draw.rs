@@ -0,0 +1,55 @@
+use std::collections::{BTreeMap, BinaryHeap, LinkedList, VecDeque};
+
+use print_data_structs::draw;
+
+#[test]
+fn vec_shows_length_and_spare_capacity() {
+    let mut v = Vec::with_capacity(4);
+    v.extend([10, 20, 30]);
+    let out = draw::vec(&v);
+    assert!(out.starts_with("Vec: len 3 / capacity 4"));
+    assert!(out.contains("| 10 | 20 | 30 | ·  |"));
+}
+
+#[test]
+fn vecdeque_marks_head_and_tail_when_wrapped() {
+    let mut d = VecDeque::with_capacity(4);
+    d.extend([1, 2, 3, 4]);
+    d.pop_front();
+    d.pop_front();
+    d.push_back(5);
+    let cap = d.capacity();
+    let out = draw::vecdeque(&d);
+    assert!(out.contains("wrapped"));
+    assert!(out.contains("| 5 |"));
+    assert!(out.contains("| 3 | 4 |"));
+    let markers = out.lines().nth(2).unwrap();
+    assert_eq!(markers.find('T'), Some(2 + 4));
+    assert_eq!(markers.find('H'), Some(2 + 4 * (cap - 2)));
+}
+
+#[test]
+fn linkedlist_chains_nodes() {
+    let list: LinkedList<i32> = [1, 5, 10].into_iter().collect();
+    assert!(draw::linkedlist(&list).contains("head -> [1] <-> [5] <-> [10] <- tail"));
+}
+
+#[test]
+fn binaryheap_draws_one_line_per_level() {
+    let heap: BinaryHeap<i32> = [3, 1, 4, 1, 5, 9, 2].into_iter().collect();
+    let out = draw::binaryheap(&heap);
+    let levels: Vec<&str> = out.lines().filter(|l| l.starts_with('L')).collect();
+    assert_eq!(levels.len(), 3);
+    assert!(levels[0].contains('9'));
+    assert_eq!(levels[2].split_whitespace().count(), 5);
+}
+
+#[test]
+fn btreemap_buckets_are_sorted() {
+    let map: BTreeMap<String, i32> = (0..15).map(|i| (format!("k{:02}", i), i)).collect();
+    let out = draw::btreemap(&map);
+    let buckets: Vec<&str> = out.lines().nth(1).unwrap().split(" -> ").collect();
+    assert_eq!(buckets.len(), 2);
+    assert!(buckets[0].starts_with("[k00=0"));
+    assert!(buckets[1].ends_with("k14=14]"));
+}
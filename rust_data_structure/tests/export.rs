@@ -0,0 +1,66 @@
+use print_data_structs::export::{self, ExportOptions, Format};
+use print_data_structs::import::{self, ImportOptions};
+
+fn options(format: Format, sorted: bool) -> ExportOptions {
+    ExportOptions { format, sorted }
+}
+
+#[test]
+fn csv_keeps_given_order_unless_sorted() {
+    assert_eq!(
+        export::values(vec![3, 1, 2], &options(Format::Csv, false)),
+        "value\n3\n1\n2\n"
+    );
+    assert_eq!(
+        export::values(vec![3, 1, 2], &options(Format::Csv, true)),
+        "value\n1\n2\n3\n"
+    );
+}
+
+#[test]
+fn csv_quotes_keys_that_need_it() {
+    let pairs = vec![(String::from("b,c"), 2), (String::from("a"), 1)];
+    assert_eq!(
+        export::pairs(pairs, &options(Format::Csv, true)),
+        "key,value\na,1\n\"b,c\",2\n"
+    );
+}
+
+#[test]
+fn json_and_markdown() {
+    let pairs = vec![(String::from("x|y"), 5)];
+    assert_eq!(
+        export::pairs(pairs.clone(), &options(Format::Json, false)),
+        "[\n  {\"key\":\"x|y\",\"value\":5}\n]\n"
+    );
+    assert_eq!(
+        export::pairs(pairs, &options(Format::Markdown, false)),
+        "| key | value |\n| --- | ---: |\n| x\\|y | 5 |\n"
+    );
+    assert_eq!(
+        export::values(vec![], &options(Format::Json, false)),
+        "[]\n"
+    );
+}
+
+#[test]
+fn jsonl_export_round_trips_through_import() {
+    let pairs = vec![(String::from("a"), 1), (String::from("b \"q\""), -4)];
+    let text = export::pairs(pairs.clone(), &options(Format::JsonLines, false));
+
+    let mut read = ImportOptions::new(import::Format::JsonLines);
+    read.key = import::Field::Name(String::from("key"));
+    read.value = import::Field::Name(String::from("value"));
+    assert_eq!(import::pairs(&text, &read).records, pairs);
+}
+
+#[test]
+fn parses_command_arguments() {
+    let (path, opts) = export::parse_args(&["out.md", "--sorted"]).unwrap();
+    assert_eq!(path, "out.md");
+    assert_eq!(opts, options(Format::Markdown, true));
+
+    let (_, opts) = export::parse_args(&["out.txt", "--format", "json"]).unwrap();
+    assert_eq!(opts.format, Format::Json);
+    assert!(export::parse_args(&["out.txt", "--format", "xml"]).is_err());
+}
@@ -0,0 +1,186 @@
+use std::fs;
+use std::path::PathBuf;
+
+use print_data_structs::extsort::{self, Format, KeySpec, SortKey, SortOptions};
+use print_data_structs::import::Field;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("extsort-test-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn sort_text(input: &str, options: &SortOptions, spec: KeySpec) -> (String, extsort::SortStats) {
+    let mut out = Vec::new();
+    let stats = extsort::sort(input.as_bytes(), &mut out, options, |line| {
+        spec.extract(line, options.format)
+    })
+    .unwrap();
+    (String::from_utf8(out).unwrap(), stats)
+}
+
+#[test]
+fn sorts_in_memory_under_budget() {
+    let options = SortOptions::new(Format::Lines);
+    let whole = KeySpec {
+        field: None,
+        numeric: false,
+    };
+    let (out, stats) = sort_text("pear\napple\nfig\n", &options, whole);
+    assert_eq!(out, "apple\nfig\npear\n");
+    assert_eq!(stats.rows, 3);
+    assert_eq!(stats.runs, 0);
+}
+
+#[test]
+fn spills_runs_and_merges_them() {
+    let dir = temp_dir("spill");
+    let mut options = SortOptions::new(Format::Lines);
+    options.memory_budget = 200;
+    options.temp_dir = dir.clone();
+    let input: String = (0..100).map(|i| format!("{}\n", (i * 37) % 100)).collect();
+    let numeric = KeySpec {
+        field: Some(0),
+        numeric: true,
+    };
+
+    let (out, stats) = sort_text(&input, &options, numeric);
+    let expected: String = (0..100).map(|i| format!("{}\n", i)).collect();
+    assert_eq!(out, expected);
+    assert!(stats.runs > 1, "expected several runs, got {}", stats.runs);
+    assert_eq!(
+        fs::read_dir(&dir).unwrap().count(),
+        0,
+        "run files left behind"
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn keeps_equal_keys_in_input_order_across_runs() {
+    let dir = temp_dir("stable");
+    let mut options = SortOptions::new(Format::Csv { header: true });
+    options.memory_budget = 120;
+    options.temp_dir = dir.clone();
+    let input = "name,team\nann,b\nbob,a\ncid,b\ndan,a\neve,b\nfay,a\n";
+    let team = KeySpec {
+        field: Some(extsort::resolve_field(&Field::parse("team"), input.lines().next()).unwrap()),
+        numeric: false,
+    };
+
+    let (out, stats) = sort_text(input, &options, team);
+    assert_eq!(out, "name,team\nbob,a\ndan,a\nfay,a\nann,b\ncid,b\neve,b\n");
+    assert_eq!(stats.rows, 6);
+    assert!(stats.runs > 1);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn merges_many_runs_in_passes() {
+    let dir = temp_dir("fan-in");
+    let mut options = SortOptions::new(Format::Lines);
+    options.memory_budget = 100;
+    options.fan_in = 3;
+    options.temp_dir = dir.clone();
+    // Equal keys (the first field) must stay in input order (the second).
+    let input: String = (0..200)
+        .map(|i| format!("{} {}\n", (i * 7) % 10, i))
+        .collect();
+    let first = KeySpec {
+        field: Some(0),
+        numeric: true,
+    };
+
+    let (out, stats) = sort_text(&input, &options, first);
+    let mut expected: Vec<(i32, i32)> = (0..200).map(|i| ((i * 7) % 10, i)).collect();
+    expected.sort_by_key(|&(key, _)| key);
+    let expected: String = expected
+        .iter()
+        .map(|(key, i)| format!("{} {}\n", key, i))
+        .collect();
+    assert_eq!(out, expected);
+    assert!(
+        stats.runs > 9,
+        "expected several passes, got {} runs",
+        stats.runs
+    );
+    assert_eq!(
+        fs::read_dir(&dir).unwrap().count(),
+        0,
+        "run files left behind"
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn numeric_keys_sort_before_text() {
+    let spec = KeySpec {
+        field: Some(1),
+        numeric: true,
+    };
+    let format = Format::Csv { header: false };
+    assert_eq!(spec.extract("a,10", format), SortKey::Number(10));
+    assert_eq!(
+        spec.extract("a,n/a", format),
+        SortKey::Text(String::from("n/a"))
+    );
+    assert!(spec.extract("a,999", format) < spec.extract("a,abc", format));
+    assert!(extsort::resolve_field(&Field::parse("missing"), Some("a,b")).is_err());
+}
+
+#[test]
+fn parses_subcommand_arguments() {
+    let args = extsort::parse_args(&[
+        "in.csv",
+        "-o",
+        "out.csv",
+        "--key",
+        "2",
+        "--numeric",
+        "--memory",
+        "4K",
+    ])
+    .unwrap();
+    assert_eq!(args.options.format, Format::Csv { header: true });
+    assert_eq!(args.output.as_deref(), Some("out.csv"));
+    assert_eq!(args.key, Some(Field::Index(2)));
+    assert!(args.numeric && !args.reverse);
+    assert_eq!(args.options.memory_budget, 4096);
+    for flags in [["--no-header", "--csv"], ["--csv", "--no-header"]] {
+        let args = extsort::parse_args(&["in.txt", flags[0], flags[1]]).unwrap();
+        assert_eq!(args.options.format, Format::Csv { header: false });
+    }
+    assert!(extsort::parse_args(&["in.txt", "--memory", "lots"]).is_err());
+    assert!(extsort::parse_args(&[]).is_err());
+}
+
+#[test]
+fn csv_records_keep_quoted_line_breaks_together() {
+    let dir = temp_dir("multiline");
+    let mut options = SortOptions::new(Format::Csv { header: true });
+    options.memory_budget = 60;
+    options.temp_dir = dir.clone();
+    let by_id = KeySpec {
+        field: Some(0),
+        numeric: true,
+    };
+    let input = "id,note\n3,\"c\nsecond line\"\n1,plain\n2,\"b, \"\"quoted\"\"\r\nzz\"\n";
+    let (out, stats) = sort_text(input, &options, by_id);
+    assert_eq!(
+        out,
+        "id,note\n1,plain\n2,\"b, \"\"quoted\"\"\r\nzz\"\n3,\"c\nsecond line\"\n"
+    );
+    assert_eq!(stats.rows, 3);
+    assert!(stats.runs > 1);
+
+    let mut out = Vec::new();
+    let err = extsort::sort(
+        "id\n2\n1,\"open\n0\n".as_bytes(),
+        &mut out,
+        &options,
+        |line| by_id.extract(line, options.format),
+    )
+    .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    fs::remove_dir_all(dir).unwrap();
+}
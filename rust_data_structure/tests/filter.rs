@@ -0,0 +1,98 @@
+use print_data_structs::filter::{
+    false_positive_rate, BloomFilter, CuckooFilter, FilterFull, Membership,
+};
+
+fn ids(range: std::ops::Range<u32>) -> impl Iterator<Item = Vec<u8>> {
+    range.map(|i| format!("id-{}", i).into_bytes())
+}
+
+#[test]
+fn bloom_has_no_false_negatives_and_meets_its_rate() {
+    let mut bloom = BloomFilter::with_rate(10_000, 0.01);
+    for id in ids(0..10_000) {
+        bloom.insert(&id);
+    }
+    assert!(ids(0..10_000).all(|id| bloom.contains(&id)));
+
+    let measured = false_positive_rate(&bloom, ids(10_000..60_000));
+    assert!(measured < 0.02, "measured false-positive rate {}", measured);
+    assert!((bloom.estimated_fp_rate() - 0.01).abs() < 0.005);
+    assert_eq!(bloom.hashes(), 7);
+}
+
+#[test]
+fn cuckoo_supports_deletion() {
+    let mut cuckoo = CuckooFilter::with_capacity(1_000);
+    for id in ids(0..1_000) {
+        cuckoo.insert(&id).unwrap();
+    }
+    assert_eq!(cuckoo.len(), 1_000);
+    assert!(ids(0..1_000).all(|id| cuckoo.contains(&id)));
+
+    for id in ids(0..500) {
+        assert!(cuckoo.remove(&id));
+    }
+    assert_eq!(cuckoo.len(), 500);
+    assert!(ids(500..1_000).all(|id| cuckoo.contains(&id)));
+    assert!(false_positive_rate(&cuckoo, ids(0..500)) < 0.05);
+}
+
+#[test]
+fn cuckoo_reports_when_full_without_losing_items() {
+    let mut cuckoo = CuckooFilter::with_capacity(8);
+    let mut stored = Vec::new();
+    for id in ids(0..100) {
+        match cuckoo.insert(&id) {
+            Ok(()) => stored.push(id),
+            Err(FilterFull) => break,
+        }
+    }
+    assert!(stored.len() >= cuckoo.capacity() * 3 / 4);
+    assert!(stored.iter().all(|id| cuckoo.contains(id)));
+    assert!(cuckoo.load_factor() <= 1.0 + 1.0 / cuckoo.capacity() as f64);
+}
+
+#[test]
+fn filters_round_trip_through_bytes_and_files() {
+    let mut bloom = BloomFilter::with_rate(100, 0.05);
+    let mut cuckoo = CuckooFilter::with_capacity(100);
+    for id in ids(0..100) {
+        bloom.insert(&id);
+        cuckoo.insert(&id).unwrap();
+    }
+    assert_eq!(BloomFilter::from_bytes(&bloom.to_bytes()).unwrap(), bloom);
+
+    let path = std::env::temp_dir().join(format!("cuckoo-{}.bin", std::process::id()));
+    cuckoo.save(&path).unwrap();
+    let loaded = CuckooFilter::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded, cuckoo);
+    assert!(ids(0..100).all(|id| loaded.contains(&id)));
+
+    assert!(BloomFilter::from_bytes(&cuckoo.to_bytes()).is_err());
+    let bytes = bloom.to_bytes();
+    assert!(BloomFilter::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+}
+
+#[test]
+fn oversized_headers_are_rejected_before_allocating() {
+    let mut bloom = BloomFilter::with_rate(10, 0.05).to_bytes();
+    bloom[4..12].copy_from_slice(&(1u64 << 62).to_le_bytes());
+    assert!(BloomFilter::from_bytes(&bloom).is_err());
+    bloom[4..12].copy_from_slice(&u64::MAX.to_le_bytes());
+    assert!(BloomFilter::from_bytes(&bloom).is_err());
+
+    let mut cuckoo = CuckooFilter::with_capacity(10).to_bytes();
+    cuckoo[4..12].copy_from_slice(&(1u64 << 62).to_le_bytes());
+    assert!(CuckooFilter::from_bytes(&cuckoo).is_err());
+    cuckoo[4..12].copy_from_slice(&(1u64 << 40).to_le_bytes());
+    assert!(CuckooFilter::from_bytes(&cuckoo).is_err());
+
+    let mut filter = CuckooFilter::with_capacity(10);
+    filter.insert(b"a").unwrap();
+    let mut cuckoo = filter.to_bytes();
+    assert_eq!(CuckooFilter::from_bytes(&cuckoo).unwrap().len(), 1);
+    cuckoo[12..20].copy_from_slice(&0u64.to_le_bytes());
+    let err = CuckooFilter::from_bytes(&cuckoo).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
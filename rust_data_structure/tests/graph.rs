@@ -0,0 +1,101 @@
+use print_data_structs::graph::{Graph, GraphError, Path};
+
+fn roads() -> Graph {
+    let mut graph = Graph::new(false);
+    for (from, to, km) in [
+        ("a", "b", 7),
+        ("a", "c", 9),
+        ("a", "f", 14),
+        ("b", "c", 10),
+        ("b", "d", 15),
+        ("c", "d", 11),
+        ("c", "f", 2),
+        ("d", "e", 6),
+        ("e", "f", 9),
+    ] {
+        graph.add_edge(from, to, km);
+    }
+    graph
+}
+
+#[test]
+fn traverses_in_edge_order() {
+    let graph = roads();
+    assert_eq!(graph.bfs("a").unwrap(), vec!["a", "b", "c", "f", "d", "e"]);
+    assert_eq!(graph.dfs("a").unwrap(), vec!["a", "b", "c", "d", "e", "f"]);
+    assert_eq!(graph.bfs("z"), Err(GraphError::UnknownNode("z".into())));
+}
+
+#[test]
+fn finds_cheapest_paths() {
+    let graph = roads();
+    assert_eq!(
+        graph.shortest_path("a", "e").unwrap(),
+        Some(Path {
+            nodes: vec!["a".into(), "c".into(), "f".into(), "e".into()],
+            cost: 20,
+        })
+    );
+    assert_eq!(graph.shortest_path("a", "a").unwrap().unwrap().cost, 0);
+
+    let mut island = roads();
+    island.add_node("x");
+    assert_eq!(island.shortest_path("a", "x").unwrap(), None);
+}
+
+#[test]
+fn sorts_topologically_or_reports_cycles() {
+    let mut build = Graph::new(true);
+    for (from, to) in [
+        ("fetch", "compile"),
+        ("compile", "link"),
+        ("config", "compile"),
+        ("link", "test"),
+    ] {
+        build.add_edge(from, to, 1);
+    }
+    assert_eq!(
+        build.topological_sort().unwrap(),
+        vec!["fetch", "config", "compile", "link", "test"]
+    );
+
+    build.add_edge("test", "compile", 1);
+    assert_eq!(
+        build.topological_sort(),
+        Err(GraphError::Cycle(vec![
+            "compile".into(),
+            "link".into(),
+            "test".into()
+        ]))
+    );
+    assert_eq!(roads().topological_sort(), Err(GraphError::Undirected));
+}
+
+#[test]
+fn groups_connected_components_ignoring_direction() {
+    let mut graph = Graph::new(true);
+    graph.add_edge("a", "b", 1);
+    graph.add_edge("c", "b", 1);
+    graph.add_edge("d", "e", 1);
+    graph.add_node("f");
+    assert_eq!(
+        graph.components(),
+        vec![vec!["a", "b", "c"], vec!["d", "e"], vec!["f"]]
+    );
+}
+
+#[test]
+fn loads_edge_list_csv() {
+    let text = "from,to,weight\na,b,3\nb,\"c d\",4\n\nc d,a\n";
+    let graph = Graph::from_edge_csv(text, true).unwrap();
+    assert_eq!(graph.node_count(), 3);
+    assert_eq!(graph.edge_count(), 3);
+    assert_eq!(graph.neighbors("c d").unwrap(), vec![("a", 1)]);
+    assert_eq!(graph.shortest_path("a", "c d").unwrap().unwrap().cost, 7);
+
+    let headerless = Graph::from_edge_csv("x,y\ny,z,2\n", false).unwrap();
+    assert_eq!(headerless.edge_count(), 2);
+
+    let err = Graph::from_edge_csv("a,b,1\nb,c,-2\n", true).unwrap_err();
+    assert!(err.to_string().contains("line 2"));
+}
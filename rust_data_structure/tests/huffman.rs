@@ -0,0 +1,86 @@
+use print_data_structs::huffman::{self, frequencies, Codebook};
+
+fn round_trip(data: &[u8]) -> Vec<u8> {
+    let encoded = huffman::encode(data);
+    let decoded = huffman::decode(&encoded).unwrap();
+    assert_eq!(decoded, data);
+    encoded
+}
+
+#[test]
+fn round_trips_edge_cases() {
+    round_trip(b"");
+    round_trip(b"a");
+    round_trip(&[7; 1000]);
+    round_trip(&(0..=255).collect::<Vec<u8>>());
+    let noise: Vec<u8> = (0u32..5000)
+        .map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8)
+        .collect();
+    round_trip(&noise);
+}
+
+#[test]
+fn frequent_bytes_get_shorter_codes() {
+    let text = b"abracadabra";
+    let book = Codebook::for_data(text);
+    assert_eq!(book.code(b'a').unwrap().len(), 1);
+    assert!(book.code(b'r').unwrap().len() <= book.code(b'c').unwrap().len());
+    assert_eq!(book.code(b'z'), None);
+    // 5 a's at 1 bit, and the rest fit in 3 bits each.
+    assert_eq!(book.encoded_bits(&frequencies(text)), 23);
+
+    // No code is a prefix of another.
+    let codes: Vec<String> = book
+        .lengths()
+        .iter()
+        .filter_map(|&(b, _)| book.code(b))
+        .collect();
+    for a in &codes {
+        for b in &codes {
+            assert!(a == b || !b.starts_with(a.as_str()));
+        }
+    }
+}
+
+#[test]
+fn compresses_skewed_text() {
+    let text = "the quick brown fox jumps over the lazy dog. ".repeat(200);
+    let encoded = round_trip(text.as_bytes());
+    assert!(
+        encoded.len() * 10 < text.len() * 6,
+        "{} bytes",
+        encoded.len()
+    );
+}
+
+#[test]
+fn rejects_corrupt_input() {
+    assert!(huffman::decode(b"nope").is_err());
+    let encoded = huffman::encode(b"hello, world");
+    assert!(huffman::decode(&encoded[..encoded.len() - 1]).is_err());
+    assert!(huffman::decode(&encoded[..15]).is_err());
+}
+
+#[test]
+fn compresses_and_restores_files() {
+    let dir = std::env::temp_dir();
+    let id = std::process::id();
+    let (plain, packed, restored) = (
+        dir.join(format!("huffman-{}.txt", id)),
+        dir.join(format!("huffman-{}.huf", id)),
+        dir.join(format!("huffman-{}.out", id)),
+    );
+    std::fs::write(&plain, "aaaaaaaabbbbccd".repeat(50)).unwrap();
+    let stats = huffman::compress_file(&plain, &packed).unwrap();
+    assert_eq!(stats.original, 750);
+    assert!(stats.ratio() < 0.3);
+    let back = huffman::decompress_file(&packed, &restored).unwrap();
+    assert_eq!(back, stats);
+    assert_eq!(
+        std::fs::read(&restored).unwrap(),
+        std::fs::read(&plain).unwrap()
+    );
+    for path in [plain, packed, restored] {
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,77 @@
+use print_data_structs::import::{self, Field, Format, ImportOptions};
+
+#[test]
+fn csv_column_by_name() {
+    let text = "id,amount\n1,10\n2,\n3,abc\n\n4,\"40\"\n";
+    let mut options = ImportOptions::new(Format::Csv);
+    options.column = Field::Name(String::from("amount"));
+
+    let report = import::values(text, &options);
+    assert_eq!(report.records, vec![10, 40]);
+    assert_eq!(report.loaded, 2);
+    assert_eq!(report.skipped, 2);
+    assert_eq!(report.malformed, 1);
+    assert_eq!(report.problems, vec!["line 4: invalid number 'abc'"]);
+}
+
+#[test]
+fn json_lines_field_and_pairs() {
+    let text = concat!(
+        "{\"user\": \"ann\", \"score\": 7}\n",
+        "{\"user\": \"bob\", \"score\": null}\n",
+        "{\"user\": \"cy\"\n",
+        "[1, 2]\n",
+        "{\"score\": 3, \"user\": \"dee\"}\n",
+    );
+    let mut options = ImportOptions::new(Format::JsonLines);
+    options.column = Field::Name(String::from("score"));
+    let values = import::values(text, &options);
+    assert_eq!(values.records, vec![7, 3]);
+    assert_eq!((values.skipped, values.malformed), (1, 2));
+
+    options.key = Field::Name(String::from("user"));
+    options.value = Field::Name(String::from("score"));
+    let pairs = import::pairs(text, &options);
+    assert_eq!(
+        pairs.records,
+        vec![(String::from("ann"), 7), (String::from("dee"), 3)]
+    );
+}
+
+#[test]
+fn plain_text_values_and_pairs() {
+    let options = ImportOptions::new(Format::Text);
+    let values = import::values("# ids\n5\n\n-2\nx\n", &options);
+    assert_eq!(values.records, vec![5, -2]);
+    assert_eq!((values.loaded, values.skipped, values.malformed), (2, 2, 1));
+
+    let pairs = import::pairs("a 1\nb\nc 3\n", &options);
+    assert_eq!(pairs.loaded, 2);
+    assert_eq!(pairs.malformed, 1);
+}
+
+#[test]
+fn parses_command_arguments() {
+    let (path, options) =
+        import::parse_args(&["data.csv", "--column", "price", "--no-header"]).unwrap();
+    assert_eq!(path, "data.csv");
+    assert_eq!(options.format, Format::Csv);
+    assert_eq!(options.column, Field::Name(String::from("price")));
+    assert!(!options.header);
+
+    let (_, options) = import::parse_args(&["events.log", "--format", "jsonl"]).unwrap();
+    assert_eq!(options.format, Format::JsonLines);
+
+    assert!(import::parse_args(&[]).is_err());
+    assert!(import::parse_args(&["a.txt", "--column"]).is_err());
+    assert!(import::parse_args(&["a.txt", "--bogus", "x"]).is_err());
+}
+
+#[test]
+fn splits_quoted_csv_fields() {
+    assert_eq!(
+        import::split_csv_line("a,\"b,c\",\"say \"\"hi\"\"\",").unwrap(),
+        vec!["a", "b,c", "say \"hi\"", ""]
+    );
+    assert!(import::split_csv_line("\"open").is_err());
+}
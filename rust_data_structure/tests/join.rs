@@ -0,0 +1,79 @@
+use print_data_structs::join::{self, JoinKind, JoinRow, Strategy};
+
+fn rows(pairs: &[(&str, i32)]) -> Vec<(String, i32)> {
+    pairs.iter().map(|(k, v)| (k.to_string(), *v)).collect()
+}
+
+fn left() -> Vec<(String, i32)> {
+    rows(&[("a", 1), ("b", 2), ("b", 3), ("c", 4)])
+}
+
+fn right() -> Vec<(String, i32)> {
+    rows(&[("b", 20), ("c", 40), ("c", 41), ("d", 50)])
+}
+
+fn r(key: &str, left: Option<i32>, right: Option<i32>) -> JoinRow<String, i32, i32> {
+    JoinRow {
+        key: key.to_string(),
+        left,
+        right,
+    }
+}
+
+#[test]
+fn inner_join_pairs_every_match() {
+    let result = join::hash_join(&left(), &right(), JoinKind::Inner);
+    assert_eq!(
+        result.rows,
+        vec![
+            r("b", Some(2), Some(20)),
+            r("b", Some(3), Some(20)),
+            r("c", Some(4), Some(40)),
+            r("c", Some(4), Some(41)),
+        ]
+    );
+    assert_eq!(result.matches, 4);
+    assert_eq!(result.unmatched_left, vec!["a"]);
+    assert_eq!(result.unmatched_right, vec!["d"]);
+}
+
+#[test]
+fn outer_and_anti_joins_keep_unmatched_rows() {
+    let full = join::sort_merge_join(&left(), &right(), JoinKind::Full);
+    assert_eq!(full.rows.first(), Some(&r("a", Some(1), None)));
+    assert_eq!(full.rows.last(), Some(&r("d", None, Some(50))));
+    assert_eq!(full.rows.len(), 6);
+
+    let left_join = join::hash_join(&left(), &right(), JoinKind::Left);
+    assert_eq!(left_join.rows.len(), 5);
+    let right_join = join::hash_join(&left(), &right(), JoinKind::Right);
+    assert_eq!(right_join.rows.len(), 5);
+    assert!(right_join.rows.contains(&r("d", None, Some(50))));
+
+    let anti = join::hash_join(&left(), &right(), JoinKind::Anti);
+    assert_eq!(anti.rows, vec![r("a", Some(1), None)]);
+    assert_eq!(anti.matches, 4);
+}
+
+#[test]
+fn hash_and_sort_merge_agree() {
+    for kind in JoinKind::ALL {
+        let mut hashed = join::join(&left(), &right(), kind, Strategy::Hash);
+        let merged = join::join(&left(), &right(), kind, Strategy::SortMerge);
+        hashed.rows.sort();
+        assert_eq!(hashed, merged, "{} join", kind);
+    }
+}
+
+#[test]
+fn joins_empty_sides() {
+    let empty: Vec<(String, i32)> = Vec::new();
+    let result = join::sort_merge_join(&empty, &right(), JoinKind::Full);
+    assert_eq!(result.rows.len(), 4);
+    assert_eq!(result.matches, 0);
+    assert_eq!(result.unmatched_right, vec!["b", "c", "d"]);
+    assert!(join::hash_join(&left(), &empty, JoinKind::Inner)
+        .rows
+        .is_empty());
+    assert_eq!(JoinKind::from_name("OUTER"), Some(JoinKind::Full));
+}
@@ -0,0 +1,16 @@
+use print_data_structs::json::{self, Value};
+
+#[test]
+fn parses_and_prints_round_trip() {
+    let text = r#"{"a":1,"b":[true,null,-2.5e3],"c":"x\"y\n"}"#;
+    let value = json::parse(text).unwrap();
+    assert_eq!(value.get("a"), Some(&Value::Number(String::from("1"))));
+    assert_eq!(value.to_string(), text);
+}
+
+#[test]
+fn reports_errors_with_columns() {
+    assert!(json::parse("{\"a\" 1}").unwrap_err().contains("column 6"));
+    assert!(json::parse("[1, 2").is_err());
+    assert!(json::parse("1 2").is_err());
+}
@@ -0,0 +1,113 @@
+use print_data_structs::knn::{self, brute_force, KdTree, Metric, Point};
+use print_data_structs::sim::Rng;
+
+fn cloud(n: usize, dims: usize, seed: u64) -> Vec<Point> {
+    let mut rng = Rng::new(seed);
+    (0..n)
+        .map(|i| {
+            // Coarse grid values so that distance ties actually happen.
+            let coords = (0..dims).map(|_| rng.range(0, 20) as f64).collect();
+            Point::new(format!("p{}", i), coords)
+        })
+        .collect()
+}
+
+#[test]
+fn metrics_measure_expected_distances() {
+    let (a, b) = ([0.0, 0.0], [3.0, 4.0]);
+    assert_eq!(Metric::Euclidean.distance(&a, &b), 5.0);
+    assert_eq!(Metric::Manhattan.distance(&a, &b), 7.0);
+    assert!(Metric::Cosine.distance(&[1.0, 1.0], &[2.0, 2.0]).abs() < 1e-12);
+    assert!((Metric::Cosine.distance(&[1.0, 0.0], &[-1.0, 0.0]) - 2.0).abs() < 1e-12);
+    assert_eq!(Metric::from_name("l1"), Some(Metric::Manhattan));
+}
+
+#[test]
+fn brute_force_returns_nearest_first() {
+    let points = vec![
+        Point::new("far", vec![10.0, 10.0]),
+        Point::new("near", vec![1.0, 1.0]),
+        Point::new("mid", vec![3.0, 3.0]),
+        Point::new("tie", vec![-1.0, -1.0]),
+    ];
+    let found = brute_force(&points, &[0.0, 0.0], 3, Metric::Euclidean);
+    let labels: Vec<&str> = found
+        .iter()
+        .map(|n| points[n.index].label.as_str())
+        .collect();
+    assert_eq!(labels, vec!["near", "tie", "mid"]);
+    assert_eq!(
+        brute_force(&points, &[0.0, 0.0], 10, Metric::Euclidean).len(),
+        4
+    );
+    assert!(brute_force(&points, &[0.0, 0.0], 0, Metric::Euclidean).is_empty());
+}
+
+#[test]
+fn kd_tree_matches_brute_force() {
+    for dims in 1..=4 {
+        let points = cloud(500, dims, dims as u64);
+        let tree = KdTree::new(points.clone());
+        let queries = cloud(50, dims, 100 + dims as u64);
+        for metric in [Metric::Euclidean, Metric::Manhattan, Metric::Cosine] {
+            for query in &queries {
+                for k in [1, 5, 17] {
+                    assert_eq!(
+                        tree.nearest(&query.coords, k, metric),
+                        brute_force(&points, &query.coords, k, metric),
+                        "dims {} metric {} k {}",
+                        dims,
+                        metric,
+                        k
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn kd_tree_handles_small_and_empty_sets() {
+    let empty = KdTree::new(Vec::new());
+    assert!(empty.is_empty());
+    assert!(empty.nearest(&[1.0], 3, Metric::Euclidean).is_empty());
+
+    let single = KdTree::new(vec![Point::new("only", vec![2.0, 2.0])]);
+    let found = single.nearest(&[0.0, 0.0], 5, Metric::Manhattan);
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].distance, 4.0);
+    assert_eq!(
+        single
+            .nearest(&[0.0, 0.0], usize::MAX, Metric::Euclidean)
+            .len(),
+        1
+    );
+    assert_eq!(
+        brute_force(single.points(), &[0.0, 0.0], usize::MAX, Metric::Cosine).len(),
+        1
+    );
+
+    for metric in [Metric::Euclidean, Metric::Cosine] {
+        assert!(std::panic::catch_unwind(|| single.nearest(&[0.0], 1, metric)).is_err());
+        assert!(std::panic::catch_unwind(|| {
+            brute_force(single.points(), &[0.0, 0.0, 0.0], 1, metric)
+        })
+        .is_err());
+    }
+}
+
+#[test]
+fn parses_points_and_queries() {
+    let text = "name,x,y\nhome,0,0\nwork,\"3\",4\n\nshop,1,1\n";
+    let points = knn::parse_points(text).unwrap();
+    assert_eq!(points.len(), 3);
+    assert_eq!(points[1], Point::new("work", vec![3.0, 4.0]));
+
+    let unlabelled = knn::parse_points("1,2\n3,4\n").unwrap();
+    assert_eq!(unlabelled[1].label, "2");
+
+    let err = knn::parse_points("a,1,2\nb,1\n").unwrap_err();
+    assert!(err.to_string().contains("line 2"));
+    assert_eq!(knn::parse_query("1.5, -2").unwrap(), vec![1.5, -2.0]);
+    assert!(knn::parse_query("1,x").is_err());
+}
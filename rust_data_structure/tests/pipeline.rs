@@ -0,0 +1,116 @@
+use print_data_structs::pipeline::{Output, Pipeline, PipelineError, Reducer, Sink};
+use print_data_structs::predicate::EvalError;
+
+fn run(source: &str, values: impl IntoIterator<Item = i32>) -> Output {
+    Pipeline::parse(source)
+        .unwrap()
+        .run(values.into_iter().collect())
+        .unwrap()
+}
+
+#[test]
+fn maps_filters_and_reduces() {
+    assert_eq!(
+        run("map x * 2 | filter x > 10 | sum", 1..=8),
+        Output::Scalar(Some(12 + 14 + 16))
+    );
+    assert_eq!(
+        run("filter x % 2 == 1 | count", 1..=9),
+        Output::Scalar(Some(5))
+    );
+    assert_eq!(run("mean", [1, 2, 4]), Output::Scalar(Some(2)));
+    assert_eq!(
+        run("mean", [i32::MAX, i32::MAX]),
+        Output::Scalar(Some(i32::MAX))
+    );
+    assert_eq!(
+        run("mean", [i32::MIN, i32::MIN, 1]),
+        Output::Scalar(Some(-1_431_655_765))
+    );
+    assert_eq!(run("fold max -100", [3, -7, 5]), Output::Scalar(Some(5)));
+    assert_eq!(run("filter x > 100 | min", 1..5), Output::Scalar(None));
+}
+
+#[test]
+fn transforms_without_reducing() {
+    assert_eq!(
+        run("map x - 1 | sort | reverse | skip 1 | take 2", [4, 9, 1, 7]),
+        Output::Values(vec![6, 3])
+    );
+    let pipeline = Pipeline::parse("filter x > 2 | store").unwrap();
+    assert!(pipeline.stores());
+    assert_eq!(pipeline.sink(), Sink::Store);
+    assert_eq!(
+        Pipeline::parse("product").unwrap().sink(),
+        Sink::Reduce(Reducer::Product)
+    );
+}
+
+#[test]
+fn maps_entries_by_key_and_value() {
+    let pipeline = Pipeline::parse("filter key startswith \"a\" | map value * 10").unwrap();
+    let entries = vec![
+        (String::from("apple"), 1),
+        (String::from("berry"), 2),
+        (String::from("avocado"), 3),
+    ];
+    assert_eq!(
+        pipeline.run_entries(entries).unwrap(),
+        Output::Entries(vec![
+            (String::from("apple"), 10),
+            (String::from("avocado"), 30)
+        ])
+    );
+}
+
+#[test]
+fn reports_arithmetic_errors_with_their_stage() {
+    let err = Pipeline::parse("map x * 2").unwrap().run(vec![1, i32::MAX]);
+    assert_eq!(
+        err,
+        Err(PipelineError::Eval {
+            stage: 1,
+            text: String::from("map x * 2"),
+            error: EvalError::Overflow,
+            value: i32::MAX,
+        })
+    );
+    let err = Pipeline::parse("map x + 1 | fold / 100")
+        .unwrap()
+        .run(vec![-1]);
+    assert!(matches!(
+        err,
+        Err(PipelineError::Eval {
+            stage: 2,
+            error: EvalError::DivideByZero,
+            ..
+        })
+    ));
+    assert!(matches!(
+        Pipeline::parse("map 1 | sum")
+            .unwrap()
+            .run(vec![i32::MAX, 0]),
+        Ok(Output::Scalar(Some(2)))
+    ));
+}
+
+#[test]
+fn rejects_malformed_pipelines() {
+    assert_eq!(Pipeline::parse(" | "), Err(PipelineError::Empty));
+    for (source, stage) in [
+        ("map x > 1", 1),
+        ("filter x + 1", 1),
+        ("map x | frobnicate", 2),
+        ("sum | map x", 2),
+        ("take many", 1),
+        ("fold % 0", 1),
+        ("map x ||", 2),
+    ] {
+        match Pipeline::parse(source) {
+            Err(PipelineError::Parse { stage: found, .. }) => {
+                assert_eq!(found, stage, "{}", source)
+            }
+            other => panic!("{}: expected a parse error, got {:?}", source, other),
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use print_data_structs::predicate::{CompareOp, EvalError, Expr, Predicate, Var};
+
+fn keep(source: &str, values: impl IntoIterator<Item = i32>) -> Vec<i32> {
+    let predicate = Predicate::parse(source).unwrap();
+    values
+        .into_iter()
+        .filter(|&x| predicate.matches(x).unwrap())
+        .collect()
+}
+
+#[test]
+fn parses_with_and_without_variable() {
+    assert_eq!(
+        Predicate::parse("x > 10").unwrap().expr(),
+        &Expr::Compare(
+            CompareOp::Gt,
+            Box::new(Expr::Var(Var::Value)),
+            Box::new(Expr::Int(10))
+        )
+    );
+    assert!(matches!(
+        Predicate::parse("<= -3").unwrap().expr(),
+        Expr::Compare(CompareOp::Le, _, _)
+    ));
+}
+
+#[test]
+fn evaluates_comparisons() {
+    assert_eq!(keep("x != 4", 1..=5), vec![1, 2, 3, 5]);
+    assert_eq!(keep("x == 7", [6, 7]), vec![7]);
+}
+
+#[test]
+fn combines_arithmetic_and_logic() {
+    assert_eq!(keep("x > 10 and x % 2 == 0", 8..=15), vec![12, 14]);
+    assert_eq!(keep("value between 5 and 9", 3..=11), vec![5, 6, 7, 8, 9]);
+    assert_eq!(keep("not (x == 0 or x == 1) and -x < -1", 0..4), vec![2, 3]);
+    assert_eq!(keep("(x + 1) * 2 >= 8", 1..5), vec![3, 4]);
+}
+
+#[test]
+fn matches_map_keys() {
+    let predicate = Predicate::parse("key startswith \"user_\" and value > 0").unwrap();
+    assert!(predicate.uses_key());
+    assert_eq!(predicate.matches_entry("user_1", 3), Ok(true));
+    assert_eq!(predicate.matches_entry("admin", 3), Ok(false));
+    assert_eq!(predicate.matches(3), Err(EvalError::MissingKey));
+
+    let predicate = Predicate::parse("key contains \"a\" or key endswith \"z\"").unwrap();
+    assert_eq!(predicate.matches_entry("buzz", 0), Ok(true));
+}
+
+#[test]
+fn reports_arithmetic_errors() {
+    let predicate = Predicate::parse("10 / x > 1").unwrap();
+    assert_eq!(predicate.matches(0), Err(EvalError::DivideByZero));
+    let predicate = Predicate::parse("x * 2 > 0").unwrap();
+    assert_eq!(predicate.matches(i32::MAX), Err(EvalError::Overflow));
+}
+
+#[test]
+fn rejects_bad_input_with_positions() {
+    let err = Predicate::parse("x >").unwrap_err();
+    assert_eq!(err.column, 4);
+    assert!(err.message.contains("unexpected end"));
+
+    let err = Predicate::parse("x ~ 3").unwrap_err();
+    assert_eq!(err.column, 3);
+    assert!(err.message.contains("unexpected character"));
+    assert_eq!(err.pointer("x ~ 3"), "x ~ 3\n  ^");
+
+    assert_eq!(keep("x > -2147483648", [i32::MIN, 0]), vec![0]);
+    assert_eq!(keep("x == -(2147483647)", [-i32::MAX]), vec![-i32::MAX]);
+    let err = Predicate::parse("x > 2147483648").unwrap_err();
+    assert_eq!(
+        (err.column, err.message.contains("out of range")),
+        (5, true)
+    );
+    assert!(Predicate::parse("x > -2147483649").is_err());
+
+    let deep = format!("{}x > 1{}", "(".repeat(100_000), ")".repeat(100_000));
+    let err = Predicate::parse(&deep).unwrap_err();
+    assert!(err.message.contains("nested more than"), "{}", err);
+    assert!(Predicate::parse(&format!("{}x == 1", "not ".repeat(100_000))).is_err());
+    assert!(Predicate::parse(&format!("x == {}1", "- ".repeat(100_000))).is_err());
+    let fine = format!("{}x > 1{}", "(".repeat(60), ")".repeat(60));
+    assert!(Predicate::parse(&fine).is_ok());
+
+    let err = Predicate::parse("x > ten").unwrap_err();
+    assert!(err.message.contains("unknown name 'ten'"));
+    assert_eq!(err.column, 5);
+}
+
+#[test]
+fn rejects_type_errors() {
+    let err = Predicate::parse("key startswith 5").unwrap_err();
+    assert_eq!(err.column, 16);
+    assert!(err.message.contains("needs a string"));
+
+    assert!(Predicate::parse("x + 1")
+        .unwrap_err()
+        .message
+        .contains("expected a condition"));
+    assert!(Predicate::parse("x > 1 and 2").is_err());
+    assert!(Predicate::parse("key > 3")
+        .unwrap_err()
+        .message
+        .contains("cannot compare"));
+    assert!(Predicate::parse("x between 1 or 2")
+        .unwrap_err()
+        .message
+        .contains("expected 'and'"));
+    assert!(Predicate::parse("(x > 1")
+        .unwrap_err()
+        .message
+        .contains("expected ')'"));
+    assert!(Predicate::parse("x > 1 x")
+        .unwrap_err()
+        .message
+        .contains("unexpected"));
+}
@@ -0,0 +1,62 @@
+use print_data_structs::ring::{Policy, Push, RingBuffer};
+
+fn contents(ring: &RingBuffer) -> Vec<i32> {
+    ring.iter().copied().collect()
+}
+
+#[test]
+fn overwrites_oldest_when_full() {
+    let mut ring = RingBuffer::new(3, Policy::Overwrite);
+    assert_eq!(ring.push(1), Push::Stored);
+    ring.push(2);
+    ring.push(3);
+    assert!(ring.is_full());
+    assert_eq!(ring.push(4), Push::Evicted(1));
+    assert_eq!(contents(&ring), vec![2, 3, 4]);
+}
+
+#[test]
+fn rejects_newest_when_full() {
+    let mut ring = RingBuffer::new(2, Policy::Reject);
+    ring.push(1);
+    ring.push(2);
+    assert_eq!(ring.push(3), Push::Rejected);
+    assert_eq!(contents(&ring), vec![1, 2]);
+    ring.set_policy(Policy::Overwrite);
+    assert_eq!(ring.push(3), Push::Evicted(1));
+}
+
+#[test]
+fn keeps_aggregates_in_step_with_the_window() {
+    let values = [5, 1, 4, 1, 9, 2, 6, 5, 3, 5, -8, 9, 7, 9];
+    let mut ring = RingBuffer::new(4, Policy::Overwrite);
+    for (i, &v) in values.iter().enumerate() {
+        ring.push(v);
+        let window = &values[i.saturating_sub(3)..=i];
+        assert_eq!(
+            ring.sum(),
+            window.iter().map(|&x| i64::from(x)).sum::<i64>()
+        );
+        assert_eq!(ring.min(), window.iter().copied().min());
+        assert_eq!(ring.max(), window.iter().copied().max());
+    }
+    assert_eq!(ring.mean(), Some((-8 + 9 + 7 + 9) as f64 / 4.0));
+
+    ring.pop_oldest();
+    assert_eq!(ring.min(), Some(7));
+    ring.clear();
+    assert_eq!((ring.sum(), ring.mean(), ring.max()), (0, None, None));
+}
+
+#[test]
+fn shrinking_evicts_oldest() {
+    let mut ring = RingBuffer::new(5, Policy::Reject);
+    for v in [10, 20, 30, 40] {
+        ring.push(v);
+    }
+    assert_eq!(ring.set_capacity(2), vec![10, 20]);
+    assert_eq!(contents(&ring), vec![30, 40]);
+    assert_eq!(ring.min(), Some(30));
+    assert_eq!(ring.sum(), 70);
+    assert_eq!(Policy::from_name("reject-newest"), Some(Policy::Reject));
+}
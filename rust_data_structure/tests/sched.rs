@@ -0,0 +1,224 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Barrier, Mutex};
+use std::time::Duration;
+
+use print_data_structs::sched::{self, RetryPolicy, ScheduleError, Scheduler, Status, Task};
+
+fn recorder() -> (Arc<Mutex<Vec<String>>>, impl Fn(&str) -> Task) {
+    let ran = Arc::new(Mutex::new(Vec::new()));
+    let handle = Arc::clone(&ran);
+    let task = move |name: &str| {
+        let ran = Arc::clone(&handle);
+        let label = name.to_string();
+        Task::closure(name, move |_| {
+            ran.lock().unwrap().push(label.clone());
+            Ok(())
+        })
+    };
+    (ran, task)
+}
+
+#[test]
+fn runs_ready_tasks_by_priority_after_dependencies() {
+    let (ran, task) = recorder();
+    let mut scheduler = Scheduler::new(1);
+    scheduler.add(task("extract")).unwrap();
+    scheduler
+        .add(task("report").after("load").priority(9))
+        .unwrap();
+    scheduler.add(task("cleanup").priority(-1)).unwrap();
+    scheduler.add(task("load").after("extract")).unwrap();
+    scheduler.add(task("audit").priority(5)).unwrap();
+
+    let report = scheduler.run().unwrap();
+    assert!(report.succeeded());
+    let expected = vec!["audit", "extract", "load", "report", "cleanup"];
+    assert_eq!(*ran.lock().unwrap(), expected);
+    assert_eq!(report.start_order(), expected);
+    assert_eq!(report.log.len(), 5);
+}
+
+#[test]
+fn retries_then_skips_dependents_of_failures() {
+    let flaky_calls = Arc::new(AtomicU32::new(0));
+    let calls = Arc::clone(&flaky_calls);
+    let mut scheduler = Scheduler::new(2);
+    scheduler
+        .add(
+            Task::closure("flaky", move |attempt| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                if attempt < 3 {
+                    Err(format!("attempt {} timed out", attempt))
+                } else {
+                    Ok(())
+                }
+            })
+            .retry(3, Duration::from_millis(1)),
+        )
+        .unwrap();
+    scheduler
+        .add(Task::closure("broken", |_| Err("disk full".into())).retry(2, Duration::ZERO))
+        .unwrap();
+    scheduler
+        .add(
+            Task::closure("publish", |_| Ok(()))
+                .after("flaky")
+                .after("broken"),
+        )
+        .unwrap();
+    scheduler
+        .add(Task::closure("notify", |_| Ok(())).after("publish"))
+        .unwrap();
+
+    let report = scheduler.run().unwrap();
+    assert_eq!(flaky_calls.load(Ordering::SeqCst), 3);
+    assert_eq!(
+        report.status("flaky"),
+        Some(&Status::Succeeded { attempts: 3 })
+    );
+    assert_eq!(
+        report.status("broken"),
+        Some(&Status::Failed {
+            attempts: 2,
+            error: "disk full".into()
+        })
+    );
+    assert_eq!(
+        report.status("notify"),
+        Some(&Status::Skipped {
+            dependency: "publish".into()
+        })
+    );
+    assert!(matches!(
+        report.status("publish"),
+        Some(Status::Skipped { .. })
+    ));
+    assert!(!report.log.iter().any(|e| e.task == "publish"));
+    assert!(!report.succeeded());
+}
+
+#[test]
+fn rejects_cycles_unknown_dependencies_and_duplicates() {
+    let mut scheduler = Scheduler::new(2);
+    scheduler.add(Task::shell("a", "true").after("c")).unwrap();
+    scheduler.add(Task::shell("b", "true").after("a")).unwrap();
+    scheduler.add(Task::shell("c", "true").after("b")).unwrap();
+    scheduler.add(Task::shell("d", "true")).unwrap();
+    assert_eq!(
+        scheduler.run(),
+        Err(ScheduleError::Cycle(vec![
+            "a".into(),
+            "b".into(),
+            "c".into()
+        ]))
+    );
+    assert_eq!(
+        scheduler.add(Task::shell("d", "false")),
+        Err(ScheduleError::Duplicate("d".into()))
+    );
+
+    let mut missing = Scheduler::new(1);
+    missing
+        .add(Task::shell("a", "true").after("ghost"))
+        .unwrap();
+    assert!(matches!(
+        missing.validate(),
+        Err(ScheduleError::UnknownDependency { .. })
+    ));
+}
+
+#[test]
+fn workers_run_independent_tasks_in_parallel() {
+    // Each task waits for the other three, so they can only finish if all
+    // four run at once.
+    let barrier = Arc::new(Barrier::new(4));
+    let mut scheduler = Scheduler::new(4);
+    for i in 0..4 {
+        let barrier = Arc::clone(&barrier);
+        scheduler
+            .add(Task::closure(format!("wait{}", i), move |_| {
+                barrier.wait();
+                Ok(())
+            }))
+            .unwrap();
+    }
+    let report = scheduler.run().unwrap();
+    let mut workers: Vec<usize> = report.log.iter().map(|e| e.worker).collect();
+    workers.sort();
+    assert_eq!(workers, vec![0, 1, 2, 3]);
+    let last_start = report.log.iter().map(|e| e.started).max().unwrap();
+    let first_end = report.log.iter().map(|e| e.finished).min().unwrap();
+    assert!(last_start <= first_end, "{:?}", report.log);
+}
+
+#[test]
+fn a_panicking_task_fails_instead_of_hanging() {
+    let mut scheduler = Scheduler::new(2);
+    scheduler
+        .add(Task::closure("boom", |_| panic!("kaboom")).retry(2, Duration::ZERO))
+        .unwrap();
+    scheduler
+        .add(Task::closure("after", |_| Ok(())).after("boom"))
+        .unwrap();
+    scheduler.add(Task::closure("other", |_| Ok(()))).unwrap();
+    let report = scheduler.run().unwrap();
+    assert_eq!(
+        report.status("boom"),
+        Some(&Status::Failed {
+            attempts: 2,
+            error: "panicked: kaboom".to_string()
+        })
+    );
+    assert_eq!(
+        report.status("after"),
+        Some(&Status::Skipped {
+            dependency: "boom".to_string()
+        })
+    );
+    assert_eq!(
+        report.status("other"),
+        Some(&Status::Succeeded { attempts: 1 })
+    );
+
+    let mut task = Task::closure("zero", |_| Err("no".to_string()));
+    task.retry = RetryPolicy {
+        max_attempts: 0,
+        backoff: Duration::ZERO,
+    };
+    let mut scheduler = Scheduler::new(1);
+    scheduler.add(task).unwrap();
+    let report = scheduler.run().unwrap();
+    assert_eq!(
+        report.status("zero"),
+        Some(&Status::Failed {
+            attempts: 1,
+            error: "no".to_string()
+        })
+    );
+}
+
+#[test]
+fn runs_shell_jobs_from_a_job_file() {
+    let text = "# nightly\nprepare: true\nbuild priority=2 after=prepare: exit 0\n\
+                fail retries=1 backoff=1: echo boom >&2; exit 3\n";
+    let tasks = sched::parse_jobs(text).unwrap();
+    assert_eq!(tasks[1].dependencies, vec!["prepare"]);
+    assert_eq!(tasks[2].retry.max_attempts, 2);
+
+    let mut scheduler = Scheduler::new(2);
+    for task in tasks {
+        scheduler.add(task).unwrap();
+    }
+    let report = scheduler.run().unwrap();
+    assert_eq!(
+        report.status("build"),
+        Some(&Status::Succeeded { attempts: 1 })
+    );
+    match report.status("fail") {
+        Some(Status::Failed { attempts: 2, error }) => assert!(error.contains("boom")),
+        other => panic!("unexpected status {:?}", other),
+    }
+    assert!(sched::parse_jobs("no colon here").is_err());
+    assert!(sched::parse_jobs("x bogus=1: true").is_err());
+    assert!(sched::parse_jobs("x retries=18446744073709551615: true").is_err());
+}
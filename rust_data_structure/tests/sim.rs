@@ -0,0 +1,97 @@
+use print_data_structs::sim::{QueueModel, Rng, Simulation};
+
+#[test]
+fn events_run_in_time_order_with_fifo_ties() {
+    let mut sim = Simulation::new();
+    sim.schedule_at(5, "c");
+    sim.schedule_at(1, "a");
+    sim.schedule_at(5, "d");
+    sim.schedule_at(3, "b");
+    let mut seen = Vec::new();
+    sim.run(|sim, event| seen.push((sim.now(), event)));
+    assert_eq!(seen, vec![(1, "a"), (3, "b"), (5, "c"), (5, "d")]);
+    assert_eq!(sim.processed(), 4);
+    assert_eq!(sim.pending(), 0);
+}
+
+#[test]
+fn handlers_schedule_follow_up_events() {
+    let mut sim = Simulation::new();
+    sim.schedule_at(0, 1u32);
+    let mut fired = Vec::new();
+    sim.run_until(20, |sim, n| {
+        fired.push((sim.now(), n));
+        sim.schedule_in(u64::from(n) * 2, n + 1);
+        if n == 1 {
+            // Scheduling "in the past" happens now, after queued ties.
+            sim.schedule_at(0, 100);
+        }
+    });
+    assert_eq!(fired[..4], [(0, 1), (0, 100), (2, 2), (6, 3)]);
+    assert!(fired.iter().all(|&(t, _)| t <= 20));
+    assert!(sim.peek_time().unwrap() > 20);
+}
+
+#[test]
+fn rng_is_reproducible_and_in_range() {
+    let mut a = Rng::new(7);
+    let mut b = Rng::new(7);
+    let first: Vec<u64> = (0..10).map(|_| a.next_u64()).collect();
+    assert_eq!(first, (0..10).map(|_| b.next_u64()).collect::<Vec<_>>());
+    assert_ne!(first[0], Rng::new(8).next_u64());
+
+    let mut rng = Rng::new(1);
+    assert!((0..1000).all(|_| (10..20).contains(&rng.range(10, 20))));
+    let mean = (0..20_000).map(|_| rng.exponential(50.0)).sum::<u64>() as f64 / 20_000.0;
+    assert!((mean - 50.0).abs() < 2.0, "mean {}", mean);
+}
+
+#[test]
+fn queue_model_is_deterministic_per_seed() {
+    let model = QueueModel::default();
+    let report = model.run();
+    assert_eq!(report, model.run());
+    assert_eq!(report.served, model.customers);
+    assert!(report.median_wait <= report.p95_wait && report.p95_wait <= report.max_wait);
+    assert!(report.utilization > 0.0 && report.utilization <= 1.0);
+
+    let other = QueueModel { seed: 43, ..model }.run();
+    assert_ne!(report.mean_wait, other.mean_wait);
+}
+
+#[test]
+fn more_servers_shorten_waits() {
+    let busy = QueueModel {
+        servers: 1,
+        mean_interarrival: 10.0,
+        mean_service: 9.0,
+        customers: 2000,
+        seed: 3,
+    };
+    let relaxed = QueueModel {
+        servers: 3,
+        ..busy.clone()
+    };
+    let (busy, relaxed) = (busy.run(), relaxed.run());
+    assert!(relaxed.mean_wait < busy.mean_wait / 5.0);
+    assert!(relaxed.max_queue < busy.max_queue);
+    assert!(busy.utilization > 0.8);
+}
+
+#[test]
+fn huge_service_times_saturate_instead_of_overflowing() {
+    for mean_service in [1e30, f64::INFINITY] {
+        let report = QueueModel {
+            servers: 1,
+            mean_service,
+            customers: 5,
+            ..QueueModel::default()
+        }
+        .run();
+        assert_eq!(report.served, 5);
+        assert_eq!(report.end_time, u64::MAX);
+    }
+    let mut rng = Rng::new(1);
+    assert_eq!(rng.exponential(f64::INFINITY), u64::MAX);
+    assert_eq!(rng.exponential(f64::NAN), 0);
+}
@@ -0,0 +1,67 @@
+use print_data_structs::stream::{merge_sorted, Record, StreamExt};
+
+#[test]
+fn batches_and_slides() {
+    let batches: Vec<Vec<i32>> = (1..=7).batches(3).collect();
+    assert_eq!(batches, vec![vec![1, 2, 3], vec![4, 5, 6], vec![7]]);
+
+    let windows: Vec<Vec<i32>> = (1..=4).sliding(3).collect();
+    assert_eq!(windows, vec![vec![1, 2, 3], vec![2, 3, 4]]);
+    assert_eq!((1..=2).sliding(3).count(), 0);
+}
+
+#[test]
+fn dedups_within_bounded_memory() {
+    let keys = ["a", "b", "a", "c", "b", "d", "a"];
+    let all: Vec<&str> = keys
+        .iter()
+        .copied()
+        .dedup_by_key_bounded(10, |k| *k)
+        .collect();
+    assert_eq!(all, vec!["a", "b", "c", "d"]);
+
+    // With room for two keys, "a" is forgotten once "b" and "c" arrive.
+    let bounded: Vec<&str> = keys
+        .iter()
+        .copied()
+        .dedup_by_key_bounded(2, |k| *k)
+        .collect();
+    assert_eq!(bounded, vec!["a", "b", "c", "d", "a"]);
+}
+
+#[test]
+fn merges_sorted_streams_in_order() {
+    let merged: Vec<Record> = merge_sorted(
+        vec![
+            vec![Record::new("x", 1, 10), Record::new("x", 4, 40)].into_iter(),
+            vec![].into_iter(),
+            vec![Record::new("y", 2, 10), Record::new("y", 3, 30)].into_iter(),
+        ],
+        |r| r.time,
+    )
+    .collect();
+    let values: Vec<i32> = merged.iter().map(|r| r.value).collect();
+    assert_eq!(values, vec![1, 2, 3, 4]);
+}
+
+#[test]
+fn samples_at_most_limit_per_window() {
+    let records = (0..10).map(|t| Record::new("k", t as i32, t * 250));
+    let sampled: Vec<u64> = records
+        .sample_rate(2, 1_000, |r| r.time)
+        .map(|r| r.time)
+        .collect();
+    assert_eq!(sampled, vec![0, 250, 1_000, 1_250, 2_000, 2_250]);
+}
+
+#[test]
+fn adapters_are_lazy() {
+    let mut pulled = 0;
+    let first = (0..)
+        .inspect(|_| pulled += 1)
+        .sample_rate(1, 5, |&x| x)
+        .batches(2)
+        .next();
+    assert_eq!(first, Some(vec![0, 5]));
+    assert_eq!(pulled, 6);
+}
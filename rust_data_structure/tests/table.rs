@@ -0,0 +1,180 @@
+use print_data_structs::table::{DataType, Decimal, SortKey, Table, TableError, Value};
+
+fn orders() -> Table {
+    let mut table = Table::new(&[
+        ("id", DataType::Int),
+        ("customer", DataType::Str),
+        ("total", DataType::Decimal { scale: 2 }),
+        ("weight", DataType::Float),
+        ("paid", DataType::Bool),
+    ])
+    .unwrap();
+    let dec = |s| Value::Decimal(Decimal::parse(s, 2).unwrap());
+    for row in [
+        vec![
+            Value::Int(1),
+            Value::Str("ann".into()),
+            dec("19.99"),
+            Value::Float(1.5),
+            Value::Bool(true),
+        ],
+        vec![
+            Value::Int(2),
+            Value::Str("bob".into()),
+            dec("5"),
+            Value::Null,
+            Value::Bool(false),
+        ],
+        vec![
+            Value::Int(3),
+            Value::Str("ann".into()),
+            dec("120.5"),
+            Value::Float(0.25),
+            Value::Null,
+        ],
+        vec![
+            Value::Int(4),
+            Value::Null,
+            dec("5.00"),
+            Value::Int(2),
+            Value::Bool(true),
+        ],
+    ] {
+        table.push_row(row).unwrap();
+    }
+    table
+}
+
+fn ids(table: &Table) -> Vec<i64> {
+    table
+        .rows()
+        .map(|r| r.get("id").as_int().unwrap())
+        .collect()
+}
+
+#[test]
+fn stores_typed_columns_with_nulls() {
+    let table = orders();
+    assert_eq!((table.num_rows(), table.num_columns()), (4, 5));
+    assert_eq!(
+        table.schema().to_string(),
+        "id: int, customer: string?, total: decimal(2), weight: float?, paid: bool?"
+    );
+    let weight = table.column("weight").unwrap();
+    assert_eq!(weight.null_count(), 1);
+    assert!(weight.is_null(1));
+    assert_eq!(weight.get(3), Some(Value::Float(2.0)));
+    assert_eq!(weight.get(4), None);
+    assert_eq!(table.row(2).unwrap()[2].to_string(), "120.50");
+    assert_eq!(table.row(9), None);
+
+    let rendered = table.to_string();
+    assert_eq!(rendered.lines().count(), 6);
+    assert!(rendered.starts_with("id | customer | total  | weight | paid"));
+    assert!(rendered.lines().nth(3).unwrap().contains("| null   |"));
+}
+
+#[test]
+fn rejects_bad_rows_without_partial_writes() {
+    let mut table = orders();
+    let err = table
+        .push_row(vec![
+            Value::Int(5),
+            Value::Str("cy".into()),
+            Value::Int(3),
+            Value::Null,
+            Value::Null,
+        ])
+        .unwrap_err();
+    assert_eq!(
+        err,
+        TableError::TypeMismatch {
+            column: "total".into(),
+            expected: DataType::Decimal { scale: 2 },
+            found: DataType::Int
+        }
+    );
+    assert_eq!(
+        table.push_row(vec![Value::Null]),
+        Err(TableError::RowLength {
+            expected: 5,
+            found: 1
+        })
+    );
+    assert_eq!(table.num_rows(), 4);
+    assert!(table.columns().iter().all(|c| c.len() == 4));
+    assert!(matches!(
+        Table::new(&[("a", DataType::Int), ("a", DataType::Bool)]),
+        Err(TableError::DuplicateColumn(_))
+    ));
+}
+
+#[test]
+fn projects_and_filters() {
+    let table = orders();
+    let slim = table.project(&["total", "id"]).unwrap();
+    assert_eq!(slim.schema().fields[0].name, "total");
+    assert_eq!(slim.num_rows(), 4);
+    assert_eq!(
+        table.project(&["nope"]),
+        Err(TableError::UnknownColumn("nope".into()))
+    );
+
+    let ann = table.filter(|row| row.get("customer").as_str() == Some("ann"));
+    assert_eq!(ids(&ann), vec![1, 3]);
+    let cheap = table.filter(|row| row.get("total").as_decimal() < Decimal::parse("10", 0));
+    assert_eq!(ids(&cheap), vec![2, 4]);
+    assert_eq!(table.filter(|_| false).num_rows(), 0);
+}
+
+#[test]
+fn sorts_by_several_keys_with_nulls_last() {
+    let table = orders();
+    let by_total = table
+        .sort_by(&[SortKey::asc("total"), SortKey::desc("id")])
+        .unwrap();
+    assert_eq!(ids(&by_total), vec![4, 2, 1, 3]);
+
+    let by_customer = table
+        .sort_by(&[SortKey::desc("customer"), SortKey::asc("weight")])
+        .unwrap();
+    assert_eq!(ids(&by_customer), vec![2, 3, 1, 4]);
+
+    let by_weight = table.sort_by(&[SortKey::desc("weight")]).unwrap();
+    assert_eq!(ids(&by_weight), vec![4, 1, 3, 2]);
+    assert!(table.sort_by(&[SortKey::asc("missing")]).is_err());
+}
+
+#[test]
+fn decimals_parse_round_and_compare_exactly() {
+    assert_eq!(Decimal::parse("-1.005", 2), Some(Decimal::new(-101, 2)));
+    assert_eq!(Decimal::parse(".5", 0), Some(Decimal::new(1, 0)));
+    assert_eq!(Decimal::parse("1.2.3", 2), None);
+    assert_eq!(Decimal::parse("-", 2), None);
+    assert_eq!(Decimal::new(150, 2).rescale(1), Some(Decimal::new(15, 1)));
+    assert_eq!(Decimal::new(-5, 3).to_string(), "-0.005");
+    assert_eq!(Decimal::new(10, 1), Decimal::new(100, 2));
+    assert!(Decimal::new(10, 1) < Decimal::new(101, 2));
+    assert_eq!(
+        Decimal::new(100, 2).cmp(&Decimal::new(1, 0)),
+        std::cmp::Ordering::Equal
+    );
+}
+
+#[test]
+fn rejects_decimal_scales_above_the_maximum() {
+    let too_fine = Decimal::MAX_SCALE + 1;
+    assert_eq!(
+        Table::new(&[("x", DataType::Decimal { scale: too_fine })]).unwrap_err(),
+        TableError::InvalidScale {
+            column: "x".to_string(),
+            scale: too_fine
+        }
+    );
+    assert_eq!(Decimal::parse("1", too_fine), None);
+    assert_eq!(Decimal::new(1, 0).rescale(40), None);
+    assert!(std::panic::catch_unwind(|| Decimal::new(1, 39)).is_err());
+    let max = Decimal::new(-1, Decimal::MAX_SCALE);
+    assert_eq!(max.to_string(), format!("-0.{:0>30}", 1));
+    assert_eq!(max.rescale(0), Some(Decimal::new(0, 0)));
+}
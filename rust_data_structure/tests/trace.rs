@@ -0,0 +1,52 @@
+use std::time::Duration;
+
+use print_data_structs::trace::{diff, entries, items, Trace};
+
+#[test]
+fn records_only_while_recording() {
+    let mut trace = Trace::new("vec");
+    trace.record(&["add", "1"], 0, 1, Duration::from_micros(5));
+    assert!(trace.events().is_empty());
+
+    trace.start();
+    trace.record(&["add", "2"], 0, 1, Duration::from_micros(5));
+    trace.stop();
+    trace.record(&["add", "3"], 1, 2, Duration::from_micros(5));
+
+    assert_eq!(trace.events().len(), 1);
+    assert_eq!(trace.events()[0].command(), vec!["add", "2"]);
+}
+
+#[test]
+fn text_format_round_trips() {
+    let mut trace = Trace::new("hashmap");
+    trace.start();
+    trace.record(&["add", "a", "1"], 0, 1, Duration::from_micros(12));
+    trace.record(&["show"], 1, 1, Duration::from_micros(3));
+
+    let parsed = Trace::from_text(&trace.to_text()).unwrap();
+    assert_eq!(parsed.editor(), "hashmap");
+    assert_eq!(parsed.events(), trace.events());
+}
+
+#[test]
+fn rejects_malformed_lines() {
+    assert!(Trace::from_text("add\t1\t0\t1\t5\n").is_err());
+    let err = Trace::from_text("# trace vec\nadd\t1\tx\t1\t5\n").unwrap_err();
+    assert!(err.to_string().contains("line 2"));
+}
+
+#[test]
+fn diff_counts_duplicates_and_ignores_order() {
+    let before = items([1, 2, 2, 3]);
+    let after = items([3, 2, 4, 1]);
+    assert_eq!(diff(&before, &after), vec!["- 2", "+ 4"]);
+    assert!(diff(&before, &items([3, 2, 1, 2])).is_empty());
+}
+
+#[test]
+fn diff_shows_changed_map_values() {
+    let before = entries([("a", 1)]);
+    let after = entries([("a", 2)]);
+    assert_eq!(diff(&before, &after), vec!["- a=1", "+ a=2"]);
+}
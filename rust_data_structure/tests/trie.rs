@@ -0,0 +1,85 @@
+use print_data_structs::trie::TrieMap;
+
+fn columns() -> TrieMap<i32> {
+    let mut trie = TrieMap::new();
+    for (key, uses) in [
+        ("user_id", 40),
+        ("user_name", 12),
+        ("user", 3),
+        ("usage", 25),
+        ("order_id", 30),
+    ] {
+        trie.insert(key, uses);
+    }
+    trie
+}
+
+fn keys(entries: Vec<(String, &i32)>) -> Vec<String> {
+    entries.into_iter().map(|(k, _)| k).collect()
+}
+
+#[test]
+fn inserts_and_looks_up_exact_keys() {
+    let mut trie = columns();
+    assert_eq!(trie.len(), 5);
+    assert_eq!(trie.get("user"), Some(&3));
+    assert_eq!(trie.get("use"), None);
+    assert_eq!(trie.insert("user", 4), Some(3));
+    assert_eq!(trie.len(), 5);
+    assert!(!trie.contains_key("users"));
+}
+
+#[test]
+fn enumerates_prefixes_in_key_order() {
+    let trie = columns();
+    assert_eq!(
+        keys(trie.with_prefix("us")),
+        vec!["usage", "user", "user_id", "user_name"]
+    );
+    assert_eq!(
+        keys(trie.with_prefix("user_")),
+        vec!["user_id", "user_name"]
+    );
+    assert!(trie.with_prefix("x").is_empty());
+    assert_eq!(trie.entries().len(), 5);
+}
+
+#[test]
+fn removes_and_prunes() {
+    let mut trie = columns();
+    assert_eq!(trie.remove("user"), Some(3));
+    assert_eq!(trie.remove("user"), None);
+    assert_eq!(trie.remove("us"), None);
+    assert_eq!(keys(trie.with_prefix("user")), vec!["user_id", "user_name"]);
+    trie.remove("user_id");
+    trie.remove("user_name");
+    assert!(trie.with_prefix("user").is_empty());
+    assert_eq!(trie.len(), 2);
+}
+
+#[test]
+fn finds_longest_prefix_match() {
+    let trie = columns();
+    assert_eq!(trie.longest_prefix("user_idx"), Some(("user_id", &40)));
+    assert_eq!(trie.longest_prefix("user_email"), Some(("user", &3)));
+    assert_eq!(trie.longest_prefix("orders"), None);
+
+    let mut unicode = TrieMap::new();
+    unicode.insert("café", 1);
+    assert_eq!(unicode.longest_prefix("cafés"), Some(("café", &1)));
+}
+
+#[test]
+fn ranks_completions_by_value() {
+    let mut trie = columns();
+    trie.insert("user_age", 25);
+    let ranked = trie.autocomplete("u", 3);
+    assert_eq!(
+        ranked,
+        vec![
+            (String::from("user_id"), &40),
+            (String::from("usage"), &25),
+            (String::from("user_age"), &25),
+        ]
+    );
+}
@@ -1,12 +1,59 @@
+pub mod reduce;
+
+use std::error::Error;
+use std::fmt;
+
+/// Adds two numbers. Overflow panics in debug builds; use [`checked_add`]
+/// when the inputs are not trusted.
 pub fn add(a: i32, b: i32) -> i32 {
     a + b
 }
 
+/// Multiplies two numbers. Overflow panics in debug builds; use
+/// [`checked_mul`] when the inputs are not trusted.
 pub fn mul(a: i32, b: i32) -> i32 {
     a * b
 }
 
+/// Divides `a` by `b`, truncating toward zero. Panics when `b` is zero; use
+/// [`checked_div`] when the inputs are not trusted.
 pub fn div(a: i32, b: i32) -> i32 {
     a / b
 }
 
+/// Why a checked operation has no result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArithmeticError {
+    Overflow,
+    DivideByZero,
+}
+
+impl fmt::Display for ArithmeticError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArithmeticError::Overflow => f.write_str("arithmetic overflow"),
+            ArithmeticError::DivideByZero => f.write_str("division by zero"),
+        }
+    }
+}
+
+impl Error for ArithmeticError {}
+
+/// [`add`] that reports overflow instead of panicking or wrapping.
+pub fn checked_add(a: i32, b: i32) -> Result<i32, ArithmeticError> {
+    a.checked_add(b).ok_or(ArithmeticError::Overflow)
+}
+
+/// [`mul`] that reports overflow instead of panicking or wrapping.
+pub fn checked_mul(a: i32, b: i32) -> Result<i32, ArithmeticError> {
+    a.checked_mul(b).ok_or(ArithmeticError::Overflow)
+}
+
+/// [`div`] that reports division by zero and `i32::MIN / -1` instead of
+/// panicking.
+pub fn checked_div(a: i32, b: i32) -> Result<i32, ArithmeticError> {
+    if b == 0 {
+        return Err(ArithmeticError::DivideByZero);
+    }
+    a.checked_div(b).ok_or(ArithmeticError::Overflow)
+}
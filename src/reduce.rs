@@ -0,0 +1,217 @@
+//! Parallel reductions over slices.
+//!
+//! Input is cut into chunks of a fixed size, chunks are reduced on a pool
+//! of scoped threads, and the partial results are combined left to right
+//! in chunk order. Because the chunk boundaries and the combine order depend
+//! only on the chunk size, never on the thread count, every reduction gives
+//! the same answer with one thread or sixty-four, including float sums,
+//! whose rounding depends on the order of additions. Checked integer
+//! reductions go further and keep partial results wide, so they do not
+//! depend on the chunk size either.
+
+use std::num::NonZeroUsize;
+use std::thread;
+
+use crate::{checked_add, checked_mul, ArithmeticError};
+
+/// What an integer reduction does when a result does not fit in `i32`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Fail with [`ArithmeticError::Overflow`] when the exact result does
+    /// not fit. Partial results are kept wide and checked once at the end,
+    /// so an input like `[i32::MAX, 1, -1]`, which overflows only on the
+    /// way, succeeds however it is chunked.
+    #[default]
+    Checked,
+    /// Wrap around, like the `wrapping_*` methods. Since wrapping addition
+    /// and multiplication are associative, the result matches a plain
+    /// sequential fold.
+    Wrapping,
+    /// Clamp to `i32::MIN`/`i32::MAX`. Clamping is not associative, so the
+    /// result depends on the chunk size (but not on the thread count).
+    Saturating,
+}
+
+impl OverflowPolicy {
+    pub fn add(self, a: i32, b: i32) -> Result<i32, ArithmeticError> {
+        match self {
+            OverflowPolicy::Checked => checked_add(a, b),
+            OverflowPolicy::Wrapping => Ok(a.wrapping_add(b)),
+            OverflowPolicy::Saturating => Ok(a.saturating_add(b)),
+        }
+    }
+
+    pub fn mul(self, a: i32, b: i32) -> Result<i32, ArithmeticError> {
+        match self {
+            OverflowPolicy::Checked => checked_mul(a, b),
+            OverflowPolicy::Wrapping => Ok(a.wrapping_mul(b)),
+            OverflowPolicy::Saturating => Ok(a.saturating_mul(b)),
+        }
+    }
+}
+
+/// Splits work into chunks and reduces them on several threads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reducer {
+    threads: usize,
+    chunk_size: usize,
+}
+
+impl Default for Reducer {
+    fn default() -> Self {
+        Reducer::new()
+    }
+}
+
+impl Reducer {
+    pub const DEFAULT_CHUNK_SIZE: usize = 4096;
+
+    /// One thread per available core and chunks of
+    /// [`Reducer::DEFAULT_CHUNK_SIZE`].
+    pub fn new() -> Self {
+        Reducer {
+            threads: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            chunk_size: Self::DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    /// Uses at most `threads` threads (at least one).
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Reduces `chunk_size` items per task (at least one). This, not the
+    /// thread count, decides how results are grouped.
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    pub fn thread_count(&self) -> usize {
+        self.threads
+    }
+
+    /// Reduces each chunk with `map` and folds the partial results with
+    /// `combine` in chunk order. Returns `None` for empty input.
+    pub fn map_reduce<T, R, M, C>(&self, items: &[T], map: M, combine: C) -> Option<R>
+    where
+        T: Sync,
+        R: Send,
+        M: Fn(&[T]) -> R + Sync,
+        C: Fn(R, R) -> R,
+    {
+        let chunks: Vec<&[T]> = items.chunks(self.chunk_size).collect();
+        let threads = self.threads.min(chunks.len());
+        let mut partials: Vec<Option<R>> = Vec::with_capacity(chunks.len());
+        if threads <= 1 {
+            partials.extend(chunks.iter().map(|chunk| Some(map(chunk))));
+        } else {
+            partials.resize_with(chunks.len(), || None);
+            thread::scope(|scope| {
+                let workers: Vec<_> = (0..threads)
+                    .map(|t| {
+                        let (chunks, map) = (&chunks, &map);
+                        // Thread t takes chunks t, t + threads, t + 2 * threads...
+                        scope.spawn(move || {
+                            (t..chunks.len())
+                                .step_by(threads)
+                                .map(|i| (i, map(chunks[i])))
+                                .collect::<Vec<_>>()
+                        })
+                    })
+                    .collect();
+                for worker in workers {
+                    for (i, partial) in worker.join().expect("reduction worker panicked") {
+                        partials[i] = Some(partial);
+                    }
+                }
+            });
+        }
+        partials
+            .into_iter()
+            .map(|p| p.expect("every chunk reduced"))
+            .reduce(combine)
+    }
+
+    /// Sum of `values` under `policy`; 0 for empty input.
+    pub fn sum(&self, values: &[i32], policy: OverflowPolicy) -> Result<i32, ArithmeticError> {
+        if policy != OverflowPolicy::Checked {
+            return self.fold(values, 0, |a, b| policy.add(a, b));
+        }
+        // An i128 holds the sum of any slice of i32s exactly.
+        let sum = self
+            .map_reduce(
+                values,
+                |chunk| chunk.iter().map(|&v| i128::from(v)).sum::<i128>(),
+                |a, b| a + b,
+            )
+            .unwrap_or(0);
+        i32::try_from(sum).map_err(|_| ArithmeticError::Overflow)
+    }
+
+    /// Product of `values` under `policy`; 1 for empty input.
+    pub fn product(&self, values: &[i32], policy: OverflowPolicy) -> Result<i32, ArithmeticError> {
+        if policy != OverflowPolicy::Checked {
+            return self.fold(values, 1, |a, b| policy.mul(a, b));
+        }
+        let product = |chunk: &[i32]| {
+            chunk.iter().fold(Product::Fits(1), |acc, &v| {
+                acc.times(Product::Fits(v.into()))
+            })
+        };
+        match self.map_reduce(values, product, Product::times) {
+            None => Ok(1),
+            Some(Product::Fits(v)) => i32::try_from(v).map_err(|_| ArithmeticError::Overflow),
+            Some(Product::TooLarge) => Err(ArithmeticError::Overflow),
+        }
+    }
+
+    /// Sum of `values`, reproducible for a given chunk size whatever the
+    /// thread count.
+    pub fn sum_f64(&self, values: &[f64]) -> f64 {
+        self.map_reduce(values, |chunk| chunk.iter().sum::<f64>(), |a, b| a + b)
+            .unwrap_or(0.0)
+    }
+
+    fn fold(
+        &self,
+        values: &[i32],
+        identity: i32,
+        op: impl Fn(i32, i32) -> Result<i32, ArithmeticError> + Sync,
+    ) -> Result<i32, ArithmeticError> {
+        self.map_reduce(
+            values,
+            |chunk| chunk.iter().try_fold(identity, |acc, &v| op(acc, v)),
+            |a, b| op(a?, b?),
+        )
+        .unwrap_or(Ok(identity))
+    }
+}
+
+/// A partial product, kept in `i64` while its magnitude is at most 2^31.
+/// Multiplying by a non-zero integer never shrinks the magnitude, so once
+/// it passes 2^31 only a zero can bring the product back into `i32` range;
+/// up to 2^31 a sign flip still can (`i32::MIN * -1 * -1`).
+#[derive(Debug, Clone, Copy)]
+enum Product {
+    Fits(i64),
+    TooLarge,
+}
+
+impl Product {
+    fn times(self, other: Product) -> Product {
+        match (self, other) {
+            (Product::Fits(0), _) | (_, Product::Fits(0)) => Product::Fits(0),
+            (Product::TooLarge, _) | (_, Product::TooLarge) => Product::TooLarge,
+            (Product::Fits(a), Product::Fits(b)) => {
+                let product = a * b;
+                if product.unsigned_abs() > 1 << 31 {
+                    Product::TooLarge
+                } else {
+                    Product::Fits(product)
+                }
+            }
+        }
+    }
+}
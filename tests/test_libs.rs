@@ -1 +1,25 @@
+use trsut::{add, checked_add, checked_div, checked_mul, div, mul, ArithmeticError};
 
+#[test]
+fn basic_operations() {
+    assert_eq!(add(10, 5), 15);
+    assert_eq!(mul(10, 5), 50);
+    assert_eq!(div(10, 5), 2);
+    assert_eq!(div(-7, 2), -3);
+}
+
+#[test]
+fn checked_operations_agree_with_plain_ones() {
+    assert_eq!(checked_add(10, 5), Ok(add(10, 5)));
+    assert_eq!(checked_mul(-4, 6), Ok(mul(-4, 6)));
+    assert_eq!(checked_div(-7, 2), Ok(div(-7, 2)));
+}
+
+#[test]
+fn checked_operations_report_errors() {
+    assert_eq!(checked_add(i32::MAX, 1), Err(ArithmeticError::Overflow));
+    assert_eq!(checked_mul(i32::MIN, 2), Err(ArithmeticError::Overflow));
+    assert_eq!(checked_div(1, 0), Err(ArithmeticError::DivideByZero));
+    assert_eq!(checked_div(i32::MIN, -1), Err(ArithmeticError::Overflow));
+    assert_eq!(ArithmeticError::DivideByZero.to_string(), "division by zero");
+}
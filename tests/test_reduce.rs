@@ -0,0 +1,108 @@
+use trsut::reduce::{OverflowPolicy, Reducer};
+use trsut::ArithmeticError;
+
+fn column(n: i32) -> Vec<i32> {
+    (0..n).map(|i| (i * 7919) % 1000 - 500).collect()
+}
+
+#[test]
+fn sums_and_products_match_sequential_results() {
+    let values = column(100_000);
+    let expected: i32 = values.iter().sum();
+    for threads in [1, 2, 3, 8] {
+        let reducer = Reducer::new().threads(threads).chunk_size(1000);
+        assert_eq!(reducer.sum(&values, OverflowPolicy::Checked), Ok(expected));
+    }
+    let small = [2, -3, 4, 5];
+    let reducer = Reducer::new().threads(4).chunk_size(1);
+    assert_eq!(reducer.product(&small, OverflowPolicy::Checked), Ok(-120));
+    assert_eq!(reducer.sum(&[], OverflowPolicy::Checked), Ok(0));
+    assert_eq!(reducer.product(&[], OverflowPolicy::Checked), Ok(1));
+}
+
+#[test]
+fn overflow_policies() {
+    let values = vec![i32::MAX / 2; 6];
+    let reducer = Reducer::new().threads(3).chunk_size(2);
+    assert_eq!(
+        reducer.sum(&values, OverflowPolicy::Checked),
+        Err(ArithmeticError::Overflow)
+    );
+    let wrapped = values.iter().fold(0i32, |a, &b| a.wrapping_add(b));
+    assert_eq!(reducer.sum(&values, OverflowPolicy::Wrapping), Ok(wrapped));
+    assert_eq!(
+        reducer.sum(&values, OverflowPolicy::Saturating),
+        Ok(i32::MAX)
+    );
+    assert_eq!(
+        reducer.product(&[-65536, 65536], OverflowPolicy::Saturating),
+        Ok(i32::MIN)
+    );
+    assert_eq!(OverflowPolicy::default(), OverflowPolicy::Checked);
+}
+
+#[test]
+fn float_sums_do_not_depend_on_thread_count() {
+    // Wildly different magnitudes make the result sensitive to order.
+    let values: Vec<f64> = (0..200_000)
+        .map(|i| {
+            if i % 3 == 0 {
+                1e16
+            } else {
+                -1e16 + 1.2345 * i as f64
+            }
+        })
+        .collect();
+    let reference = Reducer::new().threads(1).chunk_size(512).sum_f64(&values);
+    for threads in [2, 5, 16, 64] {
+        let sum = Reducer::new()
+            .threads(threads)
+            .chunk_size(512)
+            .sum_f64(&values);
+        assert_eq!(sum.to_bits(), reference.to_bits(), "{} threads", threads);
+    }
+    assert_eq!(Reducer::new().sum_f64(&[]), 0.0);
+}
+
+#[test]
+fn map_reduce_combines_in_chunk_order() {
+    let words: Vec<String> = (0..50).map(|i| i.to_string()).collect();
+    let joined = Reducer::new()
+        .threads(7)
+        .chunk_size(3)
+        .map_reduce(&words, |chunk| chunk.concat(), |a, b| a + &b)
+        .unwrap();
+    assert_eq!(joined, words.concat());
+    assert_eq!(
+        Reducer::new().map_reduce(&[] as &[u8], |c| c.len(), |a, b| a + b),
+        None
+    );
+    assert_eq!(Reducer::new().threads(0).thread_count(), 1);
+}
+
+#[test]
+fn checked_results_do_not_depend_on_chunk_size() {
+    let recovers = [-1, 0, i32::MAX, 1];
+    let overflows = [i32::MAX, 1, 0, 0];
+    let products = [65536, 65536, 3, 0];
+    for chunk_size in 1..=4 {
+        let reducer = Reducer::new().threads(2).chunk_size(chunk_size);
+        let checked = OverflowPolicy::Checked;
+        assert_eq!(reducer.sum(&recovers, checked), Ok(i32::MAX));
+        assert_eq!(
+            reducer.sum(&overflows, checked),
+            Err(ArithmeticError::Overflow)
+        );
+        assert_eq!(reducer.product(&products, checked), Ok(0));
+        assert_eq!(
+            reducer.product(&products[..3], checked),
+            Err(ArithmeticError::Overflow)
+        );
+        assert_eq!(reducer.product(&[-65536, 32768], checked), Ok(i32::MIN));
+        assert_eq!(reducer.product(&[i32::MIN, -1, -1], checked), Ok(i32::MIN));
+        assert_eq!(
+            reducer.product(&[i32::MIN, -1], checked),
+            Err(ArithmeticError::Overflow)
+        );
+    }
+}